@@ -0,0 +1,66 @@
+use crate::bip32::ChildNumber::Hardened;
+use crate::bip32::ExtKey;
+use crate::btc::KeyOrigin;
+use k256::{PublicKey, SecretKey};
+use std::fmt;
+
+/// Single-sig accounts by name, purpose and coin type.
+pub const STANDARD: [(&str, u32, u32); 6] = [
+	("BTC P2PKH", 44, 0),
+	("BTC P2SH-P2WPKH", 49, 0),
+	("BTC P2WPKH", 84, 0),
+	("BTC P2TR", 86, 0),
+	("ETH", 44, 60),
+	("AVAX X/P", 44, 9000),
+];
+
+pub struct Account {
+	pub name: &'static str,
+	pub origin: KeyOrigin,
+	pub xpub: ExtKey<PublicKey>,
+}
+
+impl fmt::Display for Account {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", self.origin, self.xpub)
+	}
+}
+
+/// Neutered account keys `m/purpose'/coin'/account'` of every standard account.
+pub fn standard_accounts(root: &ExtKey<SecretKey>, account: u32) -> Option<Vec<Account>> {
+	STANDARD
+		.iter()
+		.map(|&(name, purpose, coin)| {
+			let path = [Hardened(purpose), Hardened(coin), Hardened(account)];
+			Some(Account {
+				name,
+				origin: KeyOrigin::new(root, &path),
+				xpub: root.derive_path(&path)?.neuter(),
+			})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn standard() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let accounts = standard_accounts(&root, 0).unwrap();
+		let data = [
+			"[73c5da0a/44'/0'/0']xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj",
+			"[73c5da0a/49'/0'/0']xpub6C6nQwHaWbSrzs5tZ1q7m5R9cPK9eYpNMFesiXsYrgc1P8bvLLAet9JfHjYXKjToD8cBRswJXXbbFpXgwsswVPAZzKMa1jUp2kVkGVUaJa7",
+			"[73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V",
+			"[73c5da0a/86'/0'/0']xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ",
+			"[73c5da0a/44'/60'/0']xpub6DCoCpSuQZB2jawqnGMEPS63ePKWkwWPH4TU45Q7LPXWuNd8TMtVxRrgjtEshuqpK3mdhaWHPFsBngh5GFZaM6si3yZdUsT8ddYM3PwnATt",
+			"[73c5da0a/44'/9000'/0']xpub6BqVigHfL2TNNs8HyeEHn4JFFyTw1vL8kC5ZhzBhrhDbQ3FhgakpivT97Cd7oVCJiAwiqWu313vKMZMwCghXgSVDnYR3FrYzTz24yY3nFHR",
+		];
+		assert_eq!(accounts.len(), data.len());
+		for (account, exp) in accounts.iter().zip(data) {
+			assert_eq!(account.to_string(), exp);
+		}
+	}
+}
@@ -0,0 +1,193 @@
+use crate::bech32::{self, to_base32, Variant};
+use crate::bip32::ChildNumber::{self, Hardened};
+use crate::memory::zeroize;
+use crate::slip10::Ed25519Key;
+use crate::util::Secret;
+use curve25519_dalek::scalar::clamp_integer;
+use curve25519_dalek::MontgomeryPoint;
+
+/// "AGE" in ASCII. Identity `index` is at `m/4278085'/index'`.
+pub const PURPOSE: u32 = 0x414745;
+
+pub fn path(index: u32) -> [ChildNumber; 2] {
+	[Hardened(PURPOSE), Hardened(index)]
+}
+
+/// An age X25519 identity, the clamped SLIP-0010 ed25519 secret at `path`.
+pub struct AgeIdentity {
+	secret: [u8; 32],
+}
+
+impl AgeIdentity {
+	pub fn new(root: &Ed25519Key, index: u32) -> Option<Self> {
+		let key = root.derive_path(&path(index))?;
+		Some(Self {
+			secret: clamp_integer(*key.secret_key()),
+		})
+	}
+
+	/// `AGE-SECRET-KEY-1...`, for an identity file.
+	pub fn identity(&self) -> Secret<String> {
+		let mut data = to_base32(self.secret);
+		let identity = bech32::encode_upper("age-secret-key-", &data, Variant::Bech32);
+		zeroize(&mut data);
+		Secret::new(identity)
+	}
+
+	/// `age1...`, to encrypt to with `age -r`.
+	pub fn recipient(&self) -> String {
+		let point = MontgomeryPoint::mul_base_clamped(self.secret);
+		bech32::encode("age", &to_base32(point.as_bytes()), Variant::Bech32)
+	}
+}
+
+impl Drop for AgeIdentity {
+	fn drop(&mut self) {
+		zeroize(&mut self.secret);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+	use crate::hash::hkdf_sha256;
+	use chacha20poly1305::aead::Aead;
+	use chacha20poly1305::{ChaCha20Poly1305, KeyInit};
+	use hmac::{Hmac, Mac};
+	use sha2::Sha256;
+
+	fn derived(index: u32) -> AgeIdentity {
+		let seed = Mnemonic::from_entropy([0; 16]).seed("");
+		AgeIdentity::new(&Ed25519Key::from_seed(&seed), index).unwrap()
+	}
+
+	fn unbase64(s: &str) -> Vec<u8> {
+		const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+		let mut out = vec![];
+		let (mut acc, mut bits) = (0u32, 0);
+		for c in s.bytes() {
+			acc = acc << 6 | ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+			bits += 6;
+			if bits >= 8 {
+				bits -= 8;
+				out.push((acc >> bits) as u8);
+			}
+		}
+		out
+	}
+
+	/// Just enough of age v1 to open a one-chunk file for an X25519
+	/// recipient. Other stanzas are skipped, the header MAC is checked.
+	fn decrypt(identity: &AgeIdentity, file: &[u8]) -> Option<Vec<u8>> {
+		let mac_start = file.windows(4).position(|w| w == b"\n---")? + 1;
+		let mac_end = mac_start + file[mac_start..].iter().position(|&b| b == b'\n')?;
+		let header = std::str::from_utf8(&file[..mac_start]).ok()?;
+		let mac = unbase64(std::str::from_utf8(&file[mac_start + 4..mac_end]).ok()?);
+		let payload = &file[mac_end + 1..];
+
+		let mut lines = header.lines();
+		if lines.next()? != "age-encryption.org/v1" {
+			return None;
+		}
+		let recipient = MontgomeryPoint::mul_base_clamped(identity.secret);
+		let mut file_key = None;
+		while let Some(line) = lines.next() {
+			let args: Vec<_> = line.strip_prefix("-> ")?.split(' ').collect();
+			let mut body = String::new();
+			loop {
+				let line = lines.next()?;
+				body += line;
+				if line.len() < 64 {
+					break;
+				}
+			}
+			if args[0] != "X25519" {
+				continue;
+			}
+			let share: [u8; 32] = unbase64(args[1]).try_into().ok()?;
+			let shared = MontgomeryPoint(share).mul_clamped(identity.secret);
+			let salt = [share, recipient.to_bytes()].concat();
+			let key = hkdf_sha256(
+				shared.as_bytes(),
+				&salt,
+				b"age-encryption.org/v1/X25519",
+				32,
+			)?;
+			let cipher = ChaCha20Poly1305::new_from_slice(&key).ok()?;
+			if let Ok(key) = cipher.decrypt(&[0; 12].into(), &unbase64(&body)[..]) {
+				file_key = Some(key);
+			}
+		}
+		let file_key = file_key?;
+
+		let mac_key = hkdf_sha256(&file_key, b"", b"header", 32)?;
+		let mut hmac = <Hmac<Sha256> as Mac>::new_from_slice(&mac_key).ok()?;
+		hmac.update(&file[..mac_start + 3]);
+		hmac.verify_slice(&mac).ok()?;
+
+		let (nonce, chunk) = payload.split_at(16);
+		let key = hkdf_sha256(&file_key, nonce, b"payload", 32)?;
+		let mut nonce = [0; 12];
+		nonce[11] = 1;
+		ChaCha20Poly1305::new_from_slice(&key)
+			.ok()?
+			.decrypt(&nonce.into(), chunk)
+			.ok()
+	}
+
+	#[test]
+	fn identity() {
+		// recipients checked against the age crate
+		let data = [
+			(
+				0,
+				"AGE-SECRET-KEY-1RPGYUKLVZK52ZNVK9WP6RQCHKH7Z9J6YV6WMC02G4MY49P55J34QTF7D4Z",
+				"age15lnr3zqsym3zs379vugwtsyv7zdq9eswm4a4hpvup3xqug5gmvmqsx70ff",
+			),
+			(
+				1,
+				"AGE-SECRET-KEY-1PQH8W32027S3AZTA7P56JUTJLEMDMRUGNCRL23E5AG3ZES2ZHAWSWWH5P3",
+				"age1prwed6wdjnkpx7yw9seasff3fp6v6t88u92474q8jeaaf9szvqssy5hew6",
+			),
+		];
+		for (index, secret, recipient) in data {
+			let identity = derived(index);
+			assert_eq!(identity.identity().reveal(), secret);
+			assert_eq!(identity.recipient(), recipient);
+			let (hrp, data, variant) = bech32::decode_raw(secret).unwrap();
+			assert_eq!(
+				(hrp.as_str(), variant),
+				("age-secret-key-", Variant::Bech32)
+			);
+			assert_eq!(bech32::from_base32(&data).unwrap(), identity.secret);
+		}
+	}
+
+	// Encrypted by the age crate to the recipient of identity 0
+	#[test]
+	fn decrypt_fixture() {
+		let file = hex::decode(
+			[
+				"6167652d656e6372797074696f6e2e6f72672f76310a2d3e2058323535313920397074654b436131",
+				"4b4c785468306e34386c2b5353324e39526a31495334713665355552753838366e57730a4a4c374c",
+				"3741684d4763636d52742f35347830447a30757a6a70332b6d7a3473596f47506b572b4f72486f0a",
+				"2d3e20426659682f535e312d677265617365202d733041495f4020426622205e2b6a37480a576832",
+				"63434b6b69644b615751556e5553697365472b4e6e522f652f0a2d2d2d206a546e336b7952743851",
+				"4534505a66794538516f5547543873674c327261744f7034306e6747424c4768630a938941d03bfe",
+				"46dd27d550dc5ac3c776bca13955d709783f1bc36c59e86d243bbd1689675345481aba0ab28e32ae",
+				"720b1f2cd6abf72cbcde4da43b206f",
+			]
+			.concat(),
+		)
+		.unwrap();
+		assert_eq!(
+			decrypt(&derived(0), &file).unwrap(),
+			b"recoverable from the mnemonic"
+		);
+		assert!(decrypt(&derived(1), &file).is_none());
+		let mut modified = file.clone();
+		modified[file.len() - 1] ^= 1;
+		assert!(decrypt(&derived(0), &modified).is_none());
+	}
+}
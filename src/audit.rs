@@ -0,0 +1,176 @@
+use crate::bip32::{format_path, ChildNumber};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Master key fingerprint, the only way events refer to a wallet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fingerprint(pub [u8; 4]);
+
+/// Something done during a session. Fields are typed so no event can carry
+/// words, seeds or private keys: there is no free-form text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+	/// A menu option was chosen.
+	Command(u8),
+	/// A wallet was entered or generated.
+	Wallet(Fingerprint),
+	/// Keys were derived at `path` below a wallet.
+	Derive {
+		wallet: Fingerprint,
+		path: Vec<ChildNumber>,
+	},
+	/// A file was written.
+	Export {
+		wallet: Option<Fingerprint>,
+		file: PathBuf,
+	},
+}
+
+impl Event {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Command(_) => "command",
+			Self::Wallet(_) => "wallet",
+			Self::Derive { .. } => "derive",
+			Self::Export { .. } => "export",
+		}
+	}
+
+	fn fields(&self) -> Vec<(&'static str, String)> {
+		let fingerprint = |f: &Fingerprint| hex::encode(f.0);
+		match self {
+			Self::Command(option) => vec![("option", option.to_string())],
+			Self::Wallet(wallet) => vec![("wallet", fingerprint(wallet))],
+			Self::Derive { wallet, path } => {
+				vec![("wallet", fingerprint(wallet)), ("path", format_path(path))]
+			}
+			Self::Export { wallet, file } => wallet
+				.iter()
+				.map(|wallet| ("wallet", fingerprint(wallet)))
+				.chain([("file", format!("{:?}", file.display().to_string()))])
+				.collect(),
+		}
+	}
+
+	/// `time=<unix seconds> event=<name>` followed by the event's fields, one
+	/// line per event.
+	pub fn to_line(&self, time: u64) -> String {
+		let mut line = format!("time={time} event={}", self.name());
+		for (key, value) in self.fields() {
+			line += &format!(" {key}={value}");
+		}
+		line
+	}
+}
+
+/// Append-only log of events, readable by the owner only.
+pub struct AuditLog(File);
+
+impl AuditLog {
+	pub fn open(path: &str) -> io::Result<Self> {
+		let mut options = OpenOptions::new();
+		options.append(true).create(true);
+		#[cfg(unix)]
+		std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+		Ok(Self(options.open(path)?))
+	}
+
+	pub fn record(&mut self, event: &Event) -> io::Result<()> {
+		let time = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |d| d.as_secs());
+		writeln!(self.0, "{}", event.to_line(time))?;
+		self.0.sync_data()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::ChildNumber::Hardened;
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn to_line() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let root = mnemonic.root_key("").unwrap();
+		let wallet = Fingerprint(root.fingerprint());
+		let path = vec![Hardened(44), Hardened(60), Hardened(0)];
+		let data = [
+			(
+				Event::Command(3),
+				&["option"][..],
+				"time=7 event=command option=3",
+			),
+			(
+				Event::Wallet(wallet),
+				&["wallet"],
+				"time=7 event=wallet wallet=73c5da0a",
+			),
+			(
+				Event::Derive { wallet, path },
+				&["wallet", "path"],
+				"time=7 event=derive wallet=73c5da0a path=m/44'/60'/0'",
+			),
+			(
+				Event::Export {
+					wallet: Some(wallet),
+					file: "out dir/a\"b.csv".into(),
+				},
+				&["wallet", "file"],
+				"time=7 event=export wallet=73c5da0a file=\"out dir/a\\\"b.csv\"",
+			),
+			(
+				Event::Export {
+					wallet: None,
+					file: "x.json".into(),
+				},
+				&["file"],
+				"time=7 event=export file=\"x.json\"",
+			),
+		];
+		let secrets = [
+			mnemonic.to_string(),
+			"abandon".to_string(),
+			mnemonic.seed("").to_hex(),
+			root.xprv().reveal().clone(),
+			hex::encode(root.key().to_bytes()),
+		];
+		for (event, fields, exp) in data {
+			let line = event.to_line(7);
+			assert_eq!(line, exp);
+			let keys: Vec<_> = event.fields().into_iter().map(|(key, _)| key).collect();
+			assert_eq!(keys, fields);
+			for secret in &secrets {
+				assert!(!line.contains(secret.as_str()), "{line}");
+			}
+		}
+	}
+
+	#[test]
+	fn record() {
+		let path = std::env::temp_dir().join(format!("keymaker-audit-{}", std::process::id()));
+		let path = path.to_str().unwrap();
+		for option in [1, 2] {
+			AuditLog::open(path)
+				.unwrap()
+				.record(&Event::Command(option))
+				.unwrap();
+		}
+		let log = std::fs::read_to_string(path).unwrap();
+		let events: Vec<_> = log
+			.lines()
+			.map(|l| l.split_once(" event=").unwrap().1)
+			.collect();
+		assert_eq!(events, ["command option=1", "command option=2"]);
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(path).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+		std::fs::remove_file(path).unwrap();
+	}
+}
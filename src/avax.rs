@@ -0,0 +1,92 @@
+use crate::bech32::{self, Variant};
+use crate::bip32::ChildNumber::{self, Hardened, Normal};
+use crate::hash::hash160;
+use crate::slip44;
+use std::fmt;
+
+/// Avalanche chains with bech32 addresses; the C-chain uses Ethereum addresses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chain {
+	X,
+	P,
+}
+
+impl Chain {
+	pub const ALL: [Chain; 2] = [Self::X, Self::P];
+
+	pub fn address(&self, pubkey: &[u8; 33]) -> String {
+		let data = bech32::to_base32(hash160(pubkey));
+		format!("{self}-{}", bech32::encode("avax", &data, Variant::Bech32))
+	}
+}
+
+impl fmt::Display for Chain {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::X => "X",
+			Self::P => "P",
+		})
+	}
+}
+
+/// Shared X/P-chain path, `m/44'/9000'/0'/0/i`.
+pub fn path(i: u32) -> Vec<ChildNumber> {
+	vec![
+		Hardened(44),
+		Hardened(slip44::AVAX),
+		Hardened(0),
+		Normal(0),
+		Normal(i),
+	]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::Key;
+	use crate::bip39::Mnemonic;
+
+	// Expected addresses are from a separate BIP32 and BIP173 reference
+	// implementation, not from this crate.
+
+	#[test]
+	fn address() {
+		let mut pubkey = [0; 33];
+		hex::decode_to_slice(
+			"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+			&mut pubkey,
+		)
+		.unwrap();
+		assert_eq!(
+			Chain::X.address(&pubkey),
+			"X-avax1w508d6qejxtdg4y5r3zarvary0c5xw7k0l6nk9"
+		);
+		assert_eq!(
+			Chain::P.address(&pubkey),
+			"P-avax1w508d6qejxtdg4y5r3zarvary0c5xw7k0l6nk9"
+		);
+	}
+
+	#[test]
+	fn derive() {
+		let data = [
+			([0; 16], 0, "X-avax1p9575chzhvcwvmvzaqh7yeld76r3af0ha56phl"),
+			([0; 16], 1, "X-avax1saceyycp6klllavjmt5xd9dxzk7mffzp6fzwtu"),
+			(
+				[0x7f; 16],
+				0,
+				"X-avax1hdr9wmz4r3la4t6qxfysnfwvzg97f9v6sm8ttl",
+			),
+			(
+				[0x7f; 16],
+				2,
+				"X-avax1q6zph8yv26dajuxufp7qwt0q05d6f92ghatske",
+			),
+		];
+		for (entropy, i, exp) in data {
+			let root = Mnemonic::from_entropy(entropy).root_key("").unwrap();
+			let pubkey = root.derive_path(&path(i)).unwrap().neuter();
+			assert_eq!(Chain::X.address(&pubkey.key().serialize()), exp);
+		}
+	}
+}
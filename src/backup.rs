@@ -0,0 +1,238 @@
+use crate::bip39::{Language, Mnemonic};
+use crate::hash::sha256;
+use crate::memory::zeroize;
+use crate::session::cipher;
+use chacha20poly1305::aead::{Aead, Payload};
+use std::fmt;
+
+const MAGIC: &[u8; 8] = b"KMBACKP\n";
+/// Format of new backups. Older versions stay readable, newer ones are
+/// refused instead of guessed at.
+pub const VERSION: u8 = 1;
+/// scrypt cost of new backups, stored in the header.
+pub const LOG_N: u8 = 17;
+const HEADER_LEN: usize = MAGIC.len() + 2 + 16 + 12;
+
+#[derive(PartialEq)]
+pub enum BackupError {
+	InvalidFormat,
+	UnsupportedVersion(u8),
+	/// Wrong passphrase, or the file was modified.
+	Decryption,
+	/// Decrypted entropy does not match its BIP39 checksum.
+	Checksum,
+	/// Only mnemonics in an official word list can be backed up.
+	CustomWordlist,
+}
+
+impl fmt::Debug for BackupError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidFormat => f.write_str("not a keymaker backup file"),
+			Self::UnsupportedVersion(v) => write!(f, "unsupported backup version {v}"),
+			Self::Decryption => f.write_str("wrong passphrase or modified file"),
+			Self::Checksum => f.write_str("mnemonic checksum mismatch"),
+			Self::CustomWordlist => f.write_str("mnemonic uses a custom word list"),
+		}
+	}
+}
+
+/// `magic | version | log_n | salt | nonce | ciphertext`, as session files.
+/// The plaintext is `language | entropy | checksum`, the checksum being the
+/// first byte of SHA256 of the entropy.
+pub fn encrypt(mnemonic: &Mnemonic, passphrase: &str) -> Result<Vec<u8>, BackupError> {
+	let mut random = [0; 28];
+	getrandom::getrandom(&mut random).expect("Cannot read random bytes");
+	let (salt, nonce) = random.split_at(16);
+	encrypt_with(
+		mnemonic,
+		passphrase,
+		LOG_N,
+		salt.try_into().unwrap(),
+		nonce.try_into().unwrap(),
+	)
+}
+
+fn encrypt_with(
+	mnemonic: &Mnemonic,
+	passphrase: &str,
+	log_n: u8,
+	salt: [u8; 16],
+	nonce: [u8; 12],
+) -> Result<Vec<u8>, BackupError> {
+	let language = mnemonic.language().ok_or(BackupError::CustomWordlist)?;
+	let entropy = mnemonic.entropy();
+	let mut plaintext = vec![Language::ALL.iter().position(|&l| l == language).unwrap() as u8];
+	plaintext.extend(entropy);
+	plaintext.push(sha256(entropy)[0]);
+
+	let mut out = MAGIC.to_vec();
+	out.extend([VERSION, log_n]);
+	out.extend(salt);
+	out.extend(nonce);
+	let ciphertext = cipher(passphrase, log_n, &salt)
+		.expect("Invalid scrypt parameters")
+		.encrypt(
+			&nonce.into(),
+			Payload {
+				msg: &plaintext,
+				aad: &out,
+			},
+		)
+		.unwrap();
+	zeroize(&mut plaintext);
+	out.extend(ciphertext);
+	Ok(out)
+}
+
+/// The backed up mnemonic, in its original word list.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Mnemonic, BackupError> {
+	if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+		return Err(BackupError::InvalidFormat);
+	}
+	let (header, ciphertext) = data.split_at(HEADER_LEN);
+	let version = header[MAGIC.len()];
+	if version != VERSION {
+		return Err(BackupError::UnsupportedVersion(version));
+	}
+	let log_n = header[MAGIC.len() + 1];
+	let salt = &header[MAGIC.len() + 2..MAGIC.len() + 18];
+	let nonce = &header[MAGIC.len() + 18..];
+	let mut plaintext = cipher(passphrase, log_n, salt)
+		.ok_or(BackupError::InvalidFormat)?
+		.decrypt(
+			nonce.into(),
+			Payload {
+				msg: ciphertext,
+				aad: header,
+			},
+		)
+		.map_err(|_| BackupError::Decryption)?;
+	let mnemonic = parse(&plaintext);
+	zeroize(&mut plaintext);
+	mnemonic
+}
+
+fn parse(plaintext: &[u8]) -> Result<Mnemonic, BackupError> {
+	let [language, entropy @ .., checksum] = plaintext else {
+		return Err(BackupError::InvalidFormat);
+	};
+	let language = Language::ALL
+		.get(*language as usize)
+		.ok_or(BackupError::InvalidFormat)?;
+	if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+		return Err(BackupError::InvalidFormat);
+	}
+	if sha256(entropy)[0] != *checksum {
+		return Err(BackupError::Checksum);
+	}
+	Ok(Mnemonic::from_entropy(entropy).translate(*language))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn mnemonic() -> Mnemonic {
+		Mnemonic::from_phrase(
+			"legal winner thank year wave sausage worth useful legal winner thank yellow",
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn round_trip() {
+		for mnemonic in [
+			mnemonic(),
+			mnemonic().translate(Language::Spanish),
+			Mnemonic::from_entropy([0x9e; 32]),
+		] {
+			let data = encrypt_with(&mnemonic, "pw", 4, [1; 16], [2; 12]).unwrap();
+			assert_eq!(data[..10], *b"KMBACKP\n\x01\x04");
+			assert_eq!(
+				data.len(),
+				HEADER_LEN + 1 + mnemonic.entropy().len() + 1 + 16
+			);
+			let restored = decrypt(&data, "pw").unwrap();
+			assert_eq!(restored.to_string(), mnemonic.to_string());
+			assert_eq!(decrypt(&data, "other").err(), Some(BackupError::Decryption));
+			assert_eq!(decrypt(&data, "").err(), Some(BackupError::Decryption));
+		}
+		// fresh salt and nonce every time
+		let a = encrypt(&mnemonic(), "pw").unwrap();
+		let b = encrypt(&mnemonic(), "pw").unwrap();
+		assert_eq!(a[..10], *b"KMBACKP\n\x01\x11");
+		assert_ne!(a[10..], b[10..]);
+	}
+
+	#[test]
+	fn tamper() {
+		let data = encrypt_with(&mnemonic(), "pw", 4, [1; 16], [2; 12]).unwrap();
+		for i in MAGIC.len() + 1..data.len() {
+			let mut data = data.clone();
+			data[i] ^= 1;
+			assert_eq!(
+				decrypt(&data, "pw").err(),
+				Some(BackupError::Decryption),
+				"byte {i}"
+			);
+		}
+		let mut other = data.clone();
+		other[MAGIC.len()] = 2;
+		assert_eq!(
+			decrypt(&other, "pw").err(),
+			Some(BackupError::UnsupportedVersion(2))
+		);
+		let mut other = data.clone();
+		other[MAGIC.len() + 1] = 40;
+		assert_eq!(
+			decrypt(&other, "pw").err(),
+			Some(BackupError::InvalidFormat)
+		);
+		let mut other = data.clone();
+		other[0] ^= 1;
+		assert_eq!(
+			decrypt(&other, "pw").err(),
+			Some(BackupError::InvalidFormat)
+		);
+		assert_eq!(
+			decrypt(&data[..data.len() - 1], "pw").err(),
+			Some(BackupError::Decryption)
+		);
+		assert_eq!(
+			decrypt(&data[..HEADER_LEN - 1], "pw").err(),
+			Some(BackupError::InvalidFormat)
+		);
+	}
+
+	#[test]
+	fn parse() {
+		let entropy = mnemonic().entropy().to_vec();
+		let checksum = sha256(&entropy)[0];
+		let plaintext = |language: u8, entropy: &[u8], checksum: u8| {
+			let mut out = vec![language];
+			out.extend(entropy);
+			out.push(checksum);
+			out
+		};
+		assert_eq!(
+			super::parse(&plaintext(0, &entropy, checksum))
+				.unwrap()
+				.to_string(),
+			mnemonic().to_string()
+		);
+		let data = [
+			(plaintext(0, &entropy, checksum ^ 1), BackupError::Checksum),
+			(plaintext(2, &entropy, checksum), BackupError::InvalidFormat),
+			(
+				plaintext(0, &entropy[1..], checksum),
+				BackupError::InvalidFormat,
+			),
+			(vec![0], BackupError::InvalidFormat),
+			(vec![], BackupError::InvalidFormat),
+		];
+		for (plaintext, err) in data {
+			assert_eq!(super::parse(&plaintext).err(), Some(err));
+		}
+	}
+}
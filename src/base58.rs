@@ -1,13 +1,15 @@
 use crate::util::IterExt;
 use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
 
-const ALPHABET: &'static [u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
 
 pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+	let zeros = input.as_ref().iter().take_while(|v| **v == 0).count();
 	let l = input.as_ref().len() * 138 / 100;
-	input
+	let encoded: String = input
 		.as_ref()
-		.into_iter()
+		.iter()
 		.map(|v| *v as u32)
 		.fold_mut((vec![0; l + 1], l), |(a, h), mut x| {
 			let mut j = a.len() - 1;
@@ -23,20 +25,80 @@ pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
 		.into_iter()
 		.skip_while(|v| *v == 0)
 		.map(|v| ALPHABET[v as usize] as char)
+		.collect();
+	"1".repeat(zeros) + &encoded
+}
+
+const BLOCK_SIZES: [usize; 9] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+
+/// Monero's variant: 8 byte blocks, each encoded to a fixed width.
+pub fn encode_blocks<T: AsRef<[u8]>>(input: T) -> String {
+	input
+		.as_ref()
+		.chunks(8)
+		.flat_map(|block| {
+			let mut n = block.iter().fold(0u64, |acc, &v| acc << 8 | v as u64);
+			let mut out = vec![ALPHABET[0]; BLOCK_SIZES[block.len()]];
+			for c in out.iter_mut().rev() {
+				*c = ALPHABET[(n % 58) as usize];
+				n /= 58;
+			}
+			out
+		})
+		.map(|c| c as char)
 		.collect()
 }
 
-pub fn encode_check<T: AsRef<[u8]>>(input: T) -> String {
-	let mut input = input.as_ref().to_vec();
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+	let zeros = input.bytes().take_while(|c| *c == ALPHABET[0]).count();
+	let mut out: Vec<u8> = Vec::with_capacity(input.len() * 733 / 1000 + 1);
+	for c in input.bytes() {
+		let mut carry = ALPHABET.iter().position(|v| *v == c)? as u32;
+		for v in out.iter_mut() {
+			carry += (*v as u32) * 58;
+			*v = carry as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			out.push(carry as u8);
+			carry >>= 8;
+		}
+	}
+	out.resize(out.len() + zeros, 0);
+	out.reverse();
+	Some(out)
+}
+
+fn checksum(input: &[u8]) -> [u8; 4] {
 	let mut hasher = Sha256::new();
-	hasher.update(&input);
+	hasher.update(input);
 	let mut hash = hasher.finalize_reset();
-	hasher.update(&hash);
+	hasher.update(hash);
 	hash = hasher.finalize();
-	input.extend_from_slice(&hash[..4]);
+	let mut out = [0; 4];
+	out.copy_from_slice(&hash[..4]);
+	out
+}
+
+pub fn encode_check<T: AsRef<[u8]>>(input: T) -> String {
+	let mut input = input.as_ref().to_vec();
+	input.extend_from_slice(&checksum(&input));
 	encode(input)
 }
 
+pub fn decode_check(input: &str) -> Option<Vec<u8>> {
+	let mut data = decode(input)?;
+	if data.len() < 4 {
+		return None;
+	}
+	let check = data.split_off(data.len() - 4);
+	// constant time, the input may be derived from a secret
+	if !bool::from(check.ct_eq(&checksum(&data))) {
+		return None;
+	}
+	Some(data)
+}
+
 #[cfg(test)]
 mod tests {
 	#[test]
@@ -56,10 +118,76 @@ mod tests {
 				b"abcdefghijklmnopqrstuvwxyz",
 				"3yxU3u1igY8WkgtjK92fbJQCd4BZiiT1v25f",
 			),
+			(&[0], "1"),
+			(&[0; 10], "1111111111"),
+			(&[0, 0, 57], "11z"),
 		];
 
 		for (input, output) in data {
 			assert_eq!(super::encode(input), output);
+			assert_eq!(super::decode(output).unwrap(), input);
+		}
+	}
+
+	#[test]
+	fn decode() {
+		assert_eq!(
+			hex::encode(super::decode("1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L").unwrap()),
+			"00eb15231dfceb60925886b67d065299925915aeb172c06647"
+		);
+		assert!(super::decode("0").is_none());
+		assert!(super::decode("1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9l").is_none());
+	}
+
+	#[test]
+	fn encode_blocks() {
+		let data = [
+			(b"".as_ref(), ""),
+			(&[0], "11"),
+			(&[0xff], "5Q"),
+			(&[0, 0x39], "11z"),
+			(&[0xff; 5], "VtB5VXc"),
+			(&[0xff; 8], "jpXCZedGfVQ"),
+			(&[0; 8], "11111111111"),
+			(
+				&[0x06, 0x15, 0x60, 0x13, 0x76, 0x28, 0x79, 0xf7],
+				"22222222222",
+			),
+			(b"Hello World", "D7LMXYjUbXc1fS9Z"),
+		];
+		for (input, output) in data {
+			assert_eq!(super::encode_blocks(input), output);
+		}
+	}
+
+	#[test]
+	fn check() {
+		let data = b"keymaker";
+		let encoded = super::encode_check(data);
+		assert_eq!(super::decode_check(&encoded).unwrap(), data);
+
+		let mut corrupted = super::decode(&encoded).unwrap();
+		*corrupted.last_mut().unwrap() ^= 1;
+		assert!(super::decode_check(&super::encode(corrupted)).is_none());
+		assert!(super::decode_check("1").is_none());
+	}
+
+	#[test]
+	fn check_corrupted() {
+		// BIP32 test vector 1 master xpub
+		let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+		let raw = super::decode(xpub).unwrap();
+		assert_eq!(super::decode_check(xpub).unwrap(), raw[..raw.len() - 4]);
+		for i in 0..raw.len() {
+			for bit in [0x01, 0x80] {
+				let mut corrupted = raw.clone();
+				corrupted[i] ^= bit;
+				assert!(
+					super::decode_check(&super::encode(&corrupted)).is_none(),
+					"byte {i}"
+				);
+			}
 		}
+		assert!(super::decode_check(&super::encode([1, 2, 3])).is_none());
 	}
 }
@@ -0,0 +1,348 @@
+use crate::util::{BitsN, IterExt};
+use std::fmt;
+use std::iter::once;
+
+const CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+	Bech32,
+	Bech32m,
+}
+
+impl Variant {
+	fn constant(&self) -> u32 {
+		match self {
+			Self::Bech32 => 1,
+			Self::Bech32m => 0x2bc830a3,
+		}
+	}
+}
+
+#[derive(PartialEq)]
+pub enum DecodeError {
+	InvalidLength,
+	InvalidChar,
+	MixedCase,
+	MissingSeparator,
+	InvalidHrp,
+	InvalidChecksum,
+	InvalidVersion,
+	InvalidProgram,
+	InvalidPadding,
+}
+
+impl fmt::Debug for DecodeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidLength => f.write_str("invalid length"),
+			Self::InvalidChar => f.write_str("invalid character"),
+			Self::MixedCase => f.write_str("mixed case"),
+			Self::MissingSeparator => f.write_str("missing separator"),
+			Self::InvalidHrp => f.write_str("invalid human readable part"),
+			Self::InvalidChecksum => f.write_str("invalid checksum"),
+			Self::InvalidVersion => f.write_str("invalid witness version"),
+			Self::InvalidProgram => f.write_str("invalid witness program"),
+			Self::InvalidPadding => f.write_str("invalid padding"),
+		}
+	}
+}
+
+fn polymod<I: Iterator<Item = u8>>(values: I) -> u32 {
+	values.fold(1, |chk, v| {
+		let top = chk >> 25;
+		(0..5)
+			.filter(|i| (top >> i) & 1 == 1)
+			.fold((chk & 0x1ffffff) << 5 ^ v as u32, |chk, i| {
+				chk ^ GENERATOR[i]
+			})
+	})
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+	hrp.bytes()
+		.map(|c| c >> 5)
+		.chain(once(0))
+		.chain(hrp.bytes().map(|c| c & 31))
+}
+
+/// Regroups bytes into 5-bit values, zero padding the last group.
+pub fn to_base32<T: AsRef<[u8]>>(data: T) -> Vec<u8> {
+	let data = data.as_ref();
+	data.iter()
+		.chain(once(&0))
+		.bits::<5>()
+		.take((data.len() * 8).div_ceil(5))
+		.map(|v| v as u8)
+		.collect()
+}
+
+/// Regroups 5-bit values into bytes. The padding must be under 5 bits and
+/// zero.
+pub fn from_base32(data: &[u8]) -> Result<Vec<u8>, DecodeError> {
+	let padding = data.len() * 5 % 8;
+	if padding >= 5 || data.last().is_some_and(|v| v & ((1 << padding) - 1) != 0) {
+		return Err(DecodeError::InvalidPadding);
+	}
+	let values: Vec<_> = data
+		.iter()
+		.map(|&v| BitsN::<5>::try_from(v as usize).map_err(|_| DecodeError::InvalidChar))
+		.collect::<Result<_, _>>()?;
+	Ok(values
+		.into_iter()
+		.bytes()
+		.map(|v| v as u8)
+		.take(data.len() * 5 / 8)
+		.collect())
+}
+
+/// Encodes 5-bit `data` with a checksum of the given variant.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+	let checksum =
+		polymod(hrp_expand(hrp).chain(data.iter().copied()).chain([0; 6])) ^ variant.constant();
+	let mut out = format!("{hrp}1");
+	out.extend(data.iter().map(|&v| CHARSET[v as usize] as char));
+	out.extend((0..6).map(|i| CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char));
+	out
+}
+
+/// As `encode`, in upper case. The checksum is over the lower case form, so
+/// both decode the same.
+pub fn encode_upper(hrp: &str, data: &[u8], variant: Variant) -> String {
+	encode(hrp, data, variant).to_ascii_uppercase()
+}
+
+/// Segwit address: bech32 for version 0, bech32m for later versions.
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> String {
+	let variant = if version == 0 {
+		Variant::Bech32
+	} else {
+		Variant::Bech32m
+	};
+	let data: Vec<u8> = once(version).chain(to_base32(program)).collect();
+	encode(hrp, &data, variant)
+}
+
+/// Human readable part and 5-bit data of a bech32 or bech32m string, per
+/// BIP173 and BIP350.
+pub fn decode_raw(s: &str) -> Result<(String, Vec<u8>, Variant), DecodeError> {
+	if s.len() > 90 {
+		return Err(DecodeError::InvalidLength);
+	}
+	if s.bytes().any(|c| !(33..=126).contains(&c)) {
+		return Err(DecodeError::InvalidChar);
+	}
+	if s.bytes().any(|c| c.is_ascii_lowercase()) && s.bytes().any(|c| c.is_ascii_uppercase()) {
+		return Err(DecodeError::MixedCase);
+	}
+	let s = s.to_ascii_lowercase();
+	let (hrp, data) = s.rsplit_once('1').ok_or(DecodeError::MissingSeparator)?;
+	if hrp.is_empty() || hrp.len() > 83 {
+		return Err(DecodeError::InvalidHrp);
+	}
+	if data.len() < 6 {
+		return Err(DecodeError::InvalidLength);
+	}
+	let data: Vec<u8> = data
+		.bytes()
+		.map(|c| CHARSET.iter().position(|&v| v == c).map(|v| v as u8))
+		.collect::<Option<_>>()
+		.ok_or(DecodeError::InvalidChar)?;
+	let checksum = polymod(hrp_expand(hrp).chain(data.iter().copied()));
+	let variant = [Variant::Bech32, Variant::Bech32m]
+		.into_iter()
+		.find(|v| v.constant() == checksum)
+		.ok_or(DecodeError::InvalidChecksum)?;
+	Ok((hrp.to_string(), data[..data.len() - 6].to_vec(), variant))
+}
+
+/// Human readable part, witness version and program of a segwit address.
+/// The checksum variant must match the version.
+pub fn decode(s: &str) -> Result<(String, u8, Vec<u8>), DecodeError> {
+	let (hrp, data, variant) = decode_raw(s)?;
+	let (&version, data) = data.split_first().ok_or(DecodeError::InvalidLength)?;
+	if version > 16 {
+		return Err(DecodeError::InvalidVersion);
+	}
+	let expected = if version == 0 {
+		Variant::Bech32
+	} else {
+		Variant::Bech32m
+	};
+	if variant != expected {
+		return Err(DecodeError::InvalidChecksum);
+	}
+	let program = from_base32(data)?;
+	if !(2..=40).contains(&program.len()) || version == 0 && ![20, 32].contains(&program.len()) {
+		return Err(DecodeError::InvalidProgram);
+	}
+	Ok((hrp, version, program))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn segwit() {
+		let data = [
+			(
+				"bc",
+				0,
+				"751e76e8199196d454941c45d1b3a323f1433bd6",
+				"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+			),
+			(
+				"tb",
+				0,
+				"1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+				"tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+			),
+			(
+				"bc",
+				1,
+				"751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+				"bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+			),
+			("bc", 16, "751e", "bc1sw50qgdz25j"),
+		];
+		for (hrp, version, program, exp) in data {
+			let program = hex::decode(program).unwrap();
+			assert_eq!(encode_segwit(hrp, version, &program), exp);
+		}
+	}
+
+	#[test]
+	fn encode() {
+		assert_eq!(super::encode("a", &[], Variant::Bech32), "a12uel5l");
+		assert_eq!(super::encode("a", &[], Variant::Bech32m), "a1lqfn3a");
+		assert_eq!(encode_upper("a", &[], Variant::Bech32), "A12UEL5L");
+		assert_eq!(to_base32([0xff]), [31, 28]);
+		assert_eq!(to_base32([]), []);
+		assert_eq!(from_base32(&[31, 28]), Ok(vec![0xff]));
+		assert!(from_base32(&[31, 29]) == Err(DecodeError::InvalidPadding));
+	}
+
+	#[test]
+	fn decode_raw() {
+		let valid = [
+			("A12UEL5L", Variant::Bech32),
+			("a12uel5l", Variant::Bech32),
+			("an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs", Variant::Bech32),
+			("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Variant::Bech32),
+			("11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j", Variant::Bech32),
+			("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", Variant::Bech32),
+			("?1ezyfcl", Variant::Bech32),
+			("A1LQFN3A", Variant::Bech32m),
+			("a1lqfn3a", Variant::Bech32m),
+			("an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6", Variant::Bech32m),
+			("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Variant::Bech32m),
+			("11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8", Variant::Bech32m),
+			("split1checkupstagehandshakeupstreamerranterredcaperredlc445v", Variant::Bech32m),
+			("?1v759aa", Variant::Bech32m),
+		];
+		for (s, variant) in valid {
+			let (hrp, data, v) = super::decode_raw(s).unwrap();
+			assert_eq!(v, variant, "{s}");
+			assert_eq!(super::encode(&hrp, &data, v), s.to_lowercase());
+		}
+
+		let invalid = [
+			("\x201nwldj5", DecodeError::InvalidChar),
+			("\x7f1axkwrx", DecodeError::InvalidChar),
+			("\u{80}1eym55h", DecodeError::InvalidChar),
+			("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx", DecodeError::InvalidLength),
+			("pzry9x0s0muk", DecodeError::MissingSeparator),
+			("1pzry9x0s0muk", DecodeError::InvalidHrp),
+			("x1b4n0q5v", DecodeError::InvalidChar),
+			("li1dgmt3", DecodeError::InvalidLength),
+			("de1lg7wt\u{ff}", DecodeError::InvalidChar),
+			("A1G7SGD8", DecodeError::InvalidChecksum),
+			("10a06t8", DecodeError::InvalidHrp),
+			("1qzzfhee", DecodeError::InvalidHrp),
+			("a12UEL5L", DecodeError::MixedCase),
+		];
+		for (s, err) in invalid {
+			assert!(super::decode_raw(s) == Err(err), "{s}");
+		}
+	}
+
+	#[test]
+	fn decode() {
+		let valid = [
+			("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+			("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+			("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+			("BC1SW50QGDZ25J", "6002751e"),
+			("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", "5210751e76e8199196d454941c45d1b3a323"),
+			("tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy", "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+			("tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c", "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+			("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+		];
+		for (s, script) in valid {
+			let (hrp, version, program) = super::decode(s).expect(s);
+			let script = hex::decode(script).unwrap();
+			let op = if version == 0 { 0 } else { version + 0x50 };
+			assert_eq!([op, program.len() as u8], script[..2], "{s}");
+			assert_eq!(program, script[2..], "{s}");
+			assert_eq!(encode_segwit(&hrp, version, &program), s.to_lowercase());
+		}
+
+		let invalid = [
+			(
+				"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+				DecodeError::InvalidChecksum,
+			),
+			(
+				"tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+				DecodeError::InvalidChecksum,
+			),
+			(
+				"BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+				DecodeError::InvalidChecksum,
+			),
+			(
+				"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+				DecodeError::InvalidChecksum,
+			),
+			(
+				"tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+				DecodeError::InvalidChecksum,
+			),
+			(
+				"bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+				DecodeError::InvalidChar,
+			),
+			(
+				"BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+				DecodeError::InvalidVersion,
+			),
+			("bc1pw5dgrnzv", DecodeError::InvalidProgram),
+			(
+				"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav",
+				DecodeError::InvalidProgram,
+			),
+			(
+				"BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+				DecodeError::InvalidProgram,
+			),
+			(
+				"tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq",
+				DecodeError::MixedCase,
+			),
+			(
+				"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+				DecodeError::InvalidPadding,
+			),
+			(
+				"tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j",
+				DecodeError::InvalidPadding,
+			),
+			("bc1gmk9yu", DecodeError::InvalidLength),
+		];
+		for (s, err) in invalid {
+			assert!(super::decode(s) == Err(err), "{s}");
+		}
+	}
+}
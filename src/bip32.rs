@@ -1,14 +1,185 @@
 use crate::base58;
-use hmac::{Hmac, Mac};
+use crate::bip340::{self, taproot_tweak};
+use crate::hash::{hash160, HmacSha512};
+use crate::util::Secret;
 use k256::{PublicKey, SecretKey};
-use ripemd::Ripemd160;
-use sha2::{Digest, Sha256, Sha512};
 use std::fmt;
 use std::ops::AddAssign;
+use std::str::FromStr;
 
 type ScalarPrimitive = k256::elliptic_curve::ScalarPrimitive<k256::Secp256k1>;
 pub const HARDENED_OFFSET: u32 = 1 << 31;
 
+#[derive(PartialEq)]
+pub enum Bip32Error {
+	InvalidChildNumber,
+	InvalidPath,
+	InvalidEncoding,
+	InvalidVersion,
+	InvalidKey,
+	InvalidChainCode,
+	NotChild,
+}
+
+impl fmt::Debug for Bip32Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidChildNumber => f.write_str("invalid child number"),
+			Self::InvalidPath => f.write_str("invalid path"),
+			Self::InvalidEncoding => f.write_str("invalid encoding"),
+			Self::InvalidVersion => f.write_str("invalid version"),
+			Self::InvalidKey => f.write_str("invalid key"),
+			Self::InvalidChainCode => f.write_str("chain code must be 32 bytes"),
+			Self::NotChild => f.write_str("not a child of the given parent"),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChildNumber {
+	Normal(u32),
+	Hardened(u32),
+}
+
+impl ChildNumber {
+	pub fn normal(i: u32) -> Result<Self, Bip32Error> {
+		Self::Normal(i).validate()
+	}
+
+	pub fn hardened(i: u32) -> Result<Self, Bip32Error> {
+		Self::Hardened(i).validate()
+	}
+
+	pub fn is_hardened(&self) -> bool {
+		matches!(self, Self::Hardened(_))
+	}
+
+	/// Raw BIP32 index, with the hardened offset applied.
+	pub fn index(&self) -> Result<u32, Bip32Error> {
+		match self.validate()? {
+			Self::Normal(i) => Ok(i),
+			Self::Hardened(i) => Ok(i + HARDENED_OFFSET),
+		}
+	}
+
+	fn validate(self) -> Result<Self, Bip32Error> {
+		match self {
+			Self::Normal(i) | Self::Hardened(i) if i >= HARDENED_OFFSET => {
+				Err(Bip32Error::InvalidChildNumber)
+			}
+			_ => Ok(self),
+		}
+	}
+}
+
+impl From<u32> for ChildNumber {
+	fn from(i: u32) -> Self {
+		if i < HARDENED_OFFSET {
+			Self::Normal(i)
+		} else {
+			Self::Hardened(i - HARDENED_OFFSET)
+		}
+	}
+}
+
+impl TryFrom<ChildNumber> for u32 {
+	type Error = Bip32Error;
+
+	fn try_from(i: ChildNumber) -> Result<Self, Self::Error> {
+		i.index()
+	}
+}
+
+impl fmt::Display for ChildNumber {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Normal(i) => write!(f, "{i}"),
+			Self::Hardened(i) => write!(f, "{i}'"),
+		}
+	}
+}
+
+impl FromStr for ChildNumber {
+	type Err = Bip32Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (num, hardened) = match s.strip_suffix(['\'', 'h', 'H']) {
+			Some(num) => (num, true),
+			None => (s, false),
+		};
+		if !num.bytes().all(|c| c.is_ascii_digit()) {
+			return Err(Bip32Error::InvalidChildNumber);
+		}
+		let i = num.parse().map_err(|_| Bip32Error::InvalidChildNumber)?;
+		if hardened {
+			Self::hardened(i)
+		} else {
+			Self::normal(i)
+		}
+	}
+}
+
+pub fn parse_path(path: &str) -> Result<Vec<ChildNumber>, Bip32Error> {
+	let mut parts = path.trim().split('/');
+	if parts.next() != Some("m") {
+		return Err(Bip32Error::InvalidPath);
+	}
+	parts.map(str::parse).collect()
+}
+
+/// Paths with the 1-based line they were read from.
+pub type PathList = Vec<(usize, Vec<ChildNumber>)>;
+
+/// One path per line, skipping blank lines and `#` comments. Fails with the
+/// line number of the first invalid path.
+pub fn parse_path_list(text: &str) -> Result<PathList, (usize, Bip32Error)> {
+	text.lines()
+		.enumerate()
+		.map(|(i, line)| (i + 1, line.trim()))
+		.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+		.map(|(n, line)| {
+			parse_path(line)
+				.map(|path| (n, path))
+				.map_err(|err| (n, err))
+		})
+		.collect()
+}
+
+/// The key above the first unhardened index of `path`, `None` if every index
+/// is hardened. Its xpub together with any private key derived below it
+/// reveals its private key, so the xpub must not be shared carelessly.
+pub fn unhardened_parent(path: &[ChildNumber]) -> Option<&[ChildNumber]> {
+	let i = path
+		.iter()
+		.position(|c| matches!(c, ChildNumber::Normal(_)))?;
+	Some(&path[..i])
+}
+
+pub fn format_path(path: &[ChildNumber]) -> String {
+	path.iter()
+		.fold("m".to_string(), |acc, i| format!("{acc}/{i}"))
+}
+
+/// Serialization version bytes of an extended key pair.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Version {
+	pub private: [u8; 4],
+	pub public: [u8; 4],
+}
+
+impl Version {
+	/// xprv/xpub
+	pub const MAINNET: Self = Self {
+		private: [0x04, 0x88, 0xad, 0xe4],
+		public: [0x04, 0x88, 0xb2, 0x1e],
+	};
+	/// tprv/tpub
+	pub const TESTNET: Self = Self {
+		private: [0x04, 0x35, 0x83, 0x94],
+		public: [0x04, 0x35, 0x87, 0xcf],
+	};
+}
+
 #[derive(Clone)]
 pub struct ExtKey<K> {
 	depth: u8,
@@ -23,15 +194,66 @@ impl<K: Key> ExtKey<K> {
 		self.depth
 	}
 
+	pub fn number(&self) -> ChildNumber {
+		ChildNumber::from(self.number)
+	}
+
+	pub fn fingerprint(&self) -> [u8; 4] {
+		self.key.fingerprint()
+	}
+
+	pub fn identifier(&self) -> [u8; 20] {
+		self.key.identifier()
+	}
+
+	pub fn parent_fingerprint(&self) -> [u8; 4] {
+		self.fingerprint
+	}
+
+	pub fn chain_code(&self) -> &[u8; 32] {
+		&self.chain_code
+	}
+
 	pub fn key(&self) -> &K {
 		&self.key
 	}
 
-	pub fn derive(&self, i: u32) -> Option<Self> {
-		let mut hmac = Hmac::<Sha512>::new_from_slice(&self.chain_code).unwrap();
+	/// BIP340 x-only public key, the taproot internal key. Signers negate
+	/// keys with odd Y, so only the X coordinate is kept.
+	pub fn x_only_key(&self) -> [u8; 32] {
+		bip340::x_only(&self.key.public()).0
+	}
+
+	/// BIP86 taproot output key, the internal key tweaked without a script
+	/// tree. `None` only for keys the tweak rejects.
+	pub fn taproot_output_key(&self) -> Option<[u8; 32]> {
+		taproot_tweak(&self.x_only_key(), None)
+	}
+
+	/// The key with its depth, child number and parent fingerprint on one
+	/// line. Private keys are shown as their xpub.
+	pub fn describe(&self) -> String {
+		let public = ExtKey {
+			depth: self.depth,
+			number: self.number,
+			fingerprint: self.fingerprint,
+			chain_code: self.chain_code,
+			key: self.key.public(),
+		};
+		format!(
+			"{public} (depth {}, child {}, parent {})",
+			self.depth,
+			self.number(),
+			hex::encode(self.fingerprint)
+		)
+	}
+
+	pub fn derive<C: Into<ChildNumber>>(&self, i: C) -> Option<Self> {
+		let i = i.into().index().ok()?;
+		let mut hmac = HmacSha512::new(&self.chain_code);
 		hmac.update(&self.key.serialize_for_child(i)?);
 		hmac.update(&i.to_be_bytes());
-		let res = hmac.finalize().into_bytes();
+		let res = hmac.finalize_locked();
 
 		let key = self
 			.key
@@ -48,7 +270,11 @@ impl<K: Key> ExtKey<K> {
 		})
 	}
 
-	pub fn derive_path(&self, path: &[u32]) -> Option<Self> {
+	pub fn derive_hardened(&self, i: u32) -> Option<Self> {
+		self.derive(ChildNumber::Hardened(i))
+	}
+
+	pub fn derive_path<C: Into<ChildNumber> + Copy>(&self, path: &[C]) -> Option<Self> {
 		let mut key = self.clone();
 		for &i in path {
 			key = key.derive(i)?;
@@ -57,8 +283,12 @@ impl<K: Key> ExtKey<K> {
 	}
 
 	pub fn serialize(&self) -> [u8; 78] {
+		self.serialize_with_version(Version::MAINNET)
+	}
+
+	pub fn serialize_with_version(&self, version: Version) -> [u8; 78] {
 		let mut out = [0; 78];
-		out[..4].copy_from_slice(K::version());
+		out[..4].copy_from_slice(&K::version(&version));
 		out[4] = self.depth;
 		out[5..9].copy_from_slice(&self.fingerprint);
 		out[9..13].copy_from_slice(&self.number.to_be_bytes());
@@ -66,27 +296,102 @@ impl<K: Key> ExtKey<K> {
 		out[45..].copy_from_slice(&self.key.serialize());
 		out
 	}
+
+	/// Checks that `parent` derives this key at its child number. A public
+	/// parent cannot derive hardened children, those are `NotChild` too.
+	pub fn verify_child_of<P: Key>(&self, parent: &ExtKey<P>) -> Result<(), Bip32Error> {
+		if parent.depth.checked_add(1) != Some(self.depth)
+			|| parent.fingerprint() != self.fingerprint
+		{
+			return Err(Bip32Error::NotChild);
+		}
+		match parent.derive(self.number) {
+			Some(child)
+				if child.chain_code == self.chain_code
+					&& child.key.public() == self.key.public() =>
+			{
+				Ok(())
+			}
+			_ => Err(Bip32Error::NotChild),
+		}
+	}
+
+	/// Displays the key with other version bytes, `Display` uses mainnet.
+	/// Only public keys display, see `ExtKey::xprv_with_version`.
+	pub fn with_version(&self, version: Version) -> Versioned<'_, K> {
+		Versioned { key: self, version }
+	}
+
+	/// Field breakdown with the private key redacted.
+	pub fn details(&self) -> String {
+		let mut out = format!("Depth:              {}\n", self.depth);
+		out += &format!("Child number:       {}\n", self.number());
+		out += &format!("Parent fingerprint: {}\n", hex::encode(self.fingerprint));
+		out += &format!("Fingerprint:        {}\n", hex::encode(self.fingerprint()));
+		out += &format!("Chain code:         {}\n", hex::encode(self.chain_code));
+		out += &format!(
+			"Public key:         {}",
+			hex::encode(self.key.public().serialize())
+		);
+		if K::PRIVATE {
+			out += "\nPrivate key:        (redacted)";
+		}
+		out
+	}
+
+	pub fn parse_with_version(s: &str, version: Version) -> Result<Self, Bip32Error> {
+		let data = base58::decode_check(s.trim()).ok_or(Bip32Error::InvalidEncoding)?;
+		if data.len() != 78 {
+			return Err(Bip32Error::InvalidEncoding);
+		}
+		if data[..4] != K::version(&version) {
+			return Err(Bip32Error::InvalidVersion);
+		}
+
+		let mut fingerprint = [0; 4];
+		fingerprint.copy_from_slice(&data[5..9]);
+		let mut number = [0; 4];
+		number.copy_from_slice(&data[9..13]);
+		let mut chain_code = [0; 32];
+		chain_code.copy_from_slice(&data[13..45]);
+		let mut key = [0; 33];
+		key.copy_from_slice(&data[45..]);
+
+		let depth = data[4];
+		let number = u32::from_be_bytes(number);
+		if depth == 0 && (number != 0 || fingerprint != [0; 4]) {
+			return Err(Bip32Error::InvalidEncoding);
+		}
+
+		Ok(Self {
+			depth,
+			number,
+			fingerprint,
+			chain_code,
+			key: K::deserialize(&key).ok_or(Bip32Error::InvalidKey)?,
+		})
+	}
 }
 
 impl ExtKey<SecretKey> {
 	pub fn from_seed<T: AsRef<[u8]>>(seed: T) -> Option<Self> {
-		let mut hmac = Hmac::<Sha512>::new_from_slice(b"Bitcoin seed").unwrap();
+		let mut hmac = HmacSha512::new(b"Bitcoin seed");
 		hmac.update(seed.as_ref());
-		let res = hmac.finalize().into_bytes();
+		let res = hmac.finalize_locked();
 		let key = SecretKey::from_slice(&res[..32]).ok()?;
-		let mut chain_code = [0; 32];
-		chain_code.copy_from_slice(&res[32..]);
-		Some(Self {
-			depth: 0,
-			number: 0,
-			fingerprint: [0; 4],
-			chain_code,
-			key,
-		})
+		Self::new_master(&res[32..], key).ok()
 	}
 
-	#[cfg(test)]
-	pub fn public_key(&self) -> ExtKey<PublicKey> {
+	/// The key as xprv, for display behind an explicit reveal.
+	pub fn xprv(&self) -> Secret<String> {
+		self.xprv_with_version(Version::MAINNET)
+	}
+
+	pub fn xprv_with_version(&self, version: Version) -> Secret<String> {
+		Secret::new(base58::encode_check(self.serialize_with_version(version)))
+	}
+
+	pub fn neuter(&self) -> ExtKey<PublicKey> {
 		ExtKey {
 			depth: self.depth,
 			number: self.number,
@@ -96,38 +401,88 @@ impl ExtKey<SecretKey> {
 		}
 	}
 
-	#[cfg(test)]
-	pub fn root_from_key<T: AsRef<[u8]>>(code: T, key: SecretKey) -> Self {
-		let mut chain_code = [0; 32];
-		chain_code.copy_from_slice(code.as_ref());
-		Self {
+	/// Master key from its parts, e.g. a BIP85 XPRV or an xprv copied field by
+	/// field.
+	pub fn new_master<T: AsRef<[u8]>>(chain_code: T, key: SecretKey) -> Result<Self, Bip32Error> {
+		Ok(Self {
 			depth: 0,
 			number: 0,
 			fingerprint: [0; 4],
-			chain_code,
+			chain_code: chain_code
+				.as_ref()
+				.try_into()
+				.map_err(|_| Bip32Error::InvalidChainCode)?,
 			key,
+		})
+	}
+
+	#[cfg(test)]
+	pub fn root_from_key<T: AsRef<[u8]>>(code: T, key: SecretKey) -> Self {
+		Self::new_master(code, key).unwrap()
+	}
+}
+
+/// `{:#}` prints `details`. Private keys have no `Display`, they are only
+/// shown through `xprv`.
+impl fmt::Display for ExtKey<PublicKey> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if f.alternate() {
+			f.write_str(&self.details())
+		} else {
+			self.with_version(Version::MAINNET).fmt(f)
 		}
 	}
 }
 
-impl<K: Key> fmt::Display for ExtKey<K> {
+pub struct Versioned<'a, K> {
+	key: &'a ExtKey<K>,
+	version: Version,
+}
+
+impl fmt::Display for Versioned<'_, PublicKey> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.write_str(&base58::encode_check(self.serialize()))
+		f.write_str(&base58::encode_check(
+			self.key.serialize_with_version(self.version),
+		))
+	}
+}
+
+impl<K: Key> FromStr for ExtKey<K> {
+	type Err = Bip32Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse_with_version(s, Version::MAINNET)
 	}
 }
 
 pub trait Key: Clone + Sized {
-	fn version() -> &'static [u8];
+	const PRIVATE: bool;
+
+	/// This key type's half of a version pair.
+	fn version(version: &Version) -> [u8; 4];
 	fn serialize(&self) -> [u8; 33];
+	fn deserialize(data: &[u8; 33]) -> Option<Self>;
 	fn serialize_for_child(&self, i: u32) -> Option<[u8; 33]>;
-	fn fingerprint(&self) -> [u8; 4];
+	fn public(&self) -> PublicKey;
 	fn add_scalar(&self, key: ScalarPrimitive) -> Option<Self>;
+
+	/// HASH160 of the compressed public key.
+	fn identifier(&self) -> [u8; 20] {
+		hash160(self.public().to_sec1_bytes())
+	}
+
+	fn fingerprint(&self) -> [u8; 4] {
+		let mut out = [0; 4];
+		out.copy_from_slice(&self.identifier()[..4]);
+		out
+	}
 }
 
 impl Key for SecretKey {
-	// 0x0488ADE4 - xprv
-	fn version() -> &'static [u8] {
-		&[4, 136, 173, 228]
+	const PRIVATE: bool = true;
+
+	fn version(version: &Version) -> [u8; 4] {
+		version.private
 	}
 
 	fn serialize(&self) -> [u8; 33] {
@@ -136,6 +491,13 @@ impl Key for SecretKey {
 		out
 	}
 
+	fn deserialize(data: &[u8; 33]) -> Option<Self> {
+		if data[0] != 0 {
+			return None;
+		}
+		SecretKey::from_slice(&data[1..]).ok()
+	}
+
 	fn serialize_for_child(&self, i: u32) -> Option<[u8; 33]> {
 		if i < HARDENED_OFFSET {
 			Some(self.public_key().serialize())
@@ -144,8 +506,8 @@ impl Key for SecretKey {
 		}
 	}
 
-	fn fingerprint(&self) -> [u8; 4] {
-		self.public_key().fingerprint()
+	fn public(&self) -> PublicKey {
+		self.public_key()
 	}
 
 	fn add_scalar(&self, mut key: ScalarPrimitive) -> Option<Self> {
@@ -159,9 +521,10 @@ impl Key for SecretKey {
 }
 
 impl Key for PublicKey {
-	// 0x0488B21E - xpub
-	fn version() -> &'static [u8] {
-		&[4, 136, 178, 30]
+	const PRIVATE: bool = false;
+
+	fn version(version: &Version) -> [u8; 4] {
+		version.public
 	}
 
 	fn serialize(&self) -> [u8; 33] {
@@ -171,6 +534,10 @@ impl Key for PublicKey {
 		out
 	}
 
+	fn deserialize(data: &[u8; 33]) -> Option<Self> {
+		PublicKey::from_sec1_bytes(data).ok()
+	}
+
 	fn serialize_for_child(&self, i: u32) -> Option<[u8; 33]> {
 		if i < HARDENED_OFFSET {
 			Some(self.serialize())
@@ -179,16 +546,8 @@ impl Key for PublicKey {
 		}
 	}
 
-	fn fingerprint(&self) -> [u8; 4] {
-		let mut hasher = Sha256::new();
-		hasher.update(&self.to_sec1_bytes());
-		let hash = hasher.finalize();
-		let mut hasher = Ripemd160::new();
-		hasher.update(&hash);
-		let hash = hasher.finalize();
-		let mut out = [0; 4];
-		out.copy_from_slice(&hash[..4]);
-		out
+	fn public(&self) -> PublicKey {
+		*self
 	}
 
 	fn add_scalar(&self, key: ScalarPrimitive) -> Option<Self> {
@@ -203,9 +562,342 @@ mod tests {
 	use super::HARDENED_OFFSET as H;
 	use super::*;
 
+	#[test]
+	fn child_number() {
+		use ChildNumber::*;
+		let data = [
+			("0", Normal(0), 0),
+			("44'", Hardened(44), H + 44),
+			("2147483647", Normal(H - 1), H - 1),
+			("2147483647'", Hardened(H - 1), u32::MAX),
+		];
+		for (s, num, raw) in data {
+			assert_eq!(num.to_string(), s);
+			assert_eq!(s.parse::<ChildNumber>(), Ok(num));
+			assert_eq!(ChildNumber::from(raw), num);
+			assert_eq!(num.index(), Ok(raw));
+		}
+
+		assert_eq!("44h".parse::<ChildNumber>(), Ok(Hardened(44)));
+		assert_eq!("44H".parse::<ChildNumber>(), Ok(Hardened(44)));
+		for s in ["", "'", "-1", "+1", "1''", "a", "2147483648", "2147483648'"] {
+			assert_eq!(
+				s.parse::<ChildNumber>(),
+				Err(Bip32Error::InvalidChildNumber)
+			);
+		}
+		assert_eq!(ChildNumber::normal(H), Err(Bip32Error::InvalidChildNumber));
+		assert_eq!(Hardened(H).index(), Err(Bip32Error::InvalidChildNumber));
+	}
+
+	#[test]
+	fn derive_hardened() {
+		let key = ExtKey::<SecretKey>::from_seed([0; 64]).unwrap();
+		let exp = key.derive(H + 44).unwrap().xprv();
+		assert_eq!(
+			key.derive_hardened(44).unwrap().xprv().reveal(),
+			exp.reveal()
+		);
+		assert_eq!(
+			key.derive(ChildNumber::Hardened(44))
+				.unwrap()
+				.xprv()
+				.reveal(),
+			exp.reveal()
+		);
+		assert!(key.derive(ChildNumber::Normal(H)).is_none());
+	}
+
+	#[test]
+	fn path() {
+		use ChildNumber::*;
+		let path = parse_path("m/44'/60'/0'/0/1").unwrap();
+		assert_eq!(
+			path,
+			[
+				Hardened(44),
+				Hardened(60),
+				Hardened(0),
+				Normal(0),
+				Normal(1)
+			]
+		);
+		assert_eq!(format_path(&path), "m/44'/60'/0'/0/1");
+		assert_eq!(parse_path("m"), Ok(vec![]));
+		assert_eq!(format_path(&[]), "m");
+		assert_eq!(parse_path("44'/0'"), Err(Bip32Error::InvalidPath));
+		for s in ["m/", "m//0", "m/0/x"] {
+			assert_eq!(parse_path(s), Err(Bip32Error::InvalidChildNumber));
+		}
+	}
+
+	#[test]
+	fn parse_path_list() {
+		let text = "# accounts\nm/44'/60'/0'/0/0\n\n  m/84'/0'/1'  \n#m/x\nm/0\n";
+		let paths: Vec<_> = super::parse_path_list(text)
+			.unwrap()
+			.into_iter()
+			.map(|(n, path)| (n, format_path(&path)))
+			.collect();
+		assert_eq!(
+			paths,
+			[
+				(2, "m/44'/60'/0'/0/0".to_string()),
+				(4, "m/84'/0'/1'".to_string()),
+				(6, "m/0".to_string())
+			]
+		);
+		assert_eq!(super::parse_path_list(""), Ok(vec![]));
+		assert_eq!(
+			super::parse_path_list("m/0\n\nm/0/x\nm/1'\n"),
+			Err((3, Bip32Error::InvalidChildNumber))
+		);
+		assert_eq!(
+			super::parse_path_list("44'/0'"),
+			Err((1, Bip32Error::InvalidPath))
+		);
+	}
+
+	#[test]
+	fn unhardened_parent() {
+		let data = [
+			("m/44'/60'/0'/0/1", Some("m/44'/60'/0'")),
+			("m/84'/0'/0'/1/5", Some("m/84'/0'/0'")),
+			("m/44'/60'/0/0'", Some("m/44'/60'")),
+			("m/0/1", Some("m")),
+			("m/44'/501'/0'/0'", None),
+			("m", None),
+		];
+		for (path, exp) in data {
+			let path = parse_path(path).unwrap();
+			assert_eq!(
+				super::unhardened_parent(&path).map(format_path).as_deref(),
+				exp
+			);
+		}
+	}
+
+	#[test]
+	fn decode() {
+		use Bip32Error::*;
+		let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+		let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+		assert_eq!(
+			xprv.parse::<ExtKey<PublicKey>>().err(),
+			Some(InvalidVersion)
+		);
+		assert_eq!(
+			xpub.parse::<ExtKey<SecretKey>>().err(),
+			Some(InvalidVersion)
+		);
+
+		// BIP32 test vector 5
+		let data = [
+			("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm", InvalidKey),
+			("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Txnt3siSujt9RCVYsx4qHZGc62TG4McvMGcAUjeuwZdduYEvFn", InvalidKey),
+			("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6N8ZMMXctdiCjxTNq964yKkwrkBJJwpzZS4HS2fxvyYUA4q2Xe4", InvalidKey),
+			("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ", InvalidEncoding),
+			("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8", InvalidEncoding),
+			("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHGMQzT7ayAmfo4z3gY5KfbrZWZ6St24UVf2Qgo6oujFktLHdHY4", InvalidVersion),
+			("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Q5JXayek4PRsn35jii4veMimro1xefsM58PgBMrvdYre8QyULY", InvalidKey),
+		];
+		for (s, err) in data {
+			assert_eq!(s.parse::<ExtKey<PublicKey>>().err(), Some(err));
+		}
+		let data = [
+			("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH", InvalidKey),
+			("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGpWnsj83BHtEy5Zt8CcDr1UiRXuWCmTQLxEK9vbz5gPstX92JQ", InvalidKey),
+			("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD9y5gkZ6Eq3Rjuahrv17fEQ3Qen6J", InvalidKey),
+			("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv", InvalidEncoding),
+			("xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN", InvalidEncoding),
+			("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHPmHJiEDXkTiJTVV9rHEBUem2mwVbbNfvT2MTcAqj3nesx8uBf9", InvalidVersion),
+			("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzF93Y5wvzdUayhgkkFoicQZcP3y52uPPxFnfoLZB21Teqt1VvEHx", InvalidKey),
+			("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD5SDKr24z3aiUvKr9bJpdrcLg1y3G", InvalidKey),
+			("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHL", InvalidEncoding),
+		];
+		for (s, err) in data {
+			assert_eq!(s.parse::<ExtKey<SecretKey>>().err(), Some(err));
+		}
+	}
+
 	fn cmp(key: &ExtKey<SecretKey>, pk: &str, sk: &str) {
-		assert_eq!(key.to_string(), sk);
-		assert_eq!(key.public_key().to_string(), pk);
+		assert_eq!(*key.xprv().reveal(), sk);
+		assert_eq!(key.neuter().to_string(), pk);
+		assert_eq!(sk.parse::<ExtKey<SecretKey>>().unwrap().xprv().reveal(), sk);
+		assert_eq!(pk.parse::<ExtKey<PublicKey>>().unwrap().to_string(), pk);
+	}
+
+	#[test]
+	fn verify_child_of() {
+		let m = ExtKey::<SecretKey>::from_seed(
+			hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		)
+		.unwrap();
+		let m0h = m.derive(H).unwrap();
+		let m0h1 = m0h.derive(1).unwrap();
+		let m0h2 = m0h.derive(2).unwrap();
+		assert_eq!(m0h.verify_child_of(&m), Ok(()));
+		assert_eq!(m0h1.verify_child_of(&m0h), Ok(()));
+		assert_eq!(m0h1.neuter().verify_child_of(&m0h.neuter()), Ok(()));
+		assert_eq!(m0h.verify_child_of(&m.neuter()), Err(Bip32Error::NotChild));
+
+		let mut forged = m0h2.clone();
+		forged.number = 1;
+		// the right chain code with another key
+		let mut swapped = m0h2.clone();
+		swapped.number = 1;
+		swapped.chain_code = m0h1.chain_code;
+		let data = [
+			(&m0h1, &m),
+			(&m, &m0h),
+			(&m0h1, &m0h2),
+			(&forged, &m0h),
+			(&swapped, &m0h),
+		];
+		for (child, parent) in data {
+			assert_eq!(child.verify_child_of(parent), Err(Bip32Error::NotChild));
+		}
+	}
+
+	#[test]
+	fn identifier() {
+		let m = ExtKey::<SecretKey>::from_seed(
+			hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		)
+		.unwrap();
+		let data = [
+			(m.clone(), "3442193e1bb70916e914552172cd4e2dbc9df811"),
+			(
+				m.derive(H).unwrap(),
+				"5c1bd648ed23aa5fd50ba52b2457c11e9e80a6a7",
+			),
+		];
+		for (key, exp) in data {
+			assert_eq!(hex::encode(key.identifier()), exp);
+			assert_eq!(key.neuter().identifier(), key.identifier());
+			assert_eq!(key.fingerprint(), key.identifier()[..4]);
+		}
+	}
+
+	#[test]
+	fn breakdown() {
+		let key = ExtKey::<SecretKey>::from_seed(
+			hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		)
+		.unwrap()
+		.derive(H)
+		.unwrap();
+		let public = "Depth:              1
+Child number:       0'
+Parent fingerprint: 3442193e
+Fingerprint:        5c1bd648
+Chain code:         47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141
+Public key:         035a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56";
+		assert_eq!(format!("{:#}", key.neuter()), public);
+		assert_eq!(
+			key.details(),
+			format!("{public}\nPrivate key:        (redacted)")
+		);
+		assert_eq!(format!("{:?}", key.xprv()), "Secret(..)");
+		assert_eq!(&key.xprv().reveal()[..4], "xprv");
+	}
+
+	#[test]
+	fn describe() {
+		let master = ExtKey::<SecretKey>::from_seed(
+			hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		)
+		.unwrap();
+		let data = [
+			(master.clone(), "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8 (depth 0, child 0, parent 00000000)"),
+			(master.derive(H).unwrap(), "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw (depth 1, child 0', parent 3442193e)"),
+			(master.derive_path(&[H, 1]).unwrap(), "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ (depth 2, child 1, parent 5c1bd648)"),
+		];
+		for (key, exp) in data {
+			assert_eq!(key.describe(), exp);
+			assert_eq!(key.neuter().describe(), exp);
+		}
+	}
+
+	#[test]
+	fn taproot_keys() {
+		// BIP86 test vectors of abandon..about
+		let seed = hex::decode("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+		let account = ExtKey::<SecretKey>::from_seed(seed)
+			.unwrap()
+			.derive_path(&[86 + H, H, H])
+			.unwrap();
+		let data = [
+			(
+				[0, 0],
+				"cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+				"a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+			),
+			(
+				[0, 1],
+				"83dfe85a3151d2517290da461fe2815591ef69f2b18a2ce63f01697a8b313145",
+				"a82f29944d65b86ae6b5e5cc75e294ead6c59391a1edc5e016e3498c67fc7bbb",
+			),
+			(
+				[1, 0],
+				"399f1b2f4393f29a18c937859c5dd8a77350103157eb880f02e8c08214277cef",
+				"882d74e5d0572d5a816cef0041a96b6c1de832f6f9676d9605c44d5e9a97d3dc",
+			),
+		];
+		for (path, internal, output) in data {
+			let key = account.derive_path(&path).unwrap();
+			for (x_only, output_key) in [
+				(key.x_only_key(), key.taproot_output_key()),
+				(key.neuter().x_only_key(), key.neuter().taproot_output_key()),
+			] {
+				assert_eq!(hex::encode(x_only), internal);
+				assert_eq!(output_key.map(hex::encode).as_deref(), Some(output));
+			}
+		}
+	}
+
+	#[test]
+	fn version() {
+		// BIP32 test vector 1 as tprv/tpub
+		let data = [
+			("tprv8ZgxMBicQKsPeDgjzdC36fs6bMjGApWDNLR9erAXMs5skhMv36j9MV5ecvfavji5khqjWaWSFhN3YcCUUdiKH6isR4Pwy3U5y5egddBr16m", "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp"),
+			("tprv8bxNLu25VazNnppTCP4fyhyCvBHcYtzE3wr3cwYeL4HA7yf6TLGEUdS4QC1vLT63TkjRssqJe4CvGNEC8DzW5AoPUw56D1Ayg6HY4oy8QZ9", "tpubD8eQVK4Kdxg3gHrF62jGP7dKVCoYiEB8dFSpuTawkL5YxTus5j5pf83vaKnii4bc6v2NVEy81P2gYrJczYne3QNNwMTS53p5uzDyHvnw2jm"),
+		];
+		let master = ExtKey::<SecretKey>::from_seed(
+			hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(),
+		)
+		.unwrap();
+		let keys = [master.clone(), master.derive(H).unwrap()];
+		for (key, (sk, pk)) in keys.iter().zip(data) {
+			assert_eq!(*key.xprv_with_version(Version::TESTNET).reveal(), sk);
+			assert_eq!(key.neuter().with_version(Version::TESTNET).to_string(), pk);
+			let parsed = ExtKey::<SecretKey>::parse_with_version(sk, Version::TESTNET).unwrap();
+			assert_eq!(parsed.xprv().reveal(), key.xprv().reveal());
+			assert_eq!(
+				ExtKey::<PublicKey>::parse_with_version(pk, Version::TESTNET)
+					.unwrap()
+					.to_string(),
+				key.neuter().to_string()
+			);
+			assert_eq!(
+				sk.parse::<ExtKey<SecretKey>>().err(),
+				Some(Bip32Error::InvalidVersion)
+			);
+		}
+	}
+
+	#[test]
+	fn new_master() {
+		let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+		let root = ExtKey::<SecretKey>::from_seed(seed).unwrap();
+		let key = ExtKey::new_master(root.chain_code(), root.key().clone()).unwrap();
+		assert_eq!(key.xprv().reveal(), root.xprv().reveal());
+		for len in [0, 31, 33, 64] {
+			assert_eq!(
+				ExtKey::new_master(vec![0; len], root.key().clone()).err(),
+				Some(Bip32Error::InvalidChainCode)
+			);
+		}
 	}
 
 	#[test]
@@ -235,10 +927,10 @@ mod tests {
 		];
 
 		for (seed, pk, sk, path) in data {
-			let mut key = ExtKey::<SecretKey>::from_seed(&hex::decode(seed).unwrap()).unwrap();
+			let mut key = ExtKey::<SecretKey>::from_seed(hex::decode(seed).unwrap()).unwrap();
 			cmp(&key, pk, sk);
 			for (i, pk, sk) in path {
-				if let Some(p) = key.public_key().derive(i) {
+				if let Some(p) = key.neuter().derive(i) {
 					assert_eq!(p.to_string(), pk);
 				}
 
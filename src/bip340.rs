@@ -0,0 +1,104 @@
+use crate::hash::sha256;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{PublicKey, SecretKey};
+use sha2::{Digest, Sha256};
+
+/// X coordinate of a public key and whether its Y coordinate is odd.
+pub fn x_only(pubkey: &PublicKey) -> ([u8; 32], bool) {
+	let point = pubkey.to_encoded_point(true);
+	let mut x = [0; 32];
+	x.copy_from_slice(point.x().unwrap());
+	(x, point.as_bytes()[0] == 0x03)
+}
+
+/// The point with even Y for an x-only key.
+fn lift_x(x: &[u8; 32]) -> Option<PublicKey> {
+	let mut sec1 = [0x02; 33];
+	sec1[1..].copy_from_slice(x);
+	PublicKey::from_sec1_bytes(&sec1).ok()
+}
+
+/// `sha256(sha256(tag) || sha256(tag) || msg)`
+pub fn tagged_hash<T: AsRef<[u8]>>(tag: &str, msg: T) -> [u8; 32] {
+	let tag = sha256(tag);
+	Sha256::new()
+		.chain_update(tag)
+		.chain_update(tag)
+		.chain_update(msg)
+		.finalize()
+		.into()
+}
+
+/// BIP341 output key for an internal key and optional script tree root,
+/// `None` if the tweak is out of range or the key is not on the curve.
+pub fn taproot_tweak(internal_key: &[u8; 32], merkle_root: Option<[u8; 32]>) -> Option<[u8; 32]> {
+	let mut data = internal_key.to_vec();
+	data.extend(merkle_root.iter().flatten());
+	let tweak = SecretKey::from_slice(&tagged_hash("TapTweak", data)).ok()?;
+	let point = lift_x(internal_key)?.to_projective() + tweak.public_key().to_projective();
+	let output = PublicKey::from_affine(point.to_affine()).ok()?;
+	Some(x_only(&output).0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn decode(s: &str) -> [u8; 32] {
+		let mut out = [0; 32];
+		hex::decode_to_slice(s, &mut out).unwrap();
+		out
+	}
+
+	#[test]
+	fn x_only() {
+		let data = [
+			(
+				"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+				false,
+			),
+			(
+				"03f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+				true,
+			),
+		];
+		for (pubkey, odd) in data {
+			let key = PublicKey::from_sec1_bytes(&hex::decode(pubkey).unwrap()).unwrap();
+			assert_eq!(super::x_only(&key), (decode(&pubkey[2..]), odd));
+		}
+	}
+
+	#[test]
+	fn tagged_hash() {
+		// BIP341 wallet test vector 2 leaf hash
+		let script =
+			hex::decode("c02220d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")
+				.unwrap();
+		assert_eq!(
+			hex::encode(super::tagged_hash("TapLeaf", script)),
+			"5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+		);
+	}
+
+	#[test]
+	fn tweak() {
+		// BIP341 wallet test vectors
+		let data = [
+			(
+				"d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+				None,
+				"53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+			),
+			(
+				"187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+				Some("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"),
+				"147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+			),
+		];
+		for (internal, root, exp) in data {
+			let output = taproot_tweak(&decode(internal), root.map(decode)).unwrap();
+			assert_eq!(hex::encode(output), exp);
+		}
+		assert!(taproot_tweak(&[0xff; 32], None).is_none());
+	}
+}
@@ -1,19 +1,115 @@
-use crate::bip32::ExtKey;
+use crate::bip32::{Bip32Error, ExtKey};
+use crate::memory::{self, Locked};
 use crate::util::{BitsN, IterExt};
 use k256::SecretKey;
 use once_cell::sync::Lazy;
 use pbkdf2::pbkdf2_hmac;
 use sha2::{Digest, Sha256, Sha512};
-use std::fmt;
+use std::fmt::{self, Write};
+use std::hint::black_box;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 
-static RAW_WORD_LIST: &'static str = include_str!("../bip39_english.txt");
-static WORD_LIST: Lazy<Vec<&'static str>> = Lazy::new(|| RAW_WORD_LIST.lines().collect());
+static RAW_WORD_LIST: &str = include_str!("../bip39_english.txt");
+pub(crate) static WORD_LIST: Lazy<Vec<&'static str>> =
+	Lazy::new(|| RAW_WORD_LIST.lines().collect());
+static RAW_SPANISH: &str = include_str!("../bip39_spanish.txt");
+static SPANISH: Lazy<Vec<&'static str>> = Lazy::new(|| RAW_SPANISH.lines().collect());
+
+/// Number of words in a word list, one per 11-bit value.
+pub const WORD_COUNT: usize = 2048;
+
+/// A list of `WORD_COUNT` words. The official lists are available through
+/// `Language`, other lists can be used with `Mnemonic::from_phrase_with`.
+pub trait Wordlist: Sync {
+	/// Word at `index`, which is below `WORD_COUNT`.
+	fn word(&self, index: usize) -> &str;
+
+	/// Number of leading letters that identify a word.
+	fn unique_prefix_len(&self) -> usize;
+
+	fn index(&self, word: &str) -> Option<usize> {
+		(0..WORD_COUNT).find(|&i| self.word(i) == word)
+	}
+
+	/// Index of the word starting with exactly `unique_prefix_len` letters,
+	/// as metal backups store words. Accents are ignored, as metal stamps
+	/// rarely have them.
+	fn index_by_prefix(&self, prefix: &str) -> Option<usize> {
+		let len = self.unique_prefix_len();
+		let prefix = letters(prefix);
+		if prefix.chars().count() != len {
+			return None;
+		}
+		(0..WORD_COUNT).find(|&i| letters(self.word(i)).chars().take(len).eq(prefix.chars()))
+	}
+}
+
+/// `word` NFKD normalized without combining marks.
+fn letters(word: &str) -> String {
+	word.nfkd().filter(|&c| !is_combining_mark(c)).collect()
+}
+
+fn same_list(a: &dyn Wordlist, b: &dyn Wordlist) -> bool {
+	(0..WORD_COUNT).all(|i| a.word(i) == b.word(i))
+}
+
+/// Official word lists, stored NFKD normalized as BIP39 hashes them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+	English,
+	Spanish,
+}
+
+impl Language {
+	pub const ALL: [Language; 2] = [Self::English, Self::Spanish];
+
+	pub fn word_list(&self) -> &'static [&'static str] {
+		match self {
+			Self::English => &WORD_LIST,
+			Self::Spanish => &SPANISH,
+		}
+	}
+
+	fn wordlist(self) -> &'static dyn Wordlist {
+		match self {
+			Self::English => &Self::English,
+			Self::Spanish => &Self::Spanish,
+		}
+	}
+}
+
+impl Wordlist for Language {
+	fn word(&self, index: usize) -> &str {
+		self.word_list()[index]
+	}
+
+	fn unique_prefix_len(&self) -> usize {
+		4
+	}
+
+	/// Index of a word in any Unicode normalization form.
+	fn index(&self, word: &str) -> Option<usize> {
+		let word: String = word.nfkd().collect();
+		self.word_list().iter().position(|w| *w == word)
+	}
+}
+
+impl fmt::Display for Language {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::English => "English",
+			Self::Spanish => "Spanish",
+		})
+	}
+}
 
 #[derive(PartialEq)]
 pub enum MnemonicError {
 	InvalidWord,
 	IncorrectLength,
 	ChecksumMismatch,
+	InvalidHex,
 }
 
 impl fmt::Debug for MnemonicError {
@@ -22,24 +118,149 @@ impl fmt::Debug for MnemonicError {
 			Self::InvalidWord => f.write_str("invalid word"),
 			Self::IncorrectLength => f.write_str("incorrect length"),
 			Self::ChecksumMismatch => f.write_str("checksum mismatch"),
+			Self::InvalidHex => f.write_str("invalid hex"),
 		}
 	}
 }
 
-pub struct Mnemonic(Vec<u8>);
+/// Supported mnemonic lengths, 128 to 256 bits of entropy.
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Words starting with `prefix`, in word list order.
+pub fn words_with_prefix(prefix: &str) -> &'static [&'static str] {
+	let start = WORD_LIST.partition_point(|w| *w < prefix);
+	let len = WORD_LIST[start..].partition_point(|w| w.starts_with(prefix));
+	&WORD_LIST[start..start + len]
+}
+
+/// Whether word indices form a valid BIP39 checksum, for any standard length.
+pub(crate) fn checksum_valid(indices: &[usize]) -> bool {
+	if !indices.len().is_multiple_of(3) || !(12..=24).contains(&indices.len()) {
+		return false;
+	}
+	let bits = indices.len() * 11;
+	let checksum_bits = bits / 33;
+	let raw: Vec<_> = indices
+		.iter()
+		.map(|&i| BitsN::<11>::try_from(i).unwrap())
+		.chain(BitsN::<11>::try_from(0))
+		.bytes()
+		.map(|v| v as u8)
+		.take(bits.div_ceil(8))
+		.collect();
+	let entropy = &raw[..(bits - checksum_bits) / 8];
+	let checksum = Sha256::digest(entropy)[0] >> (8 - checksum_bits);
+	raw[entropy.len()] >> (8 - checksum_bits) == checksum
+}
+
+pub struct Mnemonic(Vec<u8>, &'static dyn Wordlist);
 
 impl Mnemonic {
 	pub fn from_entropy<T: AsRef<[u8]>>(entropy: T) -> Mnemonic {
-		Mnemonic(entropy.as_ref().to_vec())
+		Self::from_entropy_with(entropy, Language::English.wordlist())
+	}
+
+	pub fn from_entropy_with<T: AsRef<[u8]>>(
+		entropy: T,
+		wordlist: &'static dyn Wordlist,
+	) -> Mnemonic {
+		Mnemonic(entropy.as_ref().to_vec(), wordlist)
+	}
+
+	pub fn from_hex_entropy(hex: &str) -> Result<Mnemonic, MnemonicError> {
+		let entropy = hex::decode(hex.trim()).map_err(|_| MnemonicError::InvalidHex)?;
+		if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+			return Err(MnemonicError::IncorrectLength);
+		}
+		Ok(Self::from_entropy(entropy))
 	}
 
 	pub fn from_phrase(phrase: &str) -> Result<Mnemonic, MnemonicError> {
+		Self::from_phrase_in(phrase, Language::English)
+	}
+
+	pub fn from_phrase_in(phrase: &str, language: Language) -> Result<Mnemonic, MnemonicError> {
+		Self::from_phrase_with(phrase, language.wordlist())
+	}
+
+	pub fn from_phrase_with(
+		phrase: &str,
+		wordlist: &'static dyn Wordlist,
+	) -> Result<Mnemonic, MnemonicError> {
+		Self::parse(phrase, wordlist).map(|entropy| Self(entropy, wordlist))
+	}
+
+	/// A phrase of whole words or their first `unique_prefix_len` letters, as
+	/// stamped into metal backups.
+	pub fn from_prefixes(phrase: &str) -> Result<Mnemonic, MnemonicError> {
+		Self::from_prefixes_with(phrase, Language::English.wordlist())
+	}
+
+	pub fn from_prefixes_with(
+		phrase: &str,
+		wordlist: &'static dyn Wordlist,
+	) -> Result<Mnemonic, MnemonicError> {
+		let mut words = phrase
+			.split_whitespace()
+			.map(|w| {
+				let i = wordlist.index(w).or_else(|| wordlist.index_by_prefix(w))?;
+				Some(wordlist.word(i))
+			})
+			.collect::<Option<Vec<_>>>()
+			.ok_or(MnemonicError::InvalidWord)?
+			.join(" ");
+		let mnemonic = Self::from_phrase_with(&words, wordlist);
+		memory::zeroize_string(&mut words);
+		mnemonic
+	}
+
+	/// The official language of the word list, None for custom lists.
+	pub fn language(&self) -> Option<Language> {
+		Language::ALL
+			.into_iter()
+			.find(|l| same_list(l.wordlist(), self.1))
+	}
+
+	pub fn wordlist(&self) -> &'static dyn Wordlist {
+		self.1
+	}
+
+	/// The same entropy in another word list. The seed changes, as it is
+	/// derived from the words.
+	pub fn translate(&self, target: Language) -> Mnemonic {
+		Self(self.0.clone(), target.wordlist())
+	}
+
+	/// Whether `phrase` is a mnemonic `from_phrase` accepts: known words,
+	/// supported length and valid checksum.
+	pub fn validate(phrase: &str) -> bool {
+		Self::parse(phrase, Language::English.wordlist()).is_ok()
+	}
+
+	/// Index of a word in the English word list, in any Unicode normalization.
+	pub fn word_index(word: &str) -> Option<u16> {
+		Language::English.index(word).map(|i| i as u16)
+	}
+
+	/// Word at `index` in the English word list, None from `WORD_COUNT` on.
+	pub fn word_at(index: u16) -> Option<&'static str> {
+		WORD_LIST.get(index as usize).copied()
+	}
+
+	/// Entropy of a phrase.
+	fn parse(phrase: &str, wordlist: &dyn Wordlist) -> Result<Vec<u8>, MnemonicError> {
 		let indices = phrase
-			.trim()
 			.split_whitespace()
-			.map(|w| WORD_LIST.binary_search(&w))
-			.collect::<Result<Vec<_>, _>>()
-			.map_err(|_| MnemonicError::InvalidWord)?;
+			.map(|w| wordlist.index(w))
+			.collect::<Option<Vec<_>>>()
+			.ok_or(MnemonicError::InvalidWord)?;
+
+		if !WORD_COUNTS.contains(&indices.len()) {
+			return Err(MnemonicError::IncorrectLength);
+		}
+		if !checksum_valid(&indices) {
+			return Err(MnemonicError::ChecksumMismatch);
+		}
 
 		let mut raw: Vec<_> = indices
 			.iter()
@@ -47,31 +268,93 @@ impl Mnemonic {
 			.bytes()
 			.map(|v| v as u8)
 			.collect();
-		if raw.len() != 33 {
-			// TODO: support other lengths
-			return Err(MnemonicError::IncorrectLength);
-		}
+		raw.truncate(indices.len() * 4 / 3);
+		Ok(raw)
+	}
 
-		let mut hasher = Sha256::new();
-		hasher.update(&raw[..32]);
-		let checksum = hasher.finalize();
-		if raw[32] != checksum[0] {
-			return Err(MnemonicError::ChecksumMismatch);
-		}
-		raw.pop();
+	/// Entropy bits, 128 to 256.
+	pub fn strength(&self) -> usize {
+		self.0.len() * 8
+	}
 
-		Ok(Self(raw))
+	pub fn entropy(&self) -> &[u8] {
+		&self.0
 	}
 
 	pub fn seed(&self, passphrase: &str) -> Seed {
-		let mut seed = [0; 64];
-		pbkdf2_hmac::<Sha512>(
-			self.to_string().as_bytes(),
-			format!("mnemonic{passphrase}").as_bytes(),
-			2048,
-			&mut seed,
-		);
-		Seed(seed)
+		Seed::from_phrase(&self.to_string(), passphrase)
+	}
+
+	/// Seed with the passphrase used as typed, without the NFKD normalization
+	/// BIP39 specifies, as some wallets do.
+	pub fn seed_literal(&self, passphrase: &str) -> Seed {
+		let phrase: String = self.to_string().nfkd().collect();
+		let mut salt = format!("mnemonic{passphrase}");
+		let seed = Seed::pbkdf2(&phrase, &salt);
+		memory::zeroize_string(&mut salt);
+		seed
+	}
+
+	/// BIP32 master key, `seed` followed by `Seed::root_key`.
+	pub fn root_key(&self, passphrase: &str) -> Result<ExtKey<SecretKey>, Bip32Error> {
+		self.seed(passphrase).root_key()
+	}
+}
+
+/// Constant time for equal length entropy.
+impl PartialEq for Mnemonic {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.len() == other.0.len()
+			&& self
+				.0
+				.iter()
+				.zip(&other.0)
+				.fold(0, |acc, (a, b)| black_box(acc | (a ^ b)))
+				== 0 && same_list(self.1, other.1)
+	}
+}
+
+impl Eq for Mnemonic {}
+
+impl Mnemonic {
+	/// One numbered word per line, `1. word` to `24. word`.
+	pub fn to_numbered_string(&self) -> String {
+		let phrase = self.to_string();
+		let mut out = String::new();
+		for (i, word) in phrase.split(' ').enumerate() {
+			writeln!(out, "{:>2}. {word}", i + 1).unwrap();
+		}
+		out
+	}
+
+	/// Numbered words four to a row, like hardware wallet recovery sheets.
+	pub fn to_grid_string(&self) -> String {
+		self.numbered_rows::<4>()
+	}
+
+	/// Numbered words three to a row, the interactive display.
+	pub fn to_columns_string(&self) -> String {
+		self.numbered_rows::<3>()
+	}
+
+	fn numbered_rows<const N: usize>(&self) -> String {
+		let phrase = self.to_string();
+		let width = phrase
+			.split(' ')
+			.map(|w| w.chars().count())
+			.max()
+			.unwrap_or(0);
+		let mut out = String::new();
+		for (row, chunk) in phrase.split(' ').chunks::<N>().enumerate() {
+			let line = chunk
+				.iter()
+				.enumerate()
+				.map(|(i, word)| format!("{:>2}. {word:<width$}", row * N + i + 1))
+				.collect::<Vec<_>>()
+				.join("  ");
+			writeln!(out, "{}", line.trim_end()).unwrap();
+		}
+		out
 	}
 }
 
@@ -85,9 +368,9 @@ impl fmt::Display for Mnemonic {
 			.iter()
 			.chain(checksum.iter())
 			.bits::<11>()
-			.map(|w| WORD_LIST[w])
+			.map(|w| self.1.word(w))
 			.take(self.0.len() * 3 / 4)
-			.implode(&" ")
+			.implode(" ")
 		{
 			f.write_str(part)?;
 		}
@@ -95,23 +378,86 @@ impl fmt::Display for Mnemonic {
 	}
 }
 
-pub struct Seed([u8; 64]);
+/// A passphrase the ways wallets may have applied it: as typed, NFKD
+/// normalized as BIP39 specifies, without surrounding whitespace, and empty.
+pub fn passphrase_variants(passphrase: &str) -> [(&'static str, String); 4] {
+	[
+		("Literal", passphrase.to_string()),
+		("NFKD", passphrase.nfkd().collect()),
+		("Trimmed", passphrase.trim().to_string()),
+		("Empty", String::new()),
+	]
+}
+
+pub struct Seed(pub(crate) Locked<64>);
 
 impl Seed {
-	pub fn root_key(&self) -> Option<ExtKey<SecretKey>> {
-		ExtKey::from_seed(self)
+	/// BIP39 seed of a phrase without checking its words or checksum, for
+	/// importing phrases from other word lists or tools.
+	pub fn from_phrase(phrase: &str, passphrase: &str) -> Seed {
+		let phrase: String = phrase
+			.split_whitespace()
+			.collect::<Vec<_>>()
+			.join(" ")
+			.nfkd()
+			.collect();
+		let mut salt: String = "mnemonic"
+			.chars()
+			.chain(passphrase.chars())
+			.nfkd()
+			.collect();
+		let seed = Self::pbkdf2(&phrase, &salt);
+		memory::zeroize_string(&mut salt);
+		seed
+	}
+
+	fn pbkdf2(phrase: &str, salt: &str) -> Seed {
+		let mut seed = Locked::new();
+		pbkdf2_hmac::<Sha512>(phrase.as_bytes(), salt.as_bytes(), 2048, &mut seed[..]);
+		Seed(seed)
+	}
+
+	pub fn as_bytes(&self) -> &[u8; 64] {
+		&self.0
+	}
+
+	pub fn to_hex(&self) -> String {
+		hex::encode(&self.0)
+	}
+
+	/// Hex in groups of `block` bytes separated by spaces, easier to read back
+	/// than one run. Panics if `block` is 0.
+	pub fn to_grouped_hex(&self, block: usize) -> String {
+		self.0
+			.chunks(block)
+			.map(hex::encode)
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+
+	pub fn root_key(&self) -> Result<ExtKey<SecretKey>, Bip32Error> {
+		ExtKey::from_seed(self).ok_or(Bip32Error::InvalidKey)
+	}
+
+	/// Master key fingerprint, to compare passphrase entry across sessions.
+	pub fn fingerprint(&self) -> String {
+		hex::encode(self.root_key().unwrap().fingerprint())
 	}
 }
 
 impl AsRef<[u8]> for Seed {
 	fn as_ref(&self) -> &[u8] {
-		&self.0
+		self.0.as_ref()
 	}
 }
 
-impl fmt::Display for Seed {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		f.write_str(&hex::encode(self.0))
+/// Moves the bytes into locked memory and zeroes the copy passed in.
+impl From<[u8; 64]> for Seed {
+	fn from(mut bytes: [u8; 64]) -> Self {
+		let mut seed = Locked::new();
+		seed.copy_from_slice(&bytes);
+		memory::zeroize(&mut bytes);
+		Seed(seed)
 	}
 }
 
@@ -272,15 +618,349 @@ mod tests {
 			let entropy = hex::decode(entry[0]).unwrap();
 			let mnemonic = Mnemonic::from_entropy(entropy);
 			assert_eq!(mnemonic.to_string(), entry[1]);
+			assert!(Mnemonic::from_phrase(entry[1]).unwrap() == mnemonic);
 			let seed = mnemonic.seed("TREZOR");
-			assert_eq!(seed.to_string(), entry[2]);
+			assert_eq!(seed.to_hex(), entry[2]);
 			if mnemonic.0.len() == 32 {
 				let seed = Mnemonic::from_phrase(entry[1]).unwrap().seed("TREZOR");
-				assert_eq!(seed.to_string(), entry[2]);
+				assert_eq!(seed.to_hex(), entry[2]);
 			}
 			let root_key = seed.root_key().unwrap();
-			assert_eq!(root_key.to_string(), entry[3]);
+			assert_eq!(*root_key.xprv().reveal(), entry[3]);
+			assert_eq!(
+				mnemonic.root_key("TREZOR").unwrap().xprv().reveal(),
+				entry[3]
+			);
+			assert!(checksum_valid(&indices(entry[1])));
+		}
+	}
+
+	fn indices(phrase: &str) -> Vec<usize> {
+		phrase
+			.split(' ')
+			.map(|w| WORD_LIST.binary_search(&w).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn passphrase_variants() {
+		let data = [
+			("TREZOR", ["TREZOR", "TREZOR", "TREZOR", ""]),
+			// composed é and e with a combining accent
+			("caf\u{e9}", ["caf\u{e9}", "cafe\u{301}", "caf\u{e9}", ""]),
+			(
+				"cafe\u{301}",
+				["cafe\u{301}", "cafe\u{301}", "cafe\u{301}", ""],
+			),
+			// non-breaking spaces normalize to spaces and are trimmed
+			(
+				"\u{a0}pass word\u{a0}",
+				["\u{a0}pass word\u{a0}", " pass word ", "pass word", ""],
+			),
+			(
+				" pass\u{a0}word ",
+				[" pass\u{a0}word ", " pass word ", "pass\u{a0}word", ""],
+			),
+			("\u{fb01}le\t", ["\u{fb01}le\t", "file\t", "\u{fb01}le", ""]),
+			("", ["", "", "", ""]),
+		];
+		for (passphrase, exp) in data {
+			let variants = super::passphrase_variants(passphrase);
+			let labels: Vec<_> = variants.iter().map(|(l, _)| *l).collect();
+			assert_eq!(labels, ["Literal", "NFKD", "Trimmed", "Empty"]);
+			let values: Vec<_> = variants.iter().map(|(_, v)| v.as_str()).collect();
+			assert_eq!(values, exp, "{passphrase:?}");
+		}
+	}
+
+	#[test]
+	fn seed_literal() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let composed = "caf\u{e9}";
+		let decomposed = "cafe\u{301}";
+		let fingerprint = |seed: Seed| seed.fingerprint();
+		assert_ne!(
+			fingerprint(mnemonic.seed_literal(composed)),
+			fingerprint(mnemonic.seed_literal(decomposed))
+		);
+		assert_eq!(
+			fingerprint(mnemonic.seed(composed)),
+			fingerprint(mnemonic.seed(decomposed))
+		);
+		assert_eq!(
+			fingerprint(mnemonic.seed_literal(decomposed)),
+			fingerprint(mnemonic.seed(composed))
+		);
+		assert_eq!(
+			mnemonic.seed_literal("TREZOR").to_hex(),
+			mnemonic.seed("TREZOR").to_hex()
+		);
+	}
+
+	#[test]
+	fn eq() {
+		let a = Mnemonic::from_entropy([0; 32]);
+		assert!(a == Mnemonic::from_entropy([0; 32]));
+		assert!(a != Mnemonic::from_entropy([0; 16]));
+		let mut entropy = [0; 32];
+		entropy[31] = 1;
+		assert!(a != Mnemonic::from_entropy(entropy));
+	}
+
+	#[test]
+	fn strength() {
+		for (len, bits) in [(16, 128), (20, 160), (24, 192), (28, 224), (32, 256)] {
+			assert_eq!(Mnemonic::from_entropy(vec![0; len]).strength(), bits);
+		}
+	}
+
+	#[test]
+	fn fingerprint() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		assert_eq!(mnemonic.seed("").fingerprint(), "73c5da0a");
+		assert_ne!(mnemonic.seed("TREZOR").fingerprint(), "73c5da0a");
+	}
+
+	#[test]
+	fn seed() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let seed = mnemonic.seed("TREZOR");
+		let data = [
+			mnemonic.to_string(),
+			format!("  {}\n", mnemonic.to_string().replace(' ', "   ")),
+		];
+		for phrase in data {
+			assert_eq!(
+				Seed::from_phrase(&phrase, "TREZOR").as_bytes(),
+				seed.as_bytes()
+			);
+		}
+		// Spanish phrase in NFC, hashed as NFKD
+		let spanish = "ábaco ".repeat(23) + "ancla";
+		assert_eq!(
+			Seed::from_phrase(&spanish, "").to_hex(),
+			Mnemonic::from_entropy([0; 32])
+				.translate(Language::Spanish)
+				.seed("")
+				.to_hex()
+		);
+		// Japanese vector from bip32JP, the passphrase is not NFKD either
+		let japanese = "あいこくしん\u{3000}".repeat(11) + "あおぞら";
+		assert_eq!(
+			Seed::from_phrase(&japanese, "㍍ガバヴァぱばぐゞちぢ十人十色").to_hex(),
+			"a262d6fb6122ecf45be09c50492b31f92e9beb7d9a845987a02cefda57a15f9c467a17872029a9e92299b5cbdf306e3a0ee620245cbd508959b6cb7ca637bd55"
+		);
+
+		let data = [
+			(8, "5eb00bbddcf06908 4889a8ab91555681 65f5c453ccb85e70 811aaed6f6da5fc1 9a5ac40b389cd370 d086206dec8aa6c4 3daea6690f20ad3d 8d48b2d2ce9e38e4"),
+			(16, "5eb00bbddcf069084889a8ab91555681 65f5c453ccb85e70811aaed6f6da5fc1 9a5ac40b389cd370d086206dec8aa6c4 3daea6690f20ad3d8d48b2d2ce9e38e4"),
+			(64, "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"),
+		];
+		for (block, exp) in data {
+			assert_eq!(mnemonic.seed("").to_grouped_hex(block), exp);
+		}
+		assert_eq!(
+			mnemonic
+				.seed("")
+				.to_grouped_hex(24)
+				.split(' ')
+				.collect::<Vec<_>>()
+				.concat(),
+			mnemonic.seed("").to_hex()
+		);
+
+		let copy = Seed::from(*seed.as_bytes());
+		assert_eq!(copy.to_hex(), seed.to_hex());
+		assert_eq!(copy.to_hex(), "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04");
+		assert_eq!(
+			copy.root_key().unwrap().xprv().reveal(),
+			"xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF"
+		);
+		assert!(Seed::from([0; 64]).root_key().is_ok());
+	}
+
+	#[test]
+	fn translate() {
+		let data = [
+			(
+				[0; 32],
+				"ábaco ".repeat(23) + "ancla",
+				"1e0de8aa97db3c7988f692d9c6151968be89debdbd71b1e34cab15d15ec10eed33412891129e1274fb84624565fd835f7e56df22a997439fca3da05c9c82a156",
+			),
+			(
+				[0x7f; 32],
+				"ligero vista talar yogur venta queso yacer trozo ligero vista talar yogur venta queso yacer trozo ligero vista talar yogur venta queso yacer teatro".to_string(),
+				"826c85bc5c48aacf1782ad85dacc7172c48e27a5e2007919ce45c19c34ec058bf29d4a1c0b7c778c972de7788aabab800ed5c0faccde20ce0bb87e011a372cab",
+			),
+		];
+		for (entropy, spanish, seed) in data {
+			let english = Mnemonic::from_entropy(entropy);
+			let translated = english.translate(Language::Spanish);
+			assert_eq!(translated.language(), Some(Language::Spanish));
+			// word list entries are NFKD, the expected phrase here is NFC
+			assert_eq!(translated.to_string().nfc().collect::<String>(), spanish);
+			assert_eq!(translated.seed("").to_hex(), seed);
+			assert_ne!(english.seed("").to_hex(), seed);
+
+			let parsed = Mnemonic::from_phrase_in(&spanish, Language::Spanish).unwrap();
+			assert!(parsed == translated);
+			assert!(parsed.translate(Language::English) == english);
+			assert!(parsed != english);
+		}
+		assert_eq!(
+			Mnemonic::from_phrase_in(&"ábaco ".repeat(24), Language::Spanish).err(),
+			Some(MnemonicError::ChecksumMismatch)
+		);
+		assert_eq!(
+			Mnemonic::from_phrase_in(&"abandon ".repeat(24), Language::Spanish).err(),
+			Some(MnemonicError::InvalidWord)
+		);
+	}
+
+	/// Numbers spelled in base 26, a list no one would use.
+	struct Synthetic(Vec<String>);
+
+	impl Synthetic {
+		fn new() -> Self {
+			let word = |i: usize| {
+				[i / 676, i / 26 % 26, i % 26]
+					.iter()
+					.map(|&d| (b'a' + d as u8) as char)
+					.collect()
+			};
+			Self((0..WORD_COUNT).map(word).collect())
+		}
+	}
+
+	impl Wordlist for Synthetic {
+		fn word(&self, index: usize) -> &str {
+			&self.0[index]
+		}
+
+		fn unique_prefix_len(&self) -> usize {
+			3
+		}
+	}
+
+	#[test]
+	fn word_index() {
+		let data = [
+			(0, "abandon"),
+			(3, "about"),
+			(102, "art"),
+			(1024, "length"),
+			(2047, "zoo"),
+		];
+		for (index, word) in data {
+			assert_eq!(Mnemonic::word_index(word), Some(index));
+			assert_eq!(Mnemonic::word_at(index), Some(word));
 		}
+		assert_eq!(Mnemonic::word_index("abandom"), None);
+		assert_eq!(Mnemonic::word_index("ábaco"), None);
+		assert_eq!(Mnemonic::word_at(2048), None);
+	}
+
+	#[test]
+	fn wordlist() {
+		let synthetic: &'static Synthetic = Box::leak(Box::new(Synthetic::new()));
+		let english = Mnemonic::from_entropy([0x7f; 32]);
+		let mnemonic = Mnemonic::from_entropy_with([0x7f; 32], synthetic);
+		let phrase = mnemonic.to_string();
+		assert!(phrase.starts_with("bnf czn "));
+		assert_eq!(phrase.split(' ').count(), 24);
+		assert_eq!(mnemonic.language(), None);
+		assert_eq!(english.language(), Some(Language::English));
+
+		// same indices and checksum as the English phrase
+		let indices = |phrase: &str, list: &dyn Wordlist| -> Vec<_> {
+			phrase.split(' ').map(|w| list.index(w).unwrap()).collect()
+		};
+		assert_eq!(
+			indices(&phrase, synthetic),
+			indices(&english.to_string(), &Language::English)
+		);
+
+		let parsed = Mnemonic::from_phrase_with(&phrase, synthetic).unwrap();
+		assert!(parsed == mnemonic);
+		assert!(parsed != english);
+		assert!(parsed.translate(Language::English) == english);
+		assert_eq!(
+			Mnemonic::from_phrase_with(&"aaa ".repeat(24), synthetic).err(),
+			Some(MnemonicError::ChecksumMismatch)
+		);
+		assert_eq!(
+			Mnemonic::from_phrase_with(&english.to_string(), synthetic).err(),
+			Some(MnemonicError::InvalidWord)
+		);
+	}
+
+	#[test]
+	fn formats() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		assert_eq!(
+			mnemonic.to_numbered_string(),
+			" 1. abandon
+ 2. abandon
+ 3. abandon
+ 4. abandon
+ 5. abandon
+ 6. abandon
+ 7. abandon
+ 8. abandon
+ 9. abandon
+10. abandon
+11. abandon
+12. about
+"
+		);
+		assert_eq!(
+			mnemonic.to_grid_string(),
+			" 1. abandon   2. abandon   3. abandon   4. abandon
+ 5. abandon   6. abandon   7. abandon   8. abandon
+ 9. abandon  10. abandon  11. abandon  12. about
+"
+		);
+		assert_eq!(
+			mnemonic.to_columns_string(),
+			" 1. abandon   2. abandon   3. abandon
+ 4. abandon   5. abandon   6. abandon
+ 7. abandon   8. abandon   9. abandon
+10. abandon  11. abandon  12. about
+"
+		);
+		let mnemonic = Mnemonic::from_hex_entropy("9e885d952ad362caeb4efe34a8e91bd2").unwrap();
+		assert_eq!(
+			mnemonic.to_grid_string(),
+			" 1. ozone     2. drill     3. grab      4. fiber
+ 5. curtain   6. grace     7. pudding   8. thank
+ 9. cruise   10. elder    11. eight    12. picnic
+"
+		);
+	}
+
+	#[test]
+	fn prefix() {
+		let data: [(&str, &[&str]); 4] = [
+			("abs", &["absent", "absorb", "abstract", "absurd"]),
+			("zoo", &["zoo"]),
+			("xyz", &[]),
+			("zoom", &[]),
+		];
+		for (prefix, exp) in data {
+			assert_eq!(words_with_prefix(prefix), exp, "{prefix}");
+		}
+		assert_eq!(words_with_prefix("").len(), 2048);
+	}
+
+	#[test]
+	fn checksum() {
+		let abandon = "abandon ".repeat(11);
+		assert!(!checksum_valid(&indices(&format!("{abandon}abandon"))));
+		assert!(!checksum_valid(&indices(&format!(
+			"{abandon}abandon about"
+		))));
+		assert!(!checksum_valid(&indices(&format!("{abandon}zoo"))));
+		assert!(!checksum_valid(&[]));
 	}
 
 	#[test]
@@ -290,10 +970,90 @@ mod tests {
 			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art", Ok(())),
 			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon", Err(IncorrectLength)), 
 			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon", Err(ChecksumMismatch)), 
-			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon lol", Err(InvalidWord))
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon lol", Err(InvalidWord)),
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", Ok(())),
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon", Err(ChecksumMismatch)),
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", Err(IncorrectLength)),
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art", Err(IncorrectLength)),
+			("aban aban aban aban aban aban aban aban aban aban aban abou", Err(InvalidWord)),
 		];
 		for (phrase, res) in data {
+			assert_eq!(Mnemonic::validate(phrase), res.is_ok());
 			assert_eq!(Mnemonic::from_phrase(phrase).map(|_| ()), res)
 		}
 	}
+
+	#[test]
+	fn prefixes() {
+		use MnemonicError::*;
+		let abandon = "abandon ".repeat(11) + "about";
+		let data = [
+			(
+				"aban aban aban aban aban aban aban aban aban aban aban abou",
+				Ok(abandon.as_str()),
+			),
+			(
+				"abandon aban aban aban aban aban aban aban aban aban aban about",
+				Ok(&abandon),
+			),
+			(
+				"aba aban aban aban aban aban aban aban aban aban aban abou",
+				Err(InvalidWord),
+			),
+			(
+				"aband aban aban aban aban aban aban aban aban aban aban abou",
+				Err(InvalidWord),
+			),
+			(
+				"aban aban aban aban aban aban aban aban aban aban aban aban",
+				Err(ChecksumMismatch),
+			),
+		];
+		for (phrase, exp) in data {
+			assert_eq!(
+				Mnemonic::from_prefixes(phrase).map(|m| m.to_string()),
+				exp.map(String::from),
+				"{phrase}"
+			);
+		}
+
+		// composed, decomposed and without accents
+		let spanish =
+			Mnemonic::from_phrase_in(&("ábaco ".repeat(23) + "ancla"), Language::Spanish).unwrap();
+		for aba in ["\u{e1}bac", "a\u{301}bac", "abac"] {
+			let phrase = format!("{}ancl", format!("{aba} ").repeat(23));
+			let parsed = Mnemonic::from_prefixes_with(&phrase, Language::Spanish.wordlist());
+			assert!(parsed.unwrap() == spanish, "{phrase}");
+		}
+		assert_eq!(
+			Language::Spanish.index_by_prefix("abac"),
+			Language::Spanish.index("ábaco")
+		);
+	}
+
+	#[test]
+	fn hex_entropy() {
+		use MnemonicError::*;
+		let mnemonic = Mnemonic::from_hex_entropy("9e885d952ad362caeb4efe34a8e91bd2\n").unwrap();
+		assert_eq!(
+			mnemonic.to_string(),
+			"ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic"
+		);
+		for len in [16, 20, 24, 28, 32] {
+			let mnemonic = Mnemonic::from_hex_entropy(&"ab".repeat(len)).unwrap();
+			assert_eq!(mnemonic.to_string().split(' ').count(), len * 3 / 4);
+		}
+
+		let data = [
+			("", IncorrectLength),
+			("ab", IncorrectLength),
+			(&"ab".repeat(17), IncorrectLength),
+			(&"ab".repeat(33), IncorrectLength),
+			(&"a".repeat(33), InvalidHex),
+			(&"zz".repeat(16), InvalidHex),
+		];
+		for (hex, err) in data {
+			assert_eq!(Mnemonic::from_hex_entropy(hex).err(), Some(err));
+		}
+	}
 }
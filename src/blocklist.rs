@@ -0,0 +1,132 @@
+use crate::bip39::Mnemonic;
+use crate::hash::sha256;
+use std::fmt;
+
+/// SHA256 of the entropy of published example mnemonics, so the phrases
+/// themselves are not part of keymaker.
+const KNOWN: [&str; 27] = [
+	// BIP39 test vectors
+	"374708fff7719dd5979ec875d56cd2286f6d3cf7ec317a3b25632aab28ec37bb",
+	"87dcde7fa6df23e15fa7ba9b2a1f31408eac832f4e615ea815ae92024e3d818b",
+	"4e972baaee2ad54f78153134ef6484cd1a8e383d21582a21a481d4d214161916",
+	"5ac6a5945f16500911219129984ba8b387a06f24fe383ce4e81a73294065461b",
+	"9d908ecfb6b256def8b49a7c504e6c889c4b0e41fe6ce3e01863dd7b61a20aa0",
+	"66682ab64f5c5ad41e883cb68bc79d364603ba8bed9f92b77b75981c95b348ee",
+	"f0f7c775cc67fa28506475b93aaa2af10a9ff92211f8014f35a6f642a6a8fe2e",
+	"44a5f7891570e5631e8c91c85186e6633f4ab5364f644040b2a00126a07985b6",
+	"66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925",
+	"17a7384bf1c50a94b712ce507c5ccd58638cd091278078c6e7dc00ae0aa152fc",
+	"bd75a82b9957d6d043076dea52262635042693f1fe23bcadadaecc908e1e5cc6",
+	"af9613760f72635fbdb44a5a0a63c39f12af30f950a6ee5c971be188e89c4051",
+	"1405e8392df14cd02b976d618b57e67be7ad31e071c410cabc849966892bf9cc",
+	"49b7719c0dff6d00c7a2c9c51083ca04100e9101560851de6fe32d37d7b615be",
+	"005b30a3ab171b8c3bed01ad5e340de09c0efab48ec02a82bac2081139d18663",
+	"074ae1f8e7121aebd15c9070144c78538c18b393a00d08e2f0856a2474ff943c",
+	"68ba3c169969c3fa5071239bcab48a1ce542d573b60be09b5434e5a70269ee20",
+	"a98ed335f345b0a81195f4749340880c781377dc10cd8880dba43fb49264455f",
+	"85cee1dfe99969ecb684e984b0734068bba5385ed8f77e25a0e21b175878656a",
+	"29a8b34689f15991da2c5e2bd4511f0eeadc30f7ed31525aa7332f0a11097ebe",
+	"a209c1acb3397f89bafa9f81bcbb98484527832549d460f85c22f6eb062b352a",
+	"2165f0aafe44e727b2bdb6afec8cb5cd363a5f1d6ce7ac44d6e60562d2f14bce",
+	"8bfe0b56d42ae41677cc263f230cd45b7cfc651b19a7ffdd33cdb421647b321b",
+	"69b6509a79cef59522ec39b476831275e01e89b0af4697497a2d11bb1d4477bf",
+	// Hardhat default
+	"a13b27648ff45bcf53d78bba1170226871a88a2cae91754c0c1e31a087b169d3",
+	// Truffle develop default, "candy maple ..."
+	"eff8ddfae2b78af227bc199f194c0a81546ea157158db737573cf4977422d646",
+	// Ganache deterministic default, "myth like ..."
+	"c60dab48e48562856d784f96190be55a866600eb800defcfad2af3f8bd05e897",
+];
+
+/// Why a mnemonic must not hold funds.
+#[derive(PartialEq)]
+pub enum Weakness {
+	/// Every word is the same.
+	RepeatedWord,
+	/// Entropy is all zero or all one bits.
+	UniformEntropy,
+	/// A test vector or example from documentation, anyone can sweep it.
+	Published,
+}
+
+impl fmt::Debug for Weakness {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::RepeatedWord => "every word is the same",
+			Self::UniformEntropy => "entropy is all zeros or all ones",
+			Self::Published => "this is a published example mnemonic",
+		})
+	}
+}
+
+pub fn weakness(mnemonic: &Mnemonic) -> Option<Weakness> {
+	let phrase = mnemonic.to_string();
+	let mut words = phrase.split_whitespace();
+	let first = words.next()?;
+	if words.all(|w| w == first) {
+		return Some(Weakness::RepeatedWord);
+	}
+	let entropy = mnemonic.entropy();
+	if entropy.iter().all(|&b| b == 0) || entropy.iter().all(|&b| b == 0xff) {
+		return Some(Weakness::UniformEntropy);
+	}
+	let hash = hex::encode(sha256(entropy));
+	KNOWN
+		.contains(&hash.as_str())
+		.then_some(Weakness::Published)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Language;
+
+	#[test]
+	fn weakness() {
+		let data = [
+			(
+				"action action action action action action action action action action action action",
+				Some(Weakness::RepeatedWord),
+			),
+			(
+				"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+				Some(Weakness::UniformEntropy),
+			),
+			(
+				"zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+				Some(Weakness::UniformEntropy),
+			),
+			(
+				"legal winner thank year wave sausage worth useful legal winner thank yellow",
+				Some(Weakness::Published),
+			),
+			(
+				"void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold",
+				Some(Weakness::Published),
+			),
+			(
+				"test test test test test test test test test test test junk",
+				Some(Weakness::Published),
+			),
+			(
+				"myth like bonus scare over problem client lizard pioneer submit female collect",
+				Some(Weakness::Published),
+			),
+			(
+				"stick exact spice sock filter ginger museum horse kit multiply manual wear grief demand derive alert quiz fault december lava picture immune decade jaguar",
+				None,
+			),
+		];
+		for (phrase, exp) in data {
+			let mnemonic = Mnemonic::from_phrase(phrase).unwrap();
+			assert_eq!(super::weakness(&mnemonic), exp, "{phrase}");
+		}
+
+		let translated = Mnemonic::from_phrase(
+			"legal winner thank year wave sausage worth useful legal winner thank yellow",
+		)
+		.unwrap()
+		.translate(Language::Spanish);
+		assert_eq!(super::weakness(&translated), Some(Weakness::Published));
+	}
+}
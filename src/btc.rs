@@ -0,0 +1,349 @@
+use crate::base58;
+use crate::bech32;
+use crate::bip32::{format_path, parse_path, ChildNumber, ExtKey, Key};
+use crate::bip340::taproot_tweak;
+use crate::hash::{hash160, sha256};
+use k256::PublicKey;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+const INPUT_CHARSET: &[u8] = b"0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u64; 5] = [
+	0xf5dee51989,
+	0xa9fdca3312,
+	0x1bab10e32d,
+	0x3706b1677a,
+	0x644d626ffd,
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyOrigin {
+	pub fingerprint: [u8; 4],
+	pub path: Vec<ChildNumber>,
+}
+
+impl KeyOrigin {
+	pub fn new<K: Key>(root: &ExtKey<K>, path: &[ChildNumber]) -> Self {
+		Self {
+			fingerprint: root.fingerprint(),
+			path: path.to_vec(),
+		}
+	}
+}
+
+impl fmt::Display for KeyOrigin {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"[{}{}]",
+			hex::encode(self.fingerprint),
+			&format_path(&self.path)[1..]
+		)
+	}
+}
+
+impl FromStr for KeyOrigin {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let inner = s
+			.strip_prefix('[')
+			.and_then(|s| s.strip_suffix(']'))
+			.ok_or(())?;
+		let (fingerprint, path) = inner.split_once('/').unwrap_or((inner, ""));
+		let mut out = Self {
+			fingerprint: [0; 4],
+			path: parse_path(format!("m/{path}").trim_end_matches('/')).map_err(|_| ())?,
+		};
+		hex::decode_to_slice(fingerprint, &mut out.fingerprint).map_err(|_| ())?;
+		Ok(out)
+	}
+}
+
+fn checksum_polymod(chk: u64, value: u64) -> u64 {
+	let top = chk >> 35;
+	(0..5)
+		.filter(|i| (top >> i) & 1 == 1)
+		.fold((chk & 0x7ffffffff) << 5 ^ value, |chk, i| {
+			chk ^ GENERATOR[i]
+		})
+}
+
+/// BIP380 descriptor checksum, `None` if the descriptor has invalid characters.
+pub fn descriptor_checksum(descriptor: &str) -> Option<String> {
+	let mut chk = 1;
+	let mut groups = Vec::with_capacity(3);
+	for c in descriptor.bytes() {
+		let pos = INPUT_CHARSET.iter().position(|&v| v == c)? as u64;
+		chk = checksum_polymod(chk, pos & 31);
+		groups.push(pos >> 5);
+		if groups.len() == 3 {
+			chk = checksum_polymod(chk, groups[0] * 9 + groups[1] * 3 + groups[2]);
+			groups.clear();
+		}
+	}
+	if !groups.is_empty() {
+		chk = checksum_polymod(chk, groups.iter().fold(0, |acc, g| acc * 3 + g));
+	}
+	for _ in 0..8 {
+		chk = checksum_polymod(chk, 0);
+	}
+	chk ^= 1;
+	Some(
+		(0..8)
+			.map(|i| CHECKSUM_CHARSET[((chk >> (5 * (7 - i))) & 31) as usize] as char)
+			.collect(),
+	)
+}
+
+/// Appends `#checksum` to a descriptor.
+pub fn with_checksum(descriptor: &str) -> Option<String> {
+	Some(format!("{descriptor}#{}", descriptor_checksum(descriptor)?))
+}
+
+/// Address of a compressed or uncompressed public key.
+pub fn p2pkh_address(pubkey: &[u8]) -> String {
+	let mut data = vec![0x00];
+	data.extend_from_slice(&hash160(pubkey));
+	base58::encode_check(data)
+}
+
+pub fn p2wpkh_address(pubkey: &[u8; 33]) -> String {
+	bech32::encode_segwit("bc", 0, &hash160(pubkey))
+}
+
+/// BIP49 nested segwit address.
+pub fn p2sh_p2wpkh_address(pubkey: &[u8; 33]) -> String {
+	let mut script = vec![0x00, 0x14];
+	script.extend_from_slice(&hash160(pubkey));
+	p2sh_address(&script)
+}
+
+/// BIP86 key path only taproot address, `None` only for invalid keys.
+pub fn p2tr_address(pubkey: &[u8; 33]) -> Option<String> {
+	let internal = pubkey[1..].try_into().unwrap();
+	let output = taproot_tweak(&internal, None)?;
+	Some(bech32::encode_segwit("bc", 1, &output))
+}
+
+pub fn p2wsh_address(script: &[u8]) -> String {
+	bech32::encode_segwit("bc", 0, &sha256(script))
+}
+
+pub fn p2sh_address(script: &[u8]) -> String {
+	let mut data = vec![0x05];
+	data.extend_from_slice(&hash160(script));
+	base58::encode_check(data)
+}
+
+/// Single-sig address types.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AddressType {
+	P2wpkh,
+	P2pkh,
+	P2tr,
+}
+
+impl AddressType {
+	pub const ALL: [AddressType; 3] = [Self::P2wpkh, Self::P2pkh, Self::P2tr];
+
+	/// BIP44 purpose of the account path.
+	pub fn purpose(&self) -> u32 {
+		match self {
+			Self::P2wpkh => 84,
+			Self::P2pkh => 44,
+			Self::P2tr => 86,
+		}
+	}
+
+	/// `None` only for keys the taproot tweak rejects.
+	pub fn address(&self, pubkey: &[u8; 33]) -> Option<String> {
+		match self {
+			Self::P2wpkh => Some(p2wpkh_address(pubkey)),
+			Self::P2pkh => Some(p2pkh_address(pubkey)),
+			Self::P2tr => p2tr_address(pubkey),
+		}
+	}
+
+	fn descriptor_function(&self) -> &'static str {
+		match self {
+			Self::P2wpkh => "wpkh",
+			Self::P2pkh => "pkh",
+			Self::P2tr => "tr",
+		}
+	}
+}
+
+impl fmt::Display for AddressType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::P2wpkh => "P2WPKH",
+			Self::P2pkh => "P2PKH",
+			Self::P2tr => "P2TR",
+		})
+	}
+}
+
+/// Receive `0/i` and change `1/i` addresses of an account key.
+pub fn receive_and_change(
+	account: &ExtKey<PublicKey>,
+	address_type: AddressType,
+	range: Range<u32>,
+) -> Option<Vec<(String, String)>> {
+	let receive = account.derive(0)?;
+	let change = account.derive(1)?;
+	range
+		.map(|i| {
+			Some((
+				address_type.address(&receive.derive(i)?.key().serialize())?,
+				address_type.address(&change.derive(i)?.key().serialize())?,
+			))
+		})
+		.collect()
+}
+
+/// Receive chain descriptor of a single-sig account, with checksum.
+pub fn descriptor(
+	address_type: AddressType,
+	origin: &KeyOrigin,
+	xpub: &ExtKey<PublicKey>,
+) -> String {
+	let descriptor = format!("{}({origin}{xpub}/0/*)", address_type.descriptor_function());
+	with_checksum(&descriptor).unwrap()
+}
+
+/// `<0 32-byte-hash>` witness program, as embedded in P2SH-P2WSH.
+pub fn p2wsh_script(script: &[u8]) -> Vec<u8> {
+	let mut out = vec![0x00, 0x20];
+	out.extend_from_slice(&sha256(script));
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::ChildNumber::Hardened;
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn descriptor() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let data = [
+			(AddressType::P2wpkh, "wpkh([73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)#wc3n3van"),
+			(AddressType::P2pkh, "pkh([73c5da0a/44'/0'/0']xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj/0/*)#8w4z8fed"),
+			(AddressType::P2tr, "tr([73c5da0a/86'/0'/0']xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ/0/*)#rg247h69"),
+		];
+		for (address_type, exp) in data {
+			let path = [Hardened(address_type.purpose()), Hardened(0), Hardened(0)];
+			let origin = KeyOrigin::new(&root, &path);
+			let xpub = root.derive_path(&path).unwrap().neuter();
+			assert_eq!(super::descriptor(address_type, &origin, &xpub), exp);
+		}
+	}
+
+	#[test]
+	fn receive_and_change() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let data = [
+			(
+				AddressType::P2wpkh,
+				[
+					(
+						"bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+						"bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+					),
+					(
+						"bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+						"bc1qggnasd834t54yulsep6fta8lpjekv4zj6gv5rf",
+					),
+				],
+			),
+			(
+				AddressType::P2pkh,
+				[
+					(
+						"1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+						"1J3J6EvPrv8q6AC3VCjWV45Uf3nssNMRtH",
+					),
+					(
+						"1Ak8PffB2meyfYnbXZR9EGfLfFZVpzJvQP",
+						"13vKxXzHXXd8HquAYdpkJoi9ULVXUgfpS5",
+					),
+				],
+			),
+		];
+		for (address_type, exp) in data {
+			let path = [Hardened(address_type.purpose()), Hardened(0), Hardened(0)];
+			let account = root.derive_path(&path).unwrap().neuter();
+			let pairs = super::receive_and_change(&account, address_type, 0..2).unwrap();
+			let exp: Vec<_> = exp
+				.iter()
+				.map(|(r, c)| (r.to_string(), c.to_string()))
+				.collect();
+			assert_eq!(pairs, exp);
+		}
+	}
+
+	#[test]
+	fn address() {
+		let mut pubkey = [0; 33];
+		hex::decode_to_slice(
+			"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+			&mut pubkey,
+		)
+		.unwrap();
+		assert_eq!(p2pkh_address(&pubkey), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+		assert_eq!(
+			p2wpkh_address(&pubkey),
+			"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+		);
+		// BIP86 m/86'/0'/0'/0/0 of abandon..about
+		hex::decode_to_slice(
+			"03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+			&mut pubkey,
+		)
+		.unwrap();
+		assert_eq!(
+			p2tr_address(&pubkey).unwrap(),
+			"bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+		);
+	}
+
+	#[test]
+	fn checksum() {
+		let data = [
+			("raw(deadbeef)", "89f8spxm"),
+			("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)", "02wpgw69"),
+		];
+		for (descriptor, exp) in data {
+			assert_eq!(descriptor_checksum(descriptor).unwrap(), exp);
+		}
+		assert!(descriptor_checksum("raw(deadbeef)\n").is_none());
+	}
+
+	#[test]
+	fn key_origin() {
+		use ChildNumber::*;
+		let origin: KeyOrigin = "[73c5da0a/48'/0'/0'/2']".parse().unwrap();
+		assert_eq!(origin.fingerprint, [0x73, 0xc5, 0xda, 0x0a]);
+		assert_eq!(
+			origin.path,
+			[Hardened(48), Hardened(0), Hardened(0), Hardened(2)]
+		);
+		assert_eq!(origin.to_string(), "[73c5da0a/48'/0'/0'/2']");
+		assert_eq!(
+			"[73c5da0a]".parse::<KeyOrigin>().unwrap().to_string(),
+			"[73c5da0a]"
+		);
+		for s in [
+			"73c5da0a/48'",
+			"[73c5da/48']",
+			"[73c5da0a/x]",
+			"[73c5da0a/48'",
+		] {
+			assert!(s.parse::<KeyOrigin>().is_err());
+		}
+	}
+}
@@ -0,0 +1,221 @@
+use crate::bech32::{self, Variant};
+use crate::bip32::ChildNumber;
+use crate::bip39::Mnemonic;
+use crate::hash::{blake2b224, HmacSha512};
+use crate::slip44;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha512;
+
+// base address, payment key hash and stake key hash, mainnet
+const BASE_ADDRESS: u8 = 0x01;
+// reward address, stake key hash, mainnet
+const REWARD_ADDRESS: u8 = 0xe1;
+
+/// CIP-1852 account path.
+pub fn account_path(account: u32) -> Vec<ChildNumber> {
+	use ChildNumber::Hardened;
+	vec![Hardened(1852), Hardened(slip44::ADA), Hardened(account)]
+}
+
+/// BIP32-Ed25519 extended key, derived with the V2 scheme used by Shelley
+/// wallets. `key` is the 64 byte extended secret, kL followed by kR.
+#[derive(Clone)]
+pub struct IcarusKey {
+	key: [u8; 64],
+	chain_code: [u8; 32],
+}
+
+impl IcarusKey {
+	/// Icarus master key: PBKDF2 of the passphrase salted with the mnemonic
+	/// entropy, clamped as an ed25519 scalar.
+	pub fn from_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Self {
+		let mut raw = [0; 96];
+		pbkdf2_hmac::<Sha512>(passphrase.as_bytes(), mnemonic.entropy(), 4096, &mut raw);
+		raw[0] &= 0b1111_1000;
+		raw[31] &= 0b0001_1111;
+		raw[31] |= 0b0100_0000;
+		let mut out = Self {
+			key: [0; 64],
+			chain_code: [0; 32],
+		};
+		out.key.copy_from_slice(&raw[..64]);
+		out.chain_code.copy_from_slice(&raw[64..]);
+		out
+	}
+
+	fn hmac(&self, tag: u8, data: &[u8], i: u32) -> [u8; 64] {
+		let mut hmac = HmacSha512::new(&self.chain_code);
+		hmac.update(&[tag]);
+		hmac.update(data);
+		hmac.update(&i.to_le_bytes());
+		hmac.finalize()
+	}
+
+	pub fn derive(&self, i: ChildNumber) -> Option<Self> {
+		let i = u32::try_from(i).ok()?;
+		let (z, c) = if i >= 1 << 31 {
+			(self.hmac(0, &self.key, i), self.hmac(1, &self.key, i))
+		} else {
+			let public_key = self.public_key();
+			(self.hmac(2, &public_key, i), self.hmac(3, &public_key, i))
+		};
+
+		let mut out = Self {
+			key: [0; 64],
+			chain_code: [0; 32],
+		};
+		let mut zl = [0; 32];
+		for j in 0..28 {
+			zl[j] |= z[j] << 3;
+			zl[j + 1] = z[j] >> 5;
+		}
+		out.key[..32].copy_from_slice(&add(&self.key[..32], &zl));
+		out.key[32..].copy_from_slice(&add(&self.key[32..], &z[32..]));
+		out.chain_code.copy_from_slice(&c[32..]);
+		Some(out)
+	}
+
+	pub fn derive_path(&self, path: &[ChildNumber]) -> Option<Self> {
+		path.iter().try_fold(self.clone(), |key, &i| key.derive(i))
+	}
+
+	pub fn chain_code(&self) -> &[u8; 32] {
+		&self.chain_code
+	}
+
+	pub fn public_key(&self) -> [u8; 32] {
+		let mut kl = [0; 32];
+		kl.copy_from_slice(&self.key[..32]);
+		EdwardsPoint::mul_base(&Scalar::from_bytes_mod_order(kl))
+			.compress()
+			.to_bytes()
+	}
+
+	/// Keys of the `i`th external address of an account key: payment at
+	/// `0/i`, stake at the account's first stake key `2/0`.
+	pub fn address_keys(&self, i: u32) -> Option<AddressKeys> {
+		let payment = self.derive_path(&[0.into(), i.into()])?;
+		let stake = self.derive_path(&STAKE_PATH.map(ChildNumber::from))?;
+		Some(AddressKeys {
+			payment: payment.public_key(),
+			stake: stake.public_key(),
+		})
+	}
+
+	/// Base address of the `i`th external key of an account key.
+	pub fn base_address(&self, i: u32) -> Option<String> {
+		Some(self.address_keys(i)?.base_address())
+	}
+}
+
+/// Stake key below an account key.
+pub const STAKE_PATH: [u32; 2] = [2, 0];
+
+/// Public keys of an address, wallets show them combined as a base address.
+pub struct AddressKeys {
+	pub payment: [u8; 32],
+	pub stake: [u8; 32],
+}
+
+impl AddressKeys {
+	pub fn base_address(&self) -> String {
+		base_address(&self.payment, &self.stake)
+	}
+
+	/// Where staking rewards accrue, shared by every address of the account.
+	pub fn reward_address(&self) -> String {
+		reward_address(&self.stake)
+	}
+}
+
+pub fn base_address(payment_key: &[u8; 32], stake_key: &[u8; 32]) -> String {
+	let mut data = vec![BASE_ADDRESS];
+	data.extend_from_slice(&blake2b224(payment_key));
+	data.extend_from_slice(&blake2b224(stake_key));
+	bech32::encode("addr", &bech32::to_base32(data), Variant::Bech32)
+}
+
+pub fn reward_address(stake_key: &[u8; 32]) -> String {
+	let mut data = vec![REWARD_ADDRESS];
+	data.extend_from_slice(&blake2b224(stake_key));
+	bech32::encode("stake", &bech32::to_base32(data), Variant::Bech32)
+}
+
+/// Little endian addition mod 2^256.
+fn add(a: &[u8], b: &[u8]) -> [u8; 32] {
+	let mut out = [0; 32];
+	let mut carry = 0;
+	for j in 0..32 {
+		let sum = a[j] as u16 + b[j] as u16 + carry;
+		out[j] = sum as u8;
+		carry = sum >> 8;
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn derive() {
+		// CIP-19 test vectors, mnemonic "test walk nut penalty hip pave soap
+		// entry language right filter choice". Their addr_vk is account 0 and
+		// their stake_vk account 1, the account 0 stake key is from an
+		// independent BIP32-Ed25519 implementation.
+		let mnemonic =
+			Mnemonic::from_entropy(hex::decode("df9ed25ed146bf43336a5d7cf7395994").unwrap());
+		let root = IcarusKey::from_mnemonic(&mnemonic, "");
+		let data = [
+			(
+				(0, [0.into(), 0.into()]),
+				"73fea80d424276ad0978d4fe5310e8bc2d485f5f6bb3bf87612989f112ad5a7d",
+			),
+			(
+				(1, [2.into(), 0.into()]),
+				"09ab278d49b7b86a055185c474c4942281ddfa05a54684c7e8a6f230625aee57",
+			),
+			(
+				(0, [2.into(), 0.into()]),
+				"2c041c9c6a676ac54d25e2fdce44c56581e316ae43adc4c7bf17f23214d8d892",
+			),
+		];
+		let mut keys = Vec::new();
+		for ((account, path), exp) in data {
+			let account = root.derive_path(&account_path(account)).unwrap();
+			let key = account.derive_path(&path).unwrap().public_key();
+			assert_eq!(hex::encode(key), exp);
+			keys.push(key);
+		}
+		assert_eq!(
+			base_address(&keys[0], &keys[1]),
+			"addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x"
+		);
+		assert_eq!(
+			reward_address(&keys[1]),
+			"stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw"
+		);
+	}
+
+	#[test]
+	fn address_keys() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let account = IcarusKey::from_mnemonic(&mnemonic, "")
+			.derive_path(&account_path(0))
+			.unwrap();
+		let stake = account.derive_path(&[2.into(), 0.into()]).unwrap();
+		for i in 0..3 {
+			let keys = account.address_keys(i).unwrap();
+			let payment = account.derive_path(&[0.into(), i.into()]).unwrap();
+			assert_eq!(keys.payment, payment.public_key());
+			assert_eq!(keys.stake, stake.public_key());
+			assert_eq!(Some(keys.base_address()), account.base_address(i));
+			assert_eq!(keys.reward_address(), reward_address(&stake.public_key()));
+		}
+		assert_eq!(
+			account.base_address(0).unwrap(),
+			"addr1qy8ac7qqy0vtulyl7wntmsxc6wex80gvcyjy33qffrhm7sh927ysx5sftuw0dlft05dz3c7revpf7jx0xnlcjz3g69mq4afdhv"
+		);
+	}
+}
@@ -0,0 +1,218 @@
+use crate::bip39::{Language, Mnemonic, MnemonicError, Wordlist, WORD_COUNTS};
+use crate::suggest;
+use std::fmt;
+
+pub struct UnknownWord {
+	pub position: usize,
+	pub word: String,
+	pub suggestions: Vec<&'static str>,
+}
+
+/// Findings on a pasted phrase, without the seed or any key. Displays as a
+/// report meant for people who are not familiar with BIP39.
+pub struct Report {
+	pub word_count: usize,
+	/// The word list most of the words are from.
+	pub language: Option<Language>,
+	pub unknown: Vec<UnknownWord>,
+	pub error: Option<MnemonicError>,
+	/// Master fingerprint without passphrase, if the phrase is valid.
+	pub fingerprint: Option<String>,
+}
+
+impl Report {
+	pub fn is_valid(&self) -> bool {
+		self.error.is_none()
+	}
+}
+
+pub fn check(phrase: &str) -> Report {
+	let words: Vec<_> = phrase.split_whitespace().collect();
+	let known = |language: &Language| words.iter().filter(|w| language.index(w).is_some()).count();
+	let language = Language::ALL
+		.into_iter()
+		.filter(|l| known(l) > 0)
+		.max_by_key(known);
+
+	let list = language.unwrap_or(Language::English);
+	let unknown = words
+		.iter()
+		.enumerate()
+		.filter(|(_, w)| list.index(w).is_none())
+		.map(|(i, w)| UnknownWord {
+			position: i + 1,
+			word: w.to_string(),
+			suggestions: match list {
+				Language::English => suggest::closest_words(&w.to_lowercase(), 3),
+				_ => vec![],
+			},
+		})
+		.collect();
+
+	let (error, fingerprint) = match Mnemonic::from_phrase_in(phrase, list) {
+		Ok(mnemonic) => (None, Some(mnemonic.seed("").fingerprint())),
+		Err(err) => (Some(err), None),
+	};
+	Report {
+		word_count: words.len(),
+		language,
+		unknown,
+		error,
+		fingerprint,
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Words:       {}", self.word_count)?;
+		if !WORD_COUNTS.contains(&self.word_count) {
+			write!(f, ", expected 12, 15, 18, 21 or 24")?;
+		}
+		writeln!(f)?;
+		match self.language {
+			Some(language) => writeln!(f, "Language:    {language}")?,
+			None => writeln!(
+				f,
+				"Language:    unknown, no word is in a supported word list"
+			)?,
+		}
+		for unknown in &self.unknown {
+			let label = format!("Word {}:", unknown.position);
+			write!(f, "{label:<13}{} is not in the word list", unknown.word)?;
+			if !unknown.suggestions.is_empty() {
+				write!(f, ", did you mean {}?", unknown.suggestions.join(" or "))?;
+			}
+			writeln!(f)?;
+		}
+		let checksum = match self.error {
+			None => "valid",
+			Some(MnemonicError::ChecksumMismatch) => {
+				"INVALID, a word is wrong or words are out of order"
+			}
+			Some(_) => "not checked",
+		};
+		writeln!(f, "Checksum:    {checksum}")?;
+		if let Some(fingerprint) = &self.fingerprint {
+			writeln!(f, "Fingerprint: {fingerprint} (without passphrase)")?;
+		}
+		match self.error {
+			None => write!(f, "Result:      backup OK"),
+			Some(_) => write!(f, "Result:      backup has problems, do not rely on it"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check() {
+		let abandon = "abandon ".repeat(23);
+		let data = [
+			(abandon.clone() + "art", None, Some(Language::English), 0),
+			(
+				abandon.clone() + "zoo",
+				Some(MnemonicError::ChecksumMismatch),
+				Some(Language::English),
+				0,
+			),
+			(
+				abandon.clone() + "artt",
+				Some(MnemonicError::InvalidWord),
+				Some(Language::English),
+				1,
+			),
+			(
+				"abandon ".repeat(11) + "about",
+				None,
+				Some(Language::English),
+				0,
+			),
+			(
+				"abandon ".repeat(12),
+				Some(MnemonicError::ChecksumMismatch),
+				Some(Language::English),
+				0,
+			),
+			(
+				"abandon ".repeat(13),
+				Some(MnemonicError::IncorrectLength),
+				Some(Language::English),
+				0,
+			),
+			(
+				"ábaco ".repeat(23) + "ancla",
+				None,
+				Some(Language::Spanish),
+				0,
+			),
+			(
+				"qqq www".to_string(),
+				Some(MnemonicError::InvalidWord),
+				None,
+				2,
+			), // metal backup prefixes are not words, Mnemonic::from_prefixes reads them
+			(
+				"aban ".repeat(11) + "abou",
+				Some(MnemonicError::InvalidWord),
+				None,
+				12,
+			),
+		];
+		for (phrase, error, language, unknown) in data {
+			let report = super::check(&phrase);
+			assert!(report.error == error, "{phrase}");
+			assert_eq!(report.language, language, "{phrase}");
+			assert_eq!(report.unknown.len(), unknown, "{phrase}");
+			assert_eq!(report.fingerprint.is_some(), error.is_none(), "{phrase}");
+			assert_eq!(report.is_valid(), error.is_none(), "{phrase}");
+			if error.is_some() {
+				assert!(
+					report.to_string().ends_with("do not rely on it"),
+					"{phrase}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn report() {
+		let abandon = "abandon ".repeat(22);
+		let data = [
+			(
+				abandon.clone() + "abandon art",
+				"Words:       24
+Language:    English
+Checksum:    valid
+Fingerprint: 5436d724 (without passphrase)
+Result:      backup OK",
+			),
+			(
+				abandon.clone() + "abandom zoo",
+				"Words:       24
+Language:    English
+Word 23:     abandom is not in the word list, did you mean abandon or random?
+Checksum:    not checked
+Result:      backup has problems, do not rely on it",
+			),
+			(
+				abandon.clone() + "abandon zoo",
+				"Words:       24
+Language:    English
+Checksum:    INVALID, a word is wrong or words are out of order
+Result:      backup has problems, do not rely on it",
+			),
+			(
+				abandon.clone(),
+				"Words:       22, expected 12, 15, 18, 21 or 24
+Language:    English
+Checksum:    not checked
+Result:      backup has problems, do not rely on it",
+			),
+		];
+		for (phrase, exp) in data {
+			assert_eq!(super::check(&phrase).to_string(), exp);
+		}
+	}
+}
@@ -0,0 +1,190 @@
+use crate::util;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::{self, stdout, IsTerminal, Write};
+
+/// Where a flow writes its output. `write!` and `writeln!` work on sinks and
+/// do not return a result.
+pub trait OutputSink {
+	fn write_str(&mut self, s: &str);
+
+	/// Whether output is shown on a terminal, formats may depend on it.
+	fn is_terminal(&self) -> bool {
+		false
+	}
+
+	fn write_fmt(&mut self, args: fmt::Arguments<'_>) {
+		match args.as_str() {
+			Some(s) => self.write_str(s),
+			None => self.write_str(&args.to_string()),
+		}
+	}
+}
+
+/// Flushes after every write so prompts without a newline are shown.
+pub struct Stdout;
+
+impl OutputSink for Stdout {
+	fn write_str(&mut self, s: &str) {
+		print!("{s}");
+		stdout().flush().unwrap();
+	}
+
+	fn is_terminal(&self) -> bool {
+		stdout().is_terminal()
+	}
+}
+
+#[derive(Default)]
+pub struct Buffer(pub String);
+
+impl OutputSink for Buffer {
+	fn write_str(&mut self, s: &str) {
+		self.0.push_str(s);
+	}
+}
+
+/// An export file, written at once by `save`.
+pub struct FileSink {
+	path: String,
+	private: bool,
+	contents: String,
+}
+
+impl FileSink {
+	pub fn new(path: &str) -> Self {
+		Self {
+			path: path.to_string(),
+			private: false,
+			contents: String::new(),
+		}
+	}
+
+	/// A new file only the owner can read, see `util::write_private`.
+	pub fn private(path: &str) -> Self {
+		Self {
+			private: true,
+			..Self::new(path)
+		}
+	}
+
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// Writes the file, or with `dry_run` prints its path and contents to
+	/// `out` without touching the filesystem. Private files hold secrets, their
+	/// contents are not printed.
+	pub fn save(self, dry_run: bool, out: &mut dyn OutputSink) -> io::Result<()> {
+		if dry_run && self.private {
+			writeln!(
+				out,
+				"Dry run, would write {} bytes of secrets to {}, not shown",
+				self.contents.len(),
+				self.path
+			);
+			return Ok(());
+		}
+		if dry_run {
+			writeln!(
+				out,
+				"Dry run, would write {} bytes to {}:",
+				self.contents.len(),
+				self.path
+			);
+			out.write_str(&self.contents);
+			return Ok(());
+		}
+		if self.private {
+			util::write_private(&self.path, &self.contents)
+		} else {
+			fs::write(&self.path, &self.contents)
+		}
+	}
+}
+
+impl OutputSink for FileSink {
+	fn write_str(&mut self, s: &str) {
+		self.contents.push_str(s);
+	}
+}
+
+/// Input that exits keymaker at any prompt except passphrases. No valid
+/// mnemonic, index or roll is a lone `q` or `quit`.
+pub fn is_quit(input: &str) -> bool {
+	matches!(input.trim(), "q" | "quit")
+}
+
+/// Where a flow reads its input from.
+pub trait InputSource {
+	/// Appends a line to `buf`, false once input ends or the user quits.
+	fn read_line(&mut self, buf: &mut String) -> bool;
+}
+
+/// Scripted input lines, for tests.
+pub struct Script(VecDeque<String>);
+
+impl Script {
+	pub fn new(lines: &[&str]) -> Self {
+		Self(lines.iter().map(|l| l.to_string()).collect())
+	}
+}
+
+impl InputSource for Script {
+	fn read_line(&mut self, buf: &mut String) -> bool {
+		match self.0.pop_front() {
+			Some(line) if !is_quit(&line) => {
+				buf.push_str(&line);
+				buf.push('\n');
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn script() {
+		let mut script = Script::new(&["one", "", "q", "after"]);
+		let mut buf = String::new();
+		assert!(script.read_line(&mut buf));
+		assert!(script.read_line(&mut buf));
+		assert_eq!(buf, "one\n\n");
+		assert!(!script.read_line(&mut buf));
+		assert_eq!(buf, "one\n\n");
+	}
+
+	#[test]
+	fn file_sink() {
+		let path = std::env::temp_dir().join(format!("keymaker-sink-{}", std::process::id()));
+		let path = path.to_str().unwrap();
+		let mut file = FileSink::new(path);
+		writeln!(file, "a,{}", 1);
+		let mut out = Buffer::default();
+		file.save(true, &mut out).unwrap();
+		assert_eq!(
+			out.0,
+			format!("Dry run, would write 4 bytes to {path}:\na,1\n")
+		);
+		let mut file = FileSink::private(path);
+		writeln!(file, "secret");
+		let mut out = Buffer::default();
+		file.save(true, &mut out).unwrap();
+		assert_eq!(
+			out.0,
+			format!("Dry run, would write 7 bytes of secrets to {path}, not shown\n")
+		);
+		assert!(fs::metadata(path).is_err());
+
+		let mut file = FileSink::new(path);
+		file.write_str("b\n");
+		file.save(false, &mut out).unwrap();
+		assert_eq!(fs::read_to_string(path).unwrap(), "b\n");
+		fs::remove_file(path).unwrap();
+	}
+}
@@ -0,0 +1,332 @@
+use crate::bip32::{ChildNumber, ExtKey, Key, Version};
+use crate::bip39::{checksum_valid, Seed, WORD_LIST};
+use crate::btc;
+use crate::hash::hmac_sha512;
+use crate::memory::Locked;
+use crate::util::IterExt;
+use k256::{PublicKey, SecretKey};
+use pbkdf2::pbkdf2_hmac;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::UnicodeNormalization;
+
+/// yprv/ypub
+pub const YPUB: Version = Version {
+	private: [0x04, 0x9d, 0x78, 0x78],
+	public: [0x04, 0x9d, 0x7c, 0xb2],
+};
+
+/// zprv/zpub
+pub const ZPUB: Version = Version {
+	private: [0x04, 0xb2, 0x43, 0x0c],
+	public: [0x04, 0xb2, 0x47, 0x46],
+};
+
+#[derive(PartialEq)]
+pub enum ElectrumError {
+	NotElectrum,
+}
+
+impl fmt::Debug for ElectrumError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::NotElectrum => f.write_str("not an Electrum seed"),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeedType {
+	Standard,
+	Segwit,
+}
+
+impl SeedType {
+	fn prefix(&self) -> &'static str {
+		match self {
+			Self::Standard => "01",
+			Self::Segwit => "100",
+		}
+	}
+
+	pub fn address(&self, account: &ExtKey<PublicKey>, change: bool, i: u32) -> Option<String> {
+		let pubkey = account.derive_path(&[change as u32, i])?.key().serialize();
+		Some(match self {
+			Self::Standard => btc::p2pkh_address(&pubkey),
+			Self::Segwit => btc::p2wpkh_address(&pubkey),
+		})
+	}
+}
+
+impl fmt::Display for SeedType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Standard => "Electrum standard",
+			Self::Segwit => "Electrum segwit",
+		})
+	}
+}
+
+/// Ranges Electrum's `is_CJK` treats as CJK.
+const CJK: [(u32, u32); 29] = [
+	(0x4e00, 0x9fff),
+	(0x3400, 0x4dbf),
+	(0x20000, 0x2a6df),
+	(0x2a700, 0x2b73f),
+	(0x2b740, 0x2b81f),
+	(0xf900, 0xfaff),
+	(0x2f800, 0x2fa1d),
+	(0x3190, 0x319f),
+	(0x2e80, 0x2eff),
+	(0x2f00, 0x2fdf),
+	(0x31c0, 0x31ef),
+	(0x2ff0, 0x2fff),
+	(0xe0100, 0xe01ef),
+	(0x3100, 0x312f),
+	(0x31a0, 0x31bf),
+	(0xff00, 0xffef),
+	(0x3040, 0x309f),
+	(0x30a0, 0x30ff),
+	(0x31f0, 0x31ff),
+	(0x1b000, 0x1b0ff),
+	(0xac00, 0xd7af),
+	(0x1100, 0x11ff),
+	(0xa960, 0xa97f),
+	(0xd7b0, 0xd7ff),
+	(0x3130, 0x318f),
+	(0xa4d0, 0xa4ff),
+	(0x16f00, 0x16f9f),
+	(0xa000, 0xa48f),
+	(0xa490, 0xa4cf),
+];
+
+fn is_cjk(c: char) -> bool {
+	CJK.iter()
+		.any(|&(start, end)| (start..=end).contains(&(c as u32)))
+}
+
+/// Electrum's `normalize_text`: NFKD, lowercase, combining marks removed,
+/// whitespace collapsed and dropped between two CJK characters.
+fn normalize(text: &str) -> String {
+	let text: String = text
+		.nfkd()
+		.collect::<String>()
+		.to_lowercase()
+		.chars()
+		.filter(|&c| canonical_combining_class(c) == 0)
+		.collect();
+	let chars: Vec<char> = text
+		.split_whitespace()
+		.implode(" ")
+		.flat_map(str::chars)
+		.collect();
+	chars
+		.iter()
+		.enumerate()
+		.filter(|&(i, &c)| {
+			!(c == ' '
+				&& i > 0 && is_cjk(chars[i - 1])
+				&& chars.get(i + 1).is_some_and(|&n| is_cjk(n)))
+		})
+		.map(|(_, &c)| c)
+		.collect()
+}
+
+fn version(normalized: &str) -> String {
+	hex::encode(hmac_sha512(b"Seed version", normalized.as_bytes()))
+}
+
+/// Electrum seed type of a phrase, if it is one.
+pub fn seed_type(phrase: &str) -> Option<SeedType> {
+	let version = version(&normalize(phrase));
+	[SeedType::Segwit, SeedType::Standard]
+		.into_iter()
+		.find(|t| version.starts_with(t.prefix()))
+}
+
+pub struct ElectrumSeed {
+	phrase: String,
+	seed_type: SeedType,
+}
+
+impl ElectrumSeed {
+	pub fn from_phrase(phrase: &str) -> Result<Self, ElectrumError> {
+		let seed_type = seed_type(phrase).ok_or(ElectrumError::NotElectrum)?;
+		Ok(Self {
+			phrase: normalize(phrase),
+			seed_type,
+		})
+	}
+
+	/// Same search as Electrum's `make_seed`: 132 bits of entropy as base 2048
+	/// words, least significant first, incremented until the version prefix
+	/// matches and the phrase is not also a valid BIP39 mnemonic. Panics on
+	/// less than 17 bytes of entropy.
+	pub fn from_entropy<T: AsRef<[u8]>>(entropy: T, seed_type: SeedType) -> Self {
+		assert!(entropy.as_ref().len() >= 17);
+		let mut entropy = entropy.as_ref().to_vec();
+		let mut words: Vec<usize> = loop {
+			let words: Vec<_> = entropy.iter().bits::<11>().take(12).collect();
+			// 12 words need entropy >= 2^121
+			if words[0] != 0 {
+				break words.into_iter().rev().collect();
+			}
+			entropy = Sha256::digest(entropy).to_vec();
+		};
+		loop {
+			for i in 0.. {
+				if i == words.len() {
+					words.push(1);
+					break;
+				}
+				words[i] = (words[i] + 1) % WORD_LIST.len();
+				if words[i] != 0 {
+					break;
+				}
+			}
+			let phrase: String = words.iter().map(|&w| WORD_LIST[w]).implode(" ").collect();
+			if !checksum_valid(&words) && version(&phrase).starts_with(seed_type.prefix()) {
+				return Self { phrase, seed_type };
+			}
+		}
+	}
+
+	pub fn seed_type(&self) -> SeedType {
+		self.seed_type
+	}
+
+	pub fn seed(&self, passphrase: &str) -> Seed {
+		let mut seed = Locked::new();
+		pbkdf2_hmac::<Sha512>(
+			self.phrase.as_bytes(),
+			format!("electrum{}", normalize(passphrase)).as_bytes(),
+			2048,
+			&mut seed[..],
+		);
+		Seed(seed)
+	}
+
+	/// Account key: the root for standard wallets, `m/0'` for segwit.
+	pub fn account(&self, passphrase: &str) -> Option<ExtKey<SecretKey>> {
+		let root = self.seed(passphrase).root_key().ok()?;
+		match self.seed_type {
+			SeedType::Standard => Some(root),
+			SeedType::Segwit => root.derive(ChildNumber::Hardened(0)),
+		}
+	}
+
+	/// Account xpub, or zpub for segwit wallets.
+	pub fn master_public_key(&self, passphrase: &str) -> Option<String> {
+		let account = self.account(passphrase)?.neuter();
+		let version = match self.seed_type {
+			SeedType::Standard => Version::MAINNET,
+			SeedType::Segwit => ZPUB,
+		};
+		Some(account.with_version(version).to_string())
+	}
+}
+
+impl fmt::Display for ElectrumSeed {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.phrase)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalize() {
+		let data = [
+			("  Wild  FATHER\ttree\n", "wild father tree"),
+			("ábaco Ábaco ñandú", "abaco abaco nandu"),
+			("なのか ひろい\u{3000}しなん", "なのかひろいしなん"),
+			("がっこう", "かっこう"),
+			("abc あい def", "abc あい def"),
+			("\u{ff21}\u{ff22}", "ab"),
+			("", ""),
+		];
+		for (text, exp) in data {
+			assert_eq!(super::normalize(text), exp, "{text}");
+		}
+	}
+
+	#[test]
+	fn seed_type() {
+		let data = [
+			("wild father tree among universe such mobile favorite target dynamic credit identify", Some(SeedType::Segwit)),
+			("Wild  father tree among universe such mobile favorite target dynamic credit identify\n", Some(SeedType::Segwit)),
+			("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", None),
+		];
+		for (phrase, exp) in data {
+			assert_eq!(super::seed_type(phrase), exp);
+		}
+	}
+
+	#[test]
+	fn seed() {
+		let seed = ElectrumSeed::from_phrase(
+			"wild father tree among universe such mobile favorite target dynamic credit identify",
+		)
+		.unwrap();
+		assert_eq!(
+			seed.seed("").to_hex(),
+			"aac2a6302e48577ab4b46f23dbae0774e2e62c796f797d0a1b5faeb528301e3064342dafb79069e7c4c6b8c38ae11d7a973bec0d4f70626f8cc5184a8d0b0756"
+		);
+		assert_eq!(
+			seed.seed("Did you ever hear the tragedy of Darth Plagueis the Wise?").to_hex(),
+			"4aa29f2aeb0127efb55138ab9e7be83b36750358751906f86c662b21a1ea1370f949e6d1a12fa56d3d93cadda93038c76ac8118597364e46f5156fde6183c82f"
+		);
+		assert_eq!(
+			ElectrumSeed::from_phrase("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").err(),
+			Some(ElectrumError::NotElectrum)
+		);
+	}
+
+	#[test]
+	fn wallet() {
+		// as derived by Electrum 4
+		let seed = ElectrumSeed::from_phrase(
+			"bitter grass shiver impose acquire brush forget axis eager alone wine silver",
+		)
+		.unwrap();
+		assert_eq!(seed.seed_type(), SeedType::Segwit);
+		assert_eq!(
+			seed.master_public_key("").unwrap(),
+			"zpub6nsHdRuY92FsMKdbn9BfjBCG6X8pyhCibNP6uDvpnw2cyrVhecvHRMa3Ne8kdJZxjxgwnpbHLkcR4bfnhHy6auHPJyDTQ3kianeuVLdkCYQ"
+		);
+		let account = seed.account("").unwrap().neuter();
+		let address = |change, i| SeedType::Segwit.address(&account, change, i).unwrap();
+		assert_eq!(
+			address(false, 0),
+			"bc1q3g5tmkmlvxryhh843v4dz026avatc0zzr6h3af"
+		);
+		assert_eq!(
+			address(true, 0),
+			"bc1qdy94n2q5qcp0kg7v9yzwe6wvfkhnvyzje7nx2p"
+		);
+	}
+
+	#[test]
+	fn generate() {
+		for seed_type in [SeedType::Segwit, SeedType::Standard] {
+			for entropy in [[0; 32], [0x80; 32], [0x5a; 32]] {
+				let seed = ElectrumSeed::from_entropy(entropy, seed_type);
+				let phrase = seed.to_string();
+				assert_eq!(phrase.split(' ').count(), 12);
+				assert_eq!(super::seed_type(&phrase), Some(seed_type));
+				let words: Vec<_> = phrase
+					.split(' ')
+					.map(|w| WORD_LIST.binary_search(&w).unwrap())
+					.collect();
+				assert!(!checksum_valid(&words));
+				assert_eq!(
+					ElectrumSeed::from_entropy(entropy, seed_type).to_string(),
+					phrase
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,424 @@
+use sha2::{Digest, Sha256};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+/// Valid D6 rolls as ASCII digits with 6 mapped to 0, and whether any
+/// characters had to be discarded.
+pub fn dice_rolls(input: &str) -> (Vec<u8>, bool) {
+	let mut invalid = false;
+	let mut rolls = Vec::new();
+	for c in input.bytes() {
+		match c {
+			b'6' => rolls.push(b'0'),
+			b'1'..=b'5' => rolls.push(c),
+			_ => invalid = true,
+		}
+	}
+	(rolls, invalid)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Die {
+	D6,
+	D20,
+}
+
+impl Die {
+	pub fn faces(&self) -> u32 {
+		match self {
+			Self::D6 => 6,
+			Self::D20 => 20,
+		}
+	}
+
+	/// Rolls in the form that gets hashed, and whether any input had to be
+	/// discarded. D6 rolls are digits; D20 rolls are numbers separated by
+	/// spaces or commas, hashed as one byte each with 20 mapped to 0.
+	pub fn rolls(&self, input: &str) -> (Vec<u8>, bool) {
+		match self {
+			Self::D6 => dice_rolls(input),
+			Self::D20 => {
+				let mut invalid = false;
+				let mut rolls = Vec::new();
+				for roll in input.split(|c: char| c.is_whitespace() || c == ',') {
+					match roll.parse::<u8>() {
+						Ok(r @ 1..=20) => rolls.push(r % 20),
+						_ => invalid |= !roll.is_empty(),
+					}
+				}
+				(rolls, invalid)
+			}
+		}
+	}
+
+	/// Rolls needed for 256 bits of entropy.
+	pub fn rolls_for_256_bits(&self) -> usize {
+		self.rolls_for_bits(256)
+	}
+
+	pub fn rolls_for_bits(&self, bits: usize) -> usize {
+		(bits as f64 / (self.faces() as f64).log2()).ceil() as usize
+	}
+
+	/// Upper bound on the entropy of `n` rolls, capped at 256 bits.
+	pub fn bits(&self, n: usize) -> usize {
+		((n as f64 * (self.faces() as f64).log2()) as usize).min(256)
+	}
+
+	/// Face value of a roll from `rolls`, between 0 and `faces - 1`.
+	fn value(&self, roll: u8) -> u32 {
+		match self {
+			Self::D6 => (roll - b'0') as u32,
+			Self::D20 => roll as u32,
+		}
+	}
+
+	/// Inverse of `value`.
+	fn roll(&self, value: u32) -> u8 {
+		match self {
+			Self::D6 => b'0' + value as u8,
+			Self::D20 => value as u8,
+		}
+	}
+}
+
+/// Rolls that `DiceHash::Raw` turns back into `entropy`: its digits in base
+/// `faces`, most significant first, left padded with zero rolls (a 6 or a 20)
+/// to `rolls_for_bits` of the entropy. Rolls are in the form `Die::rolls`
+/// returns.
+pub fn raw_rolls(die: Die, entropy: &[u8]) -> Vec<u8> {
+	let mut n = entropy.to_vec();
+	let mut rolls = vec![die.roll(0); die.rolls_for_bits(entropy.len() * 8)];
+	for roll in rolls.iter_mut().rev() {
+		let mut rem = 0;
+		for byte in n.iter_mut() {
+			let v = rem << 8 | *byte as u32;
+			*byte = (v / die.faces()) as u8;
+			rem = v % die.faces();
+		}
+		*roll = die.roll(rem);
+	}
+	rolls
+}
+
+/// How dice rolls are turned into 256 bits of entropy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiceHash {
+	/// SHA-256 of the rolls and salt. Biased or short input still yields
+	/// uniformly distributed output, although no more entropy than was rolled.
+	Sha256,
+	/// 32 bytes of SHAKE256 output, with the same properties as SHA-256.
+	Shake256,
+	/// The rolls read as a base-`faces` number, reduced modulo 2^bits. Matches
+	/// tools that convert rolls directly, but nothing hides bias in the dice.
+	/// Rolls at or above the largest multiple of 2^bits the same number of
+	/// rolls can reach are rejected, so that every output is equally likely,
+	/// and so are too few rolls. Salt is not supported.
+	Raw,
+}
+
+impl DiceHash {
+	/// 32 bytes of entropy, `None` for rejected raw rolls.
+	pub fn entropy(&self, die: Die, rolls: &[u8], salt: &[u8]) -> Option<[u8; 32]> {
+		self.entropy_bytes(die, rolls, salt, 32)?.try_into().ok()
+	}
+
+	/// `len` bytes of entropy, up to 32: a prefix of the hash, which for
+	/// SHAKE256 is the same as asking for `len` bytes of output, or for raw
+	/// conversion the rolls modulo 2^(8 * len). `None` for rejected raw rolls.
+	pub fn entropy_bytes(
+		&self,
+		die: Die,
+		rolls: &[u8],
+		salt: &[u8],
+		len: usize,
+	) -> Option<Vec<u8>> {
+		match self {
+			Self::Sha256 => {
+				let hash = Sha256::new()
+					.chain_update(rolls)
+					.chain_update(salt)
+					.finalize();
+				Some(hash[..len].to_vec())
+			}
+			Self::Shake256 => {
+				let mut out = vec![0; len];
+				Shake256::default()
+					.chain(rolls)
+					.chain(salt)
+					.finalize_xof()
+					.read(&mut out);
+				Some(out)
+			}
+			Self::Raw => raw_entropy(die, rolls, len),
+		}
+	}
+}
+
+/// `v = v * m + a` for a big-endian number of any length.
+fn mul_add(v: &mut Vec<u8>, m: u32, a: u32) {
+	let mut carry = a;
+	for byte in v.iter_mut().rev() {
+		let x = *byte as u32 * m + carry;
+		*byte = x as u8;
+		carry = x >> 8;
+	}
+	while carry > 0 {
+		v.insert(0, carry as u8);
+		carry >>= 8;
+	}
+}
+
+/// Rolls as a number `n` below `faces^rolls`, accepted when `n` is below the
+/// largest multiple of 2^(8 * len) up to `faces^rolls`, i.e. when the bytes
+/// of `n` above the lowest `len` are below those of `faces^rolls`.
+fn raw_entropy(die: Die, rolls: &[u8], len: usize) -> Option<Vec<u8>> {
+	let mut n = vec![];
+	let mut max = vec![1];
+	for &roll in rolls {
+		mul_add(&mut n, die.faces(), die.value(roll));
+		mul_add(&mut max, die.faces(), 0);
+	}
+	let high = |v: &[u8]| -> Vec<u8> {
+		let high = &v[..v.len().saturating_sub(len)];
+		high.iter().copied().skip_while(|&b| b == 0).collect()
+	};
+	let (n_high, max_high) = (high(&n), high(&max));
+	if (n_high.len(), &n_high) >= (max_high.len(), &max_high) {
+		return None;
+	}
+	let mut out = vec![0; len];
+	let low = &n[n.len().saturating_sub(len)..];
+	out[len - low.len()..].copy_from_slice(low);
+	Some(out)
+}
+
+/// What the user must type to hash text with `brainwallet_entropy`.
+pub const BRAINWALLET_CONFIRMATION: &str =
+	"I understand that text I chose is not random and anyone who guesses it can take my funds";
+
+/// Whether dice input is mostly letters, as when a sentence is pasted instead
+/// of rolls.
+pub fn looks_like_text(input: &str) -> bool {
+	let chars: Vec<_> = input.chars().filter(|c| !c.is_whitespace()).collect();
+	let letters = chars.iter().filter(|c| c.is_alphabetic()).count();
+	letters * 2 > chars.len()
+}
+
+/// SHA-256 of arbitrary text, NOT RECOMMENDED: people pick guessable text.
+/// Only available when `confirmation` is exactly `BRAINWALLET_CONFIRMATION`.
+pub fn brainwallet_entropy(text: &str, confirmation: &str) -> Option<[u8; 32]> {
+	(confirmation.trim() == BRAINWALLET_CONFIRMATION).then(|| Sha256::digest(text.trim()).into())
+}
+
+/// Flags input that uses too few distinct faces or is dominated by one face.
+pub fn low_quality(rolls: &[u8]) -> bool {
+	let mut counts = [0; 256];
+	for &r in rolls {
+		counts[r as usize] += 1;
+	}
+	let distinct = counts.iter().filter(|&&c| c > 0).count();
+	let max = counts.iter().max().copied().unwrap_or(0);
+	!rolls.is_empty() && (distinct < 3 || max * 2 > rolls.len())
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn dice_rolls() {
+		assert_eq!(super::dice_rolls("123456"), (b"123450".to_vec(), false));
+		assert_eq!(super::dice_rolls("1 2-7a6"), (b"120".to_vec(), true));
+		assert_eq!(super::dice_rolls(""), (vec![], false));
+	}
+
+	#[test]
+	fn d20() {
+		use super::Die;
+		let data = [
+			("1 20 7,13", vec![1, 0, 7, 13], false),
+			(" 4  19\n", vec![4, 19], false),
+			("0 21 5 x", vec![5], true),
+			("", vec![], false),
+		];
+		for (input, rolls, invalid) in data {
+			assert_eq!(Die::D20.rolls(input), (rolls, invalid), "{input}");
+		}
+		assert_eq!(Die::D20.rolls_for_256_bits(), 60);
+		assert_eq!(Die::D6.rolls_for_256_bits(), 100);
+		assert_eq!(Die::D20.bits(10), 43);
+		assert_eq!(Die::D20.bits(100), 256);
+	}
+
+	#[test]
+	fn dice_hash() {
+		use super::{DiceHash, Die, ExtendableOutput, Shake256, Update, XofReader};
+		let rolls = b"1234501234";
+		let data = [
+			(
+				DiceHash::Sha256,
+				Some("22e3d20b00c18767597ac23466abd7a14511f1b42a30b270a2bb456bef9c5789"),
+			),
+			(
+				DiceHash::Shake256,
+				Some("50269e7d99cd38682b98a8673a27c5f56df143d93456b94a50aeedf3a89c9767"),
+			),
+			// too few rolls for 256 bits
+			(DiceHash::Raw, None),
+		];
+		for (hash, exp) in data {
+			let entropy = hash.entropy(Die::D6, rolls, b"");
+			assert_eq!(entropy.map(hex::encode).as_deref(), exp);
+		}
+		// shorter entropy, SHAKE256 output is prefix consistent
+		let mut long = [0; 32];
+		Shake256::default()
+			.chain(rolls)
+			.finalize_xof()
+			.read(&mut long);
+		let mut short = [0; 16];
+		Shake256::default()
+			.chain(rolls)
+			.finalize_xof()
+			.read(&mut short);
+		assert_eq!(
+			DiceHash::Shake256.entropy_bytes(Die::D6, rolls, b"", 16),
+			Some(short.to_vec())
+		);
+		assert_eq!(short, long[..16]);
+		let sha256 = DiceHash::Sha256.entropy(Die::D6, rolls, b"").unwrap();
+		assert_eq!(
+			DiceHash::Sha256.entropy_bytes(Die::D6, rolls, b"", 20),
+			Some(sha256[..20].to_vec())
+		);
+	}
+
+	#[test]
+	fn raw_rejection() {
+		use super::{DiceHash, Die};
+		// 4 D6 rolls reach 6^4 = 1296, 5 * 256 = 1280 is the largest multiple
+		// of 2^8 below it
+		let data = [
+			(Die::D6, &b"0000"[..], Some(vec![0])),
+			(Die::D6, b"5531", Some(vec![255])),
+			(Die::D6, b"5532", None),
+			(Die::D6, b"5555", None),
+			(Die::D6, b"555", None),
+			(Die::D20, &[0, 1, 19], Some(vec![39])),
+			(Die::D20, &[19, 19], None),
+		];
+		for (die, rolls, exp) in data {
+			assert_eq!(
+				DiceHash::Raw.entropy_bytes(die, rolls, b"", 1),
+				exp,
+				"{rolls:?}"
+			);
+		}
+		// 6^120 - 1 is above the largest multiple of 2^256 below 6^120
+		assert_eq!(DiceHash::Raw.entropy(Die::D6, &[b'5'; 120], b""), None);
+		assert_eq!(
+			DiceHash::Raw.entropy(Die::D6, &[b'0'; 120], b""),
+			Some([0; 32])
+		);
+	}
+
+	#[test]
+	fn raw_rolls() {
+		use super::{DiceHash, Die};
+		let data = [[0; 32], [0xff; 32], [0x5a; 32], {
+			let mut e = [0; 32];
+			e[31] = 7;
+			e
+		}];
+		for entropy in data {
+			for die in [Die::D6, Die::D20] {
+				let rolls = super::raw_rolls(die, &entropy);
+				assert_eq!(rolls.len(), die.rolls_for_256_bits());
+				assert_eq!(DiceHash::Raw.entropy(die, &rolls, b""), Some(entropy));
+			}
+		}
+		let mut entropy = [0; 32];
+		entropy[31] = 7;
+		let rolls = super::raw_rolls(Die::D6, &entropy);
+		assert_eq!(rolls[..98], [b'0'; 98]);
+		assert_eq!(rolls[98..], *b"11");
+		assert_eq!(super::raw_rolls(Die::D20, &entropy)[58..], [0, 7]);
+
+		// shorter mnemonics
+		for (len, d6, d20) in [(16, 50, 30), (20, 62, 38), (24, 75, 45), (28, 87, 52)] {
+			let entropy = [0xc3; 32];
+			for (die, count) in [(Die::D6, d6), (Die::D20, d20)] {
+				let rolls = super::raw_rolls(die, &entropy[..len]);
+				assert_eq!(rolls.len(), count);
+				assert_eq!(
+					DiceHash::Raw.entropy_bytes(die, &rolls, b"", len),
+					Some(entropy[..len].to_vec())
+				);
+			}
+		}
+
+		// through the text a user would enter
+		let text: String = super::raw_rolls(Die::D6, &[0xa7; 32])
+			.iter()
+			.map(|&r| if r == b'0' { '6' } else { r as char })
+			.collect();
+		let (rolls, invalid) = Die::D6.rolls(&text);
+		assert!(!invalid);
+		assert_eq!(
+			DiceHash::Raw.entropy(Die::D6, &rolls, b""),
+			Some([0xa7; 32])
+		);
+	}
+
+	#[test]
+	fn looks_like_text() {
+		let data = [
+			("correct horse battery staple", true),
+			("Satoshi's 1st block: 2009-01-03", true),
+			("1234561234561234", false),
+			("1 20 7, 13 19", false),
+			("12345 abc", false),
+			("", false),
+		];
+		for (input, exp) in data {
+			assert_eq!(super::looks_like_text(input), exp, "{input}");
+		}
+	}
+
+	#[test]
+	fn brainwallet_entropy() {
+		use super::BRAINWALLET_CONFIRMATION;
+		let text = "correct horse battery staple\n";
+		let data = [
+			("", false),
+			("yes", false),
+			(&BRAINWALLET_CONFIRMATION[1..], false),
+			(&BRAINWALLET_CONFIRMATION.to_uppercase(), false),
+			(BRAINWALLET_CONFIRMATION, true),
+			(&format!("{BRAINWALLET_CONFIRMATION}\n"), true),
+		];
+		for (confirmation, ok) in data {
+			let entropy = super::brainwallet_entropy(text, confirmation);
+			assert_eq!(entropy.is_some(), ok, "{confirmation}");
+		}
+		assert_eq!(
+			hex::encode(super::brainwallet_entropy(text, BRAINWALLET_CONFIRMATION).unwrap()),
+			"c4bbcb1fbec99d65bf59d85c8cb62ee2db963f0fe106f483d9afa73bd4e39a8a"
+		);
+	}
+
+	#[test]
+	fn low_quality() {
+		let data = [
+			("", false),
+			("1111111111", true),
+			("1212121212", true),
+			("1111111123", true),
+			("1111123456", false),
+			("3614522461", false),
+		];
+		for (input, exp) in data {
+			let (rolls, _) = super::dice_rolls(input);
+			assert_eq!(super::low_quality(&rolls), exp, "{input}");
+		}
+	}
+}
@@ -0,0 +1,229 @@
+use crate::bip39::{words_with_prefix, Mnemonic, MnemonicError};
+
+/// Candidates are offered once this many letters are typed.
+pub const MIN_PREFIX: usize = 2;
+/// Candidates selectable by number.
+pub const MAX_NUMBERED: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyEvent {
+	Char(char),
+	Tab,
+	Backspace,
+	Enter,
+}
+
+/// Word-by-word mnemonic entry restricted to the word list. Letters that
+/// match no word are ignored, Tab completes, digits pick a numbered
+/// candidate, Enter accepts an exact or unique match and Backspace on an
+/// empty word reopens the previous one.
+pub struct WordEntry {
+	count: usize,
+	words: Vec<&'static str>,
+	current: String,
+}
+
+impl WordEntry {
+	pub fn new(count: usize) -> Self {
+		Self {
+			count,
+			words: Vec::new(),
+			current: String::new(),
+		}
+	}
+
+	pub fn count(&self) -> usize {
+		self.count
+	}
+
+	pub fn words(&self) -> &[&'static str] {
+		&self.words
+	}
+
+	pub fn current(&self) -> &str {
+		&self.current
+	}
+
+	/// Words matching the current prefix, empty below `MIN_PREFIX` letters.
+	pub fn candidates(&self) -> &'static [&'static str] {
+		if self.current.len() < MIN_PREFIX {
+			return &[];
+		}
+		words_with_prefix(&self.current)
+	}
+
+	/// Handles a key, the mnemonic once the last word is accepted. After a
+	/// checksum error the words are kept so the user can go back.
+	pub fn handle(&mut self, event: KeyEvent) -> Option<Result<Mnemonic, MnemonicError>> {
+		match event {
+			KeyEvent::Char(c @ '1'..='9') => {
+				let i = c as usize - '1' as usize;
+				let candidates = self.candidates();
+				if candidates.len() <= MAX_NUMBERED && i < candidates.len() {
+					return self.accept(candidates[i]);
+				}
+			}
+			KeyEvent::Char(c) if c.is_ascii_alphabetic() && self.words.len() < self.count => {
+				let prefix = format!("{}{}", self.current, c.to_ascii_lowercase());
+				if !words_with_prefix(&prefix).is_empty() {
+					self.current = prefix;
+				}
+			}
+			KeyEvent::Char(_) => {}
+			KeyEvent::Tab => {
+				let candidates = words_with_prefix(&self.current);
+				match candidates {
+					[] => {}
+					[word] => return self.accept(word),
+					[first, .., last] if !self.current.is_empty() => {
+						let common = first
+							.chars()
+							.zip(last.chars())
+							.take_while(|(a, b)| a == b)
+							.count();
+						self.current = first[..common].to_string();
+					}
+					_ => {}
+				}
+			}
+			KeyEvent::Enter => {
+				let candidates = words_with_prefix(&self.current);
+				if self.current.is_empty() {
+					return None;
+				}
+				if let Some(word) = candidates.iter().find(|w| **w == self.current) {
+					return self.accept(word);
+				}
+				if let [word] = candidates {
+					return self.accept(word);
+				}
+			}
+			KeyEvent::Backspace => {
+				if self.current.pop().is_none() {
+					if let Some(word) = self.words.pop() {
+						self.current = word.to_string();
+					}
+				}
+			}
+		}
+		None
+	}
+
+	fn accept(&mut self, word: &'static str) -> Option<Result<Mnemonic, MnemonicError>> {
+		self.words.push(word);
+		self.current.clear();
+		(self.words.len() == self.count).then(|| Mnemonic::from_phrase(&self.words.join(" ")))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::KeyEvent::*;
+	use super::*;
+
+	fn typed(s: &str, then: &[KeyEvent]) -> Vec<KeyEvent> {
+		s.chars().map(Char).chain(then.iter().copied()).collect()
+	}
+
+	#[test]
+	fn keys() {
+		let data = [
+			// unique after a few letters
+			(typed("aba", &[Enter]), vec!["abandon"], ""),
+			(typed("zoo", &[Tab]), vec!["zoo"], ""),
+			(typed("zo", &[Tab]), vec![], "zo"),
+			// exact word that is also a prefix of others
+			(typed("act", &[Enter]), vec!["act"], ""),
+			// letters matching no word are dropped
+			(typed("abxq", &[]), vec![], "ab"),
+			// common prefix completion
+			(typed("abs", &[Tab]), vec![], "abs"),
+			(typed("ac", &[Tab]), vec![], "ac"),
+			(typed("abso", &[Tab]), vec!["absorb"], ""),
+			// numbered selection needs few enough candidates
+			(typed("abs3", &[]), vec!["abstract"], ""),
+			(typed("ab3", &[]), vec![], "ab"),
+			(typed("a1", &[]), vec![], "a"),
+			// going back to the previous word
+			(typed("zoo", &[Enter, Backspace]), vec![], "zoo"),
+			(
+				typed("zoo", &[Enter, Backspace, Backspace, Char('m')]),
+				vec![],
+				"zo",
+			),
+			(typed("ab", &[Enter]), vec![], "ab"),
+		];
+		for (events, words, current) in data {
+			let mut entry = WordEntry::new(24);
+			for event in &events {
+				assert!(entry.handle(*event).is_none());
+			}
+			assert_eq!(entry.words(), words, "{events:?}");
+			assert_eq!(entry.current(), current, "{events:?}");
+		}
+	}
+
+	#[test]
+	fn candidates() {
+		let mut entry = WordEntry::new(24);
+		entry.handle(Char('a'));
+		assert!(entry.candidates().is_empty());
+		entry.handle(Char('b'));
+		assert_eq!(entry.candidates().len(), 10);
+		entry.handle(Char('s'));
+		assert_eq!(
+			entry.candidates(),
+			["absent", "absorb", "abstract", "absurd"]
+		);
+	}
+
+	#[test]
+	fn mnemonic() {
+		let mut entry = WordEntry::new(24);
+		for _ in 0..23 {
+			for event in typed("aban", &[Tab]) {
+				assert!(entry.handle(event).is_none());
+			}
+		}
+		for event in typed("art", &[]) {
+			assert!(entry.handle(event).is_none());
+		}
+		let mnemonic = entry.handle(Enter).unwrap().unwrap();
+		assert_eq!(mnemonic.to_string(), ["abandon"; 23].join(" ") + " art");
+
+		// wrong checksum, then go back and fix the last word
+		entry.handle(Backspace);
+		assert_eq!(entry.current(), "art");
+		for _ in 0..3 {
+			entry.handle(Backspace);
+		}
+		for event in typed("zoo", &[]) {
+			entry.handle(event);
+		}
+		assert_eq!(
+			entry.handle(Enter).unwrap().err(),
+			Some(MnemonicError::ChecksumMismatch)
+		);
+		for event in [Backspace, Backspace, Backspace, Backspace] {
+			entry.handle(event);
+		}
+		for event in typed("art", &[]) {
+			entry.handle(event);
+		}
+		assert!(entry.handle(Enter).unwrap().is_ok());
+
+		let mut entry = WordEntry::new(12);
+		for _ in 0..11 {
+			for event in typed("aban", &[Tab]) {
+				assert!(entry.handle(event).is_none());
+			}
+		}
+		for event in typed("about", &[]) {
+			assert!(entry.handle(event).is_none());
+		}
+		let mnemonic = entry.handle(Enter).unwrap().unwrap();
+		assert_eq!(mnemonic.to_string(), ["abandon"; 11].join(" ") + " about");
+		assert!(entry.handle(Char('a')).is_none());
+		assert_eq!(entry.current(), "");
+	}
+}
@@ -1,21 +1,100 @@
-use crate::bip32::ExtKey;
+use crate::bip32::ChildNumber::{self, Hardened, Normal};
+use crate::bip32::{format_path, parse_path, Bip32Error, ExtKey};
+use crate::bip39::Mnemonic;
+use crate::slip44;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::{PublicKey, SecretKey};
 use sha3::{Digest, Keccak256};
 use std::fmt;
 use std::fmt::Write;
+use std::ops::Range;
+use std::str::FromStr;
 
+const BASE36: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(PartialEq)]
+pub enum AddressError {
+	InvalidLength,
+	InvalidHex,
+	ChecksumMismatch,
+}
+
+impl fmt::Debug for AddressError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidLength => f.write_str("invalid length"),
+			Self::InvalidHex => f.write_str("invalid hex"),
+			Self::ChecksumMismatch => f.write_str("checksum mismatch"),
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Address([u8; 20]);
 
+impl Address {
+	pub fn from_bytes(bytes: [u8; 20]) -> Self {
+		Self(bytes)
+	}
+
+	/// Parses 40 hex digits with or without `0x`, ignoring the checksum. Use
+	/// `parse` to reject mixed case addresses with a bad checksum.
+	pub fn from_hex(s: &str) -> Result<Self, AddressError> {
+		let s = s.trim();
+		let hex = s.strip_prefix("0x").unwrap_or(s);
+		if hex.len() != 40 {
+			return Err(AddressError::InvalidLength);
+		}
+		let mut address = Address([0; 20]);
+		hex::decode_to_slice(hex, &mut address.0).map_err(|_| AddressError::InvalidHex)?;
+		Ok(address)
+	}
+
+	/// Direct ICAP form (`XE` + mod-97 check + 30 base36 digits), only
+	/// available for addresses small enough to fit in 30 digits.
+	pub fn to_iban(&self) -> Option<String> {
+		let mut num = self.0;
+		let mut digits = Vec::new();
+		while num.iter().any(|&v| v != 0) {
+			let mut rem = 0;
+			for v in num.iter_mut() {
+				let acc = (rem << 8) | *v as u32;
+				*v = (acc / 36) as u8;
+				rem = acc % 36;
+			}
+			digits.push(BASE36[rem as usize]);
+		}
+		if digits.len() > 30 {
+			return None;
+		}
+		digits.resize(30, b'0');
+		digits.reverse();
+		let bban = String::from_utf8(digits).unwrap();
+
+		let rem = format!("{bban}XE00").chars().fold(0, |acc, c| {
+			let v = c.to_digit(36).unwrap();
+			let shift = if v < 10 { 10 } else { 100 };
+			(acc * shift + v) % 97
+		});
+		Some(format!("XE{:02}{bban}", 98 - rem))
+	}
+}
+
 impl AsRef<[u8]> for Address {
 	fn as_ref(&self) -> &[u8] {
 		&self.0
 	}
 }
 
+impl fmt::Debug for Address {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Address({self})")
+	}
+}
+
 impl fmt::Display for Address {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		let buf = hex::encode(&self.0);
+		let buf = hex::encode(self.0);
 		let mut hasher = Keccak256::new();
 		hasher.update(&buf);
 		let hash = hex::encode(hasher.finalize());
@@ -30,6 +109,146 @@ impl fmt::Display for Address {
 	}
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+	LedgerLive,
+	Bip44,
+	LedgerLegacy,
+}
+
+impl Scheme {
+	pub const ALL: [Scheme; 3] = [Self::LedgerLive, Self::Bip44, Self::LedgerLegacy];
+
+	pub fn path(&self, i: u32) -> Vec<ChildNumber> {
+		self.account_path(0, i)
+	}
+
+	/// Path of the `i`th address of an account. Ledger Live has one address
+	/// per account, so addresses there count on from the account and can leave
+	/// the hardened range, which no key derives.
+	pub fn account_path(&self, account: u32, i: u32) -> Vec<ChildNumber> {
+		match self {
+			Self::LedgerLive => vec![
+				Hardened(44),
+				Hardened(slip44::ETH),
+				Hardened(account.saturating_add(i)),
+				Normal(0),
+				Normal(0),
+			],
+			Self::Bip44 => vec![
+				Hardened(44),
+				Hardened(slip44::ETH),
+				Hardened(account),
+				Normal(0),
+				Normal(i),
+			],
+			Self::LedgerLegacy => vec![
+				Hardened(44),
+				Hardened(slip44::ETH),
+				Hardened(account),
+				Normal(i),
+			],
+		}
+	}
+}
+
+impl fmt::Display for Scheme {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::LedgerLive => "44'/60'/X'/0/0 (Ledger Live)",
+			Self::Bip44 => "44'/60'/0'/0/X (BIP44, MetaMask)",
+			Self::LedgerLegacy => "44'/60'/0'/X (Ledger legacy, MEW)",
+		})
+	}
+}
+
+/// Addresses of an account at `indices`. An index whose key cannot be
+/// derived maps to `None` instead of ending the batch.
+pub fn addresses(
+	root: &ExtKey<SecretKey>,
+	scheme: Scheme,
+	account: u32,
+	indices: Range<u32>,
+) -> Vec<(u32, Option<Address>)> {
+	indices
+		.map(|i| {
+			let key = root.derive_path(&scheme.account_path(account, i));
+			(i, key.map(|key| key.address()))
+		})
+		.collect()
+}
+
+/// Address of the key at `path` of a mnemonic, e.g. `m/44'/60'/0'/0/0`.
+pub fn address_at(
+	mnemonic: &Mnemonic,
+	passphrase: &str,
+	path: &str,
+) -> Result<Address, Bip32Error> {
+	let path = parse_path(path)?;
+	let root = mnemonic.root_key(passphrase)?;
+	let key = root.derive_path(&path).ok_or(Bip32Error::InvalidKey)?;
+	Ok(key.address())
+}
+
+pub const COMPARE_HEADER: [&str; 5] = [
+	"index",
+	"ledger_live_path",
+	"ledger_live_address",
+	"metamask_path",
+	"metamask_address",
+];
+
+/// Ledger Live and MetaMask paths and addresses of the same index on one row,
+/// columns as in `COMPARE_HEADER`. Underived addresses are `-`.
+pub fn compare_ledger_metamask(root: &ExtKey<SecretKey>, indices: Range<u32>) -> Vec<Vec<String>> {
+	let ledger = addresses(root, Scheme::LedgerLive, 0, indices.clone());
+	let metamask = addresses(root, Scheme::Bip44, 0, indices);
+	ledger
+		.into_iter()
+		.zip(metamask)
+		.map(|((i, ledger), (_, metamask))| {
+			let address = |a: Option<Address>| a.map_or("-".to_string(), |a| a.to_string());
+			vec![
+				i.to_string(),
+				format_path(&Scheme::LedgerLive.path(i)),
+				address(ledger),
+				format_path(&Scheme::Bip44.path(i)),
+				address(metamask),
+			]
+		})
+		.collect()
+}
+
+/// EIP-681 payment request; a zero value yields a plain address URI.
+pub fn payment_uri(to: &Address, value_wei: u128, chain_id: Option<u64>) -> String {
+	let mut uri = format!("ethereum:{to}");
+	if let Some(chain_id) = chain_id {
+		write!(uri, "@{chain_id}").unwrap();
+	}
+	if value_wei > 0 {
+		write!(uri, "?value={value_wei}").unwrap();
+	}
+	uri
+}
+
+impl FromStr for Address {
+	type Err = AddressError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let address = Address::from_hex(s)?;
+		let s = s.trim();
+		let hex = s.strip_prefix("0x").unwrap_or(s);
+
+		// all lower or all upper case addresses carry no checksum
+		let mixed = hex.contains(|c: char| c.is_ascii_lowercase())
+			&& hex.contains(|c: char| c.is_ascii_uppercase());
+		if mixed && address.to_string()[2..] != *hex {
+			return Err(AddressError::ChecksumMismatch);
+		}
+		Ok(address)
+	}
+}
+
 pub trait ToAddress {
 	fn address(&self) -> Address;
 }
@@ -71,7 +290,7 @@ mod tests {
 		.unwrap()
 		.address();
 		assert_eq!(
-			hex::encode(&addr),
+			hex::encode(addr),
 			"001d3f1ef827552ae1114027bd3ecf1f086ba0f9"
 		);
 		assert_eq!(
@@ -80,6 +299,157 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn account_path() {
+		let data = [
+			(Scheme::LedgerLive, 0, 0, "m/44'/60'/0'/0/0"),
+			(Scheme::LedgerLive, 1, 2, "m/44'/60'/3'/0/0"),
+			(Scheme::Bip44, 0, 2, "m/44'/60'/0'/0/2"),
+			(Scheme::Bip44, 1, 2, "m/44'/60'/1'/0/2"),
+			(Scheme::LedgerLegacy, 1, 2, "m/44'/60'/1'/2"),
+		];
+		for (scheme, account, i, path) in data {
+			assert_eq!(format_path(&scheme.account_path(account, i)), path);
+		}
+		assert_eq!(Scheme::Bip44.path(2), Scheme::Bip44.account_path(0, 2));
+	}
+
+	#[test]
+	fn addresses() {
+		let root = ExtKey::<SecretKey>::from_seed([1; 64]).unwrap();
+		let out = super::addresses(&root, Scheme::Bip44, 0, 5..8);
+		assert_eq!(out.len(), 3);
+		for (i, address) in out {
+			let key = root.derive_path(&Scheme::Bip44.path(i)).unwrap();
+			assert_eq!(address, Some(key.address()));
+		}
+
+		// Ledger Live indices past the hardened range have no key
+		let account = (1 << 31) - 2;
+		let out = super::addresses(&root, Scheme::LedgerLive, account, 0..4);
+		let derived: Vec<_> = out.iter().map(|(i, a)| (*i, a.is_some())).collect();
+		assert_eq!(derived, [(0, true), (1, true), (2, false), (3, false)]);
+		let out = super::addresses(&root, Scheme::LedgerLive, u32::MAX, 0..2);
+		assert!(out.iter().all(|(_, a)| a.is_none()));
+	}
+
+	#[test]
+	fn address_at() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let data = [
+			(
+				"",
+				"m/44'/60'/0'/0/0",
+				"0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+			),
+			(
+				"",
+				"m/44'/60'/1'/0/0",
+				"0x78839F6054d7ed13918bAe0473BA31b1Ca9D7265",
+			),
+			(
+				"",
+				"m/44'/60'/0'/0/1",
+				"0x6Fac4D18c912343BF86fa7049364Dd4E424Ab9C0",
+			),
+		];
+		for (passphrase, path, exp) in data {
+			let address = super::address_at(&mnemonic, passphrase, path).unwrap();
+			assert_eq!(address.to_string(), exp, "{path}");
+		}
+
+		let root = mnemonic.root_key("TREZOR").unwrap();
+		let key = root.derive_path(&Scheme::LedgerLive.path(0)).unwrap();
+		assert_eq!(
+			super::address_at(&mnemonic, "TREZOR", "m/44'/60'/0'/0/0"),
+			Ok(key.address())
+		);
+
+		let data = [
+			("44'/60'/0'/0/0", Bip32Error::InvalidPath),
+			("m/44'/60'/x'/0/0", Bip32Error::InvalidChildNumber),
+		];
+		for (path, err) in data {
+			assert_eq!(super::address_at(&mnemonic, "", path), Err(err));
+		}
+	}
+
+	#[test]
+	fn compare_ledger_metamask() {
+		let root = crate::bip39::Mnemonic::from_entropy([0; 16])
+			.root_key("")
+			.unwrap();
+		let rows = super::compare_ledger_metamask(&root, 0..2);
+		let exp = [
+			[
+				"0",
+				"m/44'/60'/0'/0/0",
+				"0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+				"m/44'/60'/0'/0/0",
+				"0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+			],
+			[
+				"1",
+				"m/44'/60'/1'/0/0",
+				"0x78839F6054d7ed13918bAe0473BA31b1Ca9D7265",
+				"m/44'/60'/0'/0/1",
+				"0x6Fac4D18c912343BF86fa7049364Dd4E424Ab9C0",
+			],
+		];
+		assert_eq!(rows, exp);
+	}
+
+	#[test]
+	fn iban() {
+		let data = [
+			(
+				"00c5496aee77c1ba1f0854206a26dda82a81d6d8",
+				Some("XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS"),
+			),
+			(
+				"0000000000000000000000000000000000000000",
+				Some("XE50000000000000000000000000000000"),
+			),
+			("11c5496aee77c1ba1f0854206a26dda82a81d6d8", None),
+		];
+
+		for (hex, exp) in data {
+			let address = Address::from_hex(hex).unwrap();
+			assert_eq!(address.to_iban().as_deref(), exp);
+		}
+	}
+
+	#[test]
+	fn payment() {
+		let to = Address::from_hex("fb6916095ca1df60bb79ce92ce3ea74c37c5d359").unwrap();
+		let data = [
+			(
+				0,
+				None,
+				"ethereum:0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+			),
+			(
+				0,
+				Some(1),
+				"ethereum:0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359@1",
+			),
+			(
+				2014000000000000000,
+				None,
+				"ethereum:0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359?value=2014000000000000000",
+			),
+			(
+				1,
+				Some(137),
+				"ethereum:0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359@137?value=1",
+			),
+		];
+
+		for (value, chain_id, exp) in data {
+			assert_eq!(payment_uri(&to, value, chain_id), exp);
+		}
+	}
+
 	#[test]
 	fn checksum() {
 		let data = [
@@ -94,9 +464,64 @@ mod tests {
 		];
 
 		for exp in data {
-			let mut address = Address([0; 20]);
-			hex::decode_to_slice(&exp[2..], &mut address.0).unwrap();
+			let address = Address::from_hex(&exp[2..]).unwrap();
 			assert_eq!(address.to_string(), exp);
+			assert!(exp.parse::<Address>() == Ok(address));
+		}
+	}
+
+	#[test]
+	fn from_hex() {
+		use AddressError::*;
+		let exp = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+		let address = Address::from_hex(exp).unwrap();
+		let data = [
+			(exp.to_string(), Ok(address)),
+			(exp[2..].to_string(), Ok(address)),
+			(exp.to_lowercase(), Ok(address)),
+			(
+				format!(" {}\n", exp.to_uppercase().replace("0X", "0x")),
+				Ok(address),
+			),
+			// checksum is not enforced
+			(
+				"0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+				Ok(address),
+			),
+			(exp[..41].to_string(), Err(InvalidLength)),
+			(exp.replace('a', "g"), Err(InvalidHex)),
+		];
+		for (s, res) in data {
+			assert_eq!(Address::from_hex(&s), res, "{s}");
+		}
+		let copy = address;
+		assert_eq!(Address::from_hex(&copy.to_string()), Ok(address));
+		assert_eq!(Address::from_bytes(*b"\x5a\xae\xb6\x05\x3f\x3e\x94\xc9\xb9\xa0\x9f\x33\x66\x94\x35\xe7\xef\x1b\xea\xed"), address);
+		assert_eq!(format!("{address:?}"), format!("Address({exp})"));
+	}
+
+	#[test]
+	fn parse() {
+		use AddressError::*;
+		let exp = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+		for s in [
+			exp,
+			"5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+			"0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+			"0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+		] {
+			assert_eq!(s.parse::<Address>().unwrap().to_string(), exp);
+		}
+		let data = [
+			(
+				"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+				ChecksumMismatch,
+			),
+			("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA", InvalidLength),
+			("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg", InvalidHex),
+		];
+		for (s, err) in data {
+			assert_eq!(s.parse::<Address>().err(), Some(err));
 		}
 	}
 }
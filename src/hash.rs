@@ -0,0 +1,162 @@
+use crate::memory::Locked;
+use blake2::digest::consts::U28;
+use blake2::Blake2b;
+use hmac::digest::generic_array::GenericArray;
+use hmac::digest::FixedOutput;
+use hmac::{Hmac, Mac};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use std::io;
+
+pub fn sha256<T: AsRef<[u8]>>(data: T) -> [u8; 32] {
+	Sha256::digest(data).into()
+}
+
+pub fn hash160<T: AsRef<[u8]>>(data: T) -> [u8; 20] {
+	Ripemd160::digest(sha256(data)).into()
+}
+
+/// BLAKE2b with a 224-bit digest, Cardano's key hash.
+pub fn blake2b224<T: AsRef<[u8]>>(data: T) -> [u8; 28] {
+	Blake2b::<U28>::digest(data).into()
+}
+
+/// HMAC-SHA512 fed in pieces, e.g. with `io::copy` from a file.
+pub struct HmacSha512(Hmac<Sha512>);
+
+impl HmacSha512 {
+	pub fn new(key: &[u8]) -> Self {
+		Self(Hmac::new_from_slice(key).unwrap())
+	}
+
+	pub fn update(&mut self, data: &[u8]) {
+		self.0.update(data);
+	}
+
+	pub fn finalize(self) -> [u8; 64] {
+		self.0.finalize_fixed().into()
+	}
+
+	/// Output in locked memory, zeroed on drop, for private key material.
+	pub fn finalize_locked(self) -> Locked<64> {
+		let mut out = Locked::new();
+		self.0
+			.finalize_into(GenericArray::from_mut_slice(&mut out[..]));
+		out
+	}
+}
+
+impl io::Write for HmacSha512 {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+	let mut hmac = HmacSha512::new(key);
+	hmac.update(data);
+	hmac.finalize()
+}
+
+/// RFC 5869 HKDF-SHA256 output of `len` bytes, `None` above 255 blocks.
+pub fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Option<Vec<u8>> {
+	if len > 255 * 32 {
+		return None;
+	}
+	let mut extract = Hmac::<Sha256>::new_from_slice(salt).unwrap();
+	extract.update(ikm);
+	let prk = extract.finalize().into_bytes();
+
+	let mut out = Vec::with_capacity(len);
+	let mut block = vec![];
+	for i in 1..=len.div_ceil(32) as u8 {
+		let mut expand = Hmac::<Sha256>::new_from_slice(&prk).unwrap();
+		expand.update(&block);
+		expand.update(info);
+		expand.update(&[i]);
+		block = expand.finalize().into_bytes().to_vec();
+		out.extend(&block);
+	}
+	out.truncate(len);
+	Some(out)
+}
+
+/// CRC-32 (IEEE), as used by Monero's mnemonic checksum.
+pub fn crc32<T: AsRef<[u8]>>(data: T) -> u32 {
+	!data.as_ref().iter().fold(!0, |crc, &b| {
+		(0..8).fold(crc ^ b as u32, |crc, _| {
+			(crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg())
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn hash160() {
+		let pk = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+			.unwrap();
+		assert_eq!(
+			hex::encode(super::hash160(pk)),
+			"751e76e8199196d454941c45d1b3a323f1433bd6"
+		);
+	}
+
+	#[test]
+	fn hmac_sha512() {
+		// RFC 4231 test case 2
+		let exp = "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737";
+		let data = b"what do ya want for nothing?";
+		assert_eq!(hex::encode(super::hmac_sha512(b"Jefe", data)), exp);
+
+		let mut hmac = super::HmacSha512::new(b"Jefe");
+		for chunk in data.chunks(5) {
+			hmac.update(chunk);
+		}
+		assert_eq!(hex::encode(hmac.finalize()), exp);
+
+		let mut hmac = super::HmacSha512::new(b"Jefe");
+		std::io::copy(&mut &data[..], &mut hmac).unwrap();
+		assert_eq!(hex::encode(*hmac.finalize_locked()), exp);
+	}
+
+	#[test]
+	fn hkdf_sha256() {
+		// RFC 5869 test cases 1 and 3
+		let data = [
+			(
+				"000102030405060708090a0b0c",
+				"f0f1f2f3f4f5f6f7f8f9",
+				"3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+			),
+			(
+				"",
+				"",
+				"8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+			),
+		];
+		for (salt, info, exp) in data {
+			let salt = hex::decode(salt).unwrap();
+			let info = hex::decode(info).unwrap();
+			let okm = super::hkdf_sha256(&[0x0b; 22], &salt, &info, 42).unwrap();
+			assert_eq!(hex::encode(okm), exp);
+		}
+		assert_eq!(super::hkdf_sha256(b"", b"", b"", 0), Some(vec![]));
+		assert_eq!(
+			super::hkdf_sha256(b"", b"", b"", 255 * 32).unwrap().len(),
+			255 * 32
+		);
+		assert!(super::hkdf_sha256(b"", b"", b"", 255 * 32 + 1).is_none());
+	}
+
+	#[test]
+	fn crc32() {
+		assert_eq!(super::crc32(""), 0);
+		assert_eq!(super::crc32("123456789"), 0xcbf43926);
+	}
+}
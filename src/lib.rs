@@ -0,0 +1,39 @@
+pub mod accounts;
+pub mod age;
+pub mod audit;
+pub mod avax;
+pub mod backup;
+pub mod base58;
+pub mod bech32;
+pub mod bip32;
+pub mod bip340;
+pub mod bip39;
+pub mod bip85;
+pub mod blocklist;
+pub mod btc;
+pub mod cardano;
+pub mod check;
+pub mod console;
+pub mod electrum;
+pub mod entropy;
+pub mod entry;
+pub mod eth;
+pub mod hash;
+pub mod memory;
+pub mod monero;
+pub mod multisig;
+pub mod openpgp;
+pub mod paper;
+pub mod portfolio;
+pub mod scan;
+pub mod seedxor;
+pub mod session;
+pub mod slip10;
+pub mod slip44;
+pub mod strkey;
+pub mod suggest;
+pub mod summary;
+pub mod util;
+pub mod vectors;
+pub mod verify;
+pub mod wif;
@@ -1,51 +1,439 @@
-use crate::bip32::HARDENED_OFFSET;
-use crate::bip39::Mnemonic;
-use crate::bip85::Bip85;
-use crate::eth::ToAddress;
-use sha2::{Digest, Sha256};
-use std::io::{stdin, stdout, Write};
-
-mod base58;
-mod bip32;
-mod bip39;
-mod bip85;
-mod eth;
-mod util;
+use k256::{PublicKey, SecretKey};
+use keymaker::accounts::standard_accounts;
+use keymaker::age::{self, AgeIdentity};
+use keymaker::audit::{AuditLog, Event, Fingerprint};
+use keymaker::avax::{self, Chain};
+use keymaker::backup;
+use keymaker::bip32::ChildNumber::Hardened;
+use keymaker::bip32::Key;
+use keymaker::bip32::{self, format_path, Bip32Error, ChildNumber, ExtKey};
+use keymaker::bip39::{
+	passphrase_variants, words_with_prefix, Language, Mnemonic, MnemonicError, WORD_COUNTS,
+};
+use keymaker::bip85::{self, Bip85, Bip85App};
+use keymaker::blocklist;
+use keymaker::btc::{self, AddressType, KeyOrigin};
+use keymaker::cardano::{self, IcarusKey};
+use keymaker::check;
+use keymaker::console::{is_quit, FileSink, InputSource, OutputSink, Stdout};
+use keymaker::electrum::{self, ElectrumSeed, SeedType};
+use keymaker::entropy::{self, low_quality, DiceHash, Die};
+use keymaker::entry::{KeyEvent, WordEntry, MAX_NUMBERED};
+use keymaker::eth::{self, Address, Scheme, ToAddress};
+use keymaker::memory::{self, Locked};
+use keymaker::monero::{self, Keys};
+use keymaker::multisig::{Cosigner, Multisig, ScriptType};
+use keymaker::openpgp::{self, OpenPgpKey};
+use keymaker::paper;
+use keymaker::portfolio::{self, Coin};
+use keymaker::scan::{scan, DEFAULT_GAP};
+use keymaker::seedxor::{self, MAX_PARTS};
+use keymaker::session::Session;
+use keymaker::slip10::Ed25519Key;
+use keymaker::slip44;
+use keymaker::strkey::{self, Version};
+use keymaker::suggest::{self, Finding};
+use keymaker::summary;
+use keymaker::util::{self, ExportFormat, Secret};
+use keymaker::verify::{verify, Artifact, Expectation};
+use keymaker::wif::ImportedKey;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{stdin, stdout, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use unicode_normalization::UnicodeNormalization;
+
+/// Set by `--timeout`, only enforced once a secret has been displayed.
+static TIMEOUT: OnceLock<Duration> = OnceLock::new();
+static SECRET_SHOWN: AtomicBool = AtomicBool::new(false);
+/// Set by `--clear-on-exit`, `exit` clears like `ClearOnExit` does.
+static CLEAR_ON_EXIT: AtomicBool = AtomicBool::new(false);
+/// Set by `--d20`, D6 otherwise.
+static DIE: OnceLock<Die> = OnceLock::new();
+/// Set by `--copy`, requires the `clipboard` feature.
+static COPY: AtomicBool = AtomicBool::new(false);
+/// Set by `--dice-hash`, SHA-256 otherwise.
+static DICE_HASH: OnceLock<DiceHash> = OnceLock::new();
+/// Set by `--word-entry`, mnemonics are typed a word at a time with completion.
+static WORD_ENTRY: AtomicBool = AtomicBool::new(false);
+/// Set by `--guided-dice`, reads rolls line by line with a running count.
+static GUIDED_DICE: AtomicBool = AtomicBool::new(false);
+/// Set once text was hashed instead of dice rolls.
+static WEAK_ENTROPY: AtomicBool = AtomicBool::new(false);
+/// Set by `--session`, the session file, its password once known and its
+/// contents once created or resumed.
+static SESSION: Mutex<Option<SessionFile>> = Mutex::new(None);
+/// Set by `--audit-log`, non-secret events are appended to it.
+static AUDIT: Mutex<Option<AuditLog>> = Mutex::new(None);
+/// Set by `--check-words`, long public strings are followed by check words.
+static CHECK_WORDS: AtomicBool = AtomicBool::new(false);
+/// Set by `--passphrase-env` or `--passphrase-file`, the BIP39 passphrase is
+/// read from there instead of a prompt.
+static PASSPHRASE: OnceLock<PassphraseSource> = OnceLock::new();
+/// Set by `--dry-run`, export files are shown instead of written.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+/// Set by `--strict-derivation`, unhardened derivations below an account need
+/// confirmation.
+static STRICT_DERIVATION: AtomicBool = AtomicBool::new(false);
+/// Set by `--show-root-key`, print_mnemonic offers to reveal the xprv.
+static SHOW_ROOT_KEY: AtomicBool = AtomicBool::new(false);
+/// Set by `--strict-check`, the recovery check reports every position, not only
+/// pass or fail.
+static STRICT_CHECK: AtomicBool = AtomicBool::new(false);
+/// Set by `--mnemonic-format`, otherwise columns on a terminal and a single
+/// line when output is redirected.
+static MNEMONIC_FORMAT: OnceLock<MnemonicFormat> = OnceLock::new();
+
+struct SessionFile {
+	path: String,
+	password: Option<String>,
+	session: Option<Session>,
+}
+
+enum PassphraseSource {
+	/// Read once at startup, the variable is then removed.
+	Env(Secret<String>),
+	File(String),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum MnemonicFormat {
+	Columns,
+	Line,
+	Numbered,
+	Grid,
+}
 
 fn main() {
+	memory::disable_core_dumps();
+	set_passphrase_source();
+	if std::env::args().nth(1).as_deref() == Some("summary") {
+		summary();
+		return;
+	}
+	if std::env::args().nth(1).as_deref() == Some("vectors") {
+		vectors();
+		return;
+	}
+	if std::env::args().nth(1).as_deref() == Some("backup") {
+		backup();
+		return;
+	}
+	if std::env::args().nth(1).as_deref() == Some("restore") {
+		restore();
+		return;
+	}
+	let confirm = !std::env::args().any(|a| a == "--no-confirm");
+	let _clear = std::env::args()
+		.any(|a| a == "--clear-on-exit")
+		.then(ClearOnExit::new);
+	CLEAR_ON_EXIT.store(_clear.is_some(), Ordering::Relaxed);
+	let mut timeout = std::env::args().skip_while(|a| a != "--timeout");
+	if timeout.next().is_some() {
+		let secs = timeout
+			.next()
+			.and_then(|s| s.parse().ok())
+			.expect("--timeout expects a number of seconds");
+		TIMEOUT.set(Duration::from_secs(secs)).unwrap();
+	}
+	if std::env::args().any(|a| a == "--d20") {
+		DIE.set(Die::D20).unwrap();
+	}
+	let mut dice_hash = std::env::args().skip_while(|a| a != "--dice-hash");
+	if dice_hash.next().is_some() {
+		let hash = match dice_hash.next().as_deref() {
+			Some("sha256") => DiceHash::Sha256,
+			Some("shake256") => DiceHash::Shake256,
+			Some("raw") => DiceHash::Raw,
+			_ => panic!("--dice-hash expects sha256, shake256 or raw"),
+		};
+		DICE_HASH.set(hash).unwrap();
+	}
+	let mut mnemonic_format = std::env::args().skip_while(|a| a != "--mnemonic-format");
+	if mnemonic_format.next().is_some() {
+		let format = match mnemonic_format.next().as_deref() {
+			Some("columns") => MnemonicFormat::Columns,
+			Some("line") => MnemonicFormat::Line,
+			Some("numbered") => MnemonicFormat::Numbered,
+			Some("grid") => MnemonicFormat::Grid,
+			_ => panic!("--mnemonic-format expects columns, line, numbered or grid"),
+		};
+		MNEMONIC_FORMAT.set(format).unwrap();
+	}
+	if std::env::args().any(|a| a == "--word-entry") {
+		if cfg!(unix) {
+			WORD_ENTRY.store(true, Ordering::Relaxed);
+		} else {
+			println!("Word entry needs a unix terminal, ignoring --word-entry");
+		}
+	}
+	if std::env::args().any(|a| a == "--guided-dice") {
+		GUIDED_DICE.store(true, Ordering::Relaxed);
+	}
+	if let Some(path) = std::env::args().skip_while(|a| a != "--audit-log").nth(1) {
+		let log = AuditLog::open(&path).expect("Cannot open audit log");
+		*AUDIT.lock().unwrap() = Some(log);
+	}
+	if std::env::args().any(|a| a == "--check-words") {
+		CHECK_WORDS.store(true, Ordering::Relaxed);
+	}
+	if std::env::args().any(|a| a == "--dry-run") {
+		DRY_RUN.store(true, Ordering::Relaxed);
+	}
+	if std::env::args().any(|a| a == "--strict-derivation") {
+		STRICT_DERIVATION.store(true, Ordering::Relaxed);
+	}
+	if std::env::args().any(|a| a == "--show-root-key") {
+		SHOW_ROOT_KEY.store(true, Ordering::Relaxed);
+	}
+	if std::env::args().any(|a| a == "--strict-check") {
+		STRICT_CHECK.store(true, Ordering::Relaxed);
+	}
+	if std::env::args().any(|a| a == "--copy") {
+		if cfg!(feature = "clipboard") {
+			COPY.store(true, Ordering::Relaxed);
+		} else {
+			println!("Built without the clipboard feature, ignoring --copy");
+		}
+	}
+
+	if let Some(path) = std::env::args().skip_while(|a| a != "--session").nth(1) {
+		resume_session(path);
+	}
+	if let Some(path) = std::env::args().skip_while(|a| a != "--paths-file").nth(1) {
+		derive_paths_file(&path);
+		return;
+	}
+
 	println!("Keymaker {}", env!("CARGO_PKG_VERSION"));
+	if !Locked::<64>::new().is_locked() {
+		println!("Warning: cannot lock memory, secrets may be swapped to disk (see ulimit -l)");
+	}
 	println!();
 	println!("Choose option:");
 	println!(" 1) Generate mnemonic from dice rolls");
 	println!(" 2) Derive child mnemonics from mnemonic");
-	println!(" 3) Derive ETH addresses from mnemonic");
+	println!(" 3) Derive ETH addresses from mnemonic or xprv");
+	println!(" 4) Verify mnemonic against known xpubs/addresses");
+	println!(" 5) Generate mnemonic from hex entropy");
+	println!(" 6) Scan ETH addresses against an address list");
+	println!(" 7) Create multisig wallet");
+	println!(" 8) Generate Electrum segwit seed from dice rolls");
+	println!(" 9) Derive addresses from Electrum seed");
+	println!("10) Derive Monero wallet from mnemonic");
+	println!("11) Derive Stellar accounts from mnemonic");
+	println!("12) Derive Cardano addresses from mnemonic");
+	println!("13) Derive Avalanche addresses from mnemonic");
+	println!("14) Export account xpubs for watch-only wallets");
+	println!("15) Inspect extended key");
+	println!("16) Compare passphrase wallets of a mnemonic");
+	println!("17) List BTC receive and change addresses of an account xpub");
+	println!("18) Translate mnemonic to another language");
+	println!("19) Export raw BIP39 seed of mnemonic");
+	println!("20) Check a mnemonic backup without showing keys");
+	println!("21) Express mnemonic as raw dice rolls");
+	println!("22) Export a range of child mnemonics to a file");
+	println!("23) Find the BIP85 index of a child mnemonic");
+	println!("24) Compare Ledger Live and MetaMask ETH addresses");
+	println!("25) Compare passphrase normalizations of hardware wallets");
+	println!("26) Derive BIP85 RSA key from mnemonic");
+	println!("27) Print portfolio map of mnemonic");
+	println!("28) Inspect a single private key (hex or WIF)");
+	println!("29) Export a printable paper wallet sheet");
+	println!("30) Split or combine Coldcard Seed XOR parts");
+	println!("31) Derive a labeled secret (API token, site secret) from mnemonic");
+	println!("32) Rehearse a hardware wallet recovery check");
+	println!("33) Derive OpenPGP (GnuPG) ed25519 key from mnemonic");
+	println!("34) Derive age encryption identity from mnemonic");
+	println!("35) Derive addresses of a registered coin from mnemonic");
+	println!();
+	println!("Enter q at any prompt to quit");
 
 	print!("Choice: ");
 	stdout().flush().unwrap();
 
 	let mut input = String::new();
-	stdin().read_line(&mut input).unwrap();
+	read_line(&mut input);
+	if let Ok(option) = input.trim().parse() {
+		audit(|| Event::Command(option));
+	}
 
 	println!();
 	println!();
 
 	match input.trim_end() {
-		"1" => {
-			let mnemonic = dice();
-			print_mnemonic(&mnemonic);
-			print_child_mnemonics(&mnemonic);
-		}
+		"1" => generate(confirm, &mut Terminal, &mut Stdout),
 		"2" => {
 			println!("Derive child mnemonics from mnemonic");
-			let mnemonic = prompt_mnemonic();
-			print_mnemonic(&mnemonic);
-			print_child_mnemonics(&mnemonic);
+			derive_children(&mut Terminal, &mut Stdout);
 		}
 		"3" => {
-			println!("Derive ETH addresses from mnemonic");
+			println!("Derive ETH addresses from mnemonic or xprv");
+			eth_addresses(&mut Terminal, &mut Stdout);
+		}
+		"4" => {
+			println!("Verify mnemonic against known xpubs/addresses");
+			let mnemonic = prompt_mnemonic();
+			verify_mnemonic(&mnemonic);
+		}
+		"5" => {
+			let mnemonic = hex_entropy();
+			print_mnemonic(&mnemonic, &mut Terminal, &mut Stdout);
+			copy(&mnemonic.to_string(), true);
+			print_child_mnemonics(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"6" => {
+			println!("Scan ETH addresses against an address list");
+			let mnemonic = prompt_mnemonic();
+			scan_addresses(&mnemonic);
+		}
+		"7" => {
+			println!("Create multisig wallet");
+			multisig_wallet();
+		}
+		"8" => {
+			println!("Generate Electrum segwit seed from dice rolls");
+			let Some(entropy) = dice_entropy(32, &mut Terminal, &mut Stdout) else {
+				exit(1);
+			};
+			let seed = ElectrumSeed::from_entropy(entropy, SeedType::Segwit);
+			print_electrum(&seed);
+			copy(&seed.to_string(), true);
+		}
+		"9" => {
+			println!("Derive addresses from Electrum seed");
+			print!("Enter Electrum seed: ");
+			stdout().flush().unwrap();
+			let mut input = String::new();
+			read_line(&mut input);
+			let seed = ElectrumSeed::from_phrase(&input).expect("Invalid Electrum seed");
+			print_electrum(&seed);
+			copy(&seed.master_public_key("").unwrap(), false);
+		}
+		"10" => {
+			println!("Derive Monero wallet from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_monero(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"11" => {
+			println!("Derive Stellar accounts from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_stellar_accounts(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"12" => {
+			println!("Derive Cardano addresses from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_cardano_addresses(&mnemonic);
+		}
+		"13" => {
+			println!("Derive Avalanche addresses from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_avax_addresses(&mnemonic);
+		}
+		"14" => {
+			println!("Export account xpubs for watch-only wallets");
+			let mnemonic = prompt_mnemonic();
+			print_account_xpubs(&mnemonic);
+		}
+		"15" => {
+			println!("Inspect extended key");
+			inspect_ext_key();
+		}
+		"16" => {
+			println!("Compare passphrase wallets of a mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_passphrase_wallets(&mnemonic);
+		}
+		"17" => {
+			println!("List BTC receive and change addresses of an account xpub");
+			print_receive_and_change();
+		}
+		"18" => {
+			println!("Translate mnemonic to another language");
+			translate_mnemonic();
+		}
+		"19" => {
+			println!("Export raw BIP39 seed of mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_raw_seed(&mnemonic);
+		}
+		"20" => {
+			println!("Check a mnemonic backup without showing keys");
+			check_mnemonic();
+		}
+		"21" => {
+			println!("Express mnemonic as raw dice rolls");
+			print_raw_rolls();
+		}
+		"22" => {
+			println!("Export a range of child mnemonics to a file");
+			let mnemonic = prompt_mnemonic();
+			export_child_mnemonics(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"23" => {
+			println!("Find the BIP85 index of a child mnemonic");
+			find_child_index();
+		}
+		"24" => {
+			println!("Compare Ledger Live and MetaMask ETH addresses");
+			let root_key = prompt_root_key(&mut Terminal, &mut Stdout);
+			compare_ledger_metamask(&root_key);
+		}
+		"25" => {
+			println!("Compare passphrase normalizations of hardware wallets");
+			let mnemonic = prompt_mnemonic();
+			print_passphrase_variants(&mnemonic);
+		}
+		"26" => {
+			println!("Derive BIP85 RSA key from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_rsa_key(&mnemonic);
+		}
+		"27" => {
+			println!("Print portfolio map of mnemonic");
+			let mnemonic = prompt_mnemonic();
+			print_portfolio(&mnemonic);
+		}
+		"28" => {
+			println!("Inspect a single private key (hex or WIF)");
+			inspect_private_key();
+		}
+		"29" => {
+			println!("Export a printable paper wallet sheet");
+			let mnemonic = prompt_mnemonic();
+			export_paper_wallet(&mnemonic);
+		}
+		"30" => {
+			println!("Split or combine Coldcard Seed XOR parts");
+			seed_xor(&mut Terminal, &mut Stdout);
+		}
+		"31" => {
+			println!("Derive a labeled secret from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			labeled_secret(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"32" => {
+			println!("Rehearse a hardware wallet recovery check");
+			let mnemonic = prompt_hidden_mnemonic();
+			recovery_check(&mnemonic);
+		}
+		"33" => {
+			println!("Derive OpenPGP ed25519 key from mnemonic");
 			let mnemonic = prompt_mnemonic();
-			print_mnemonic(&mnemonic);
-			print_eth_addresses(&mnemonic);
+			openpgp_key(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"34" => {
+			println!("Derive age encryption identity from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			age_identity(&mnemonic, &mut Terminal, &mut Stdout);
+		}
+		"35" => {
+			println!("Derive addresses of a registered coin from mnemonic");
+			let mnemonic = prompt_mnemonic();
+			coin_addresses(&mnemonic, &mut Terminal, &mut Stdout);
 		}
 		_ => {
 			println!("Unknown option");
@@ -53,121 +441,3153 @@ fn main() {
 	}
 }
 
-fn prompt_mnemonic() -> Mnemonic {
-	print!("Enter 24 word mnemonic: ");
-	stdout().flush().unwrap();
+/// `keymaker vectors --paths m/...,m/... [--out FILE] [--include-secrets]`,
+/// derivation vectors as JSON for a mnemonic read from stdin.
+fn vectors() {
+	let usage = || -> ! {
+		eprintln!(
+			"Usage: keymaker vectors --paths m/44'/60'/0'/0/0,m/84'/0'/0'/0/0 [--out FILE] [--include-secrets]"
+		);
+		exit(2);
+	};
+	let arg = |name: &str| std::env::args().skip_while(|a| a != name).nth(1);
+	let Some(paths) = arg("--paths") else { usage() };
+	let paths: Vec<_> = match paths.split(',').map(bip32::parse_path).collect() {
+		Ok(paths) => paths,
+		Err(err) => {
+			eprintln!("Invalid path ({err:?})");
+			usage();
+		}
+	};
+	let include_secrets = std::env::args().any(|a| a == "--include-secrets");
 
-	let mut input = String::new();
-	stdin().read_line(&mut input).unwrap();
+	eprint!("Enter mnemonic: ");
+	let mut phrase = String::new();
+	read_line(&mut phrase);
+	let mnemonic = match Mnemonic::from_phrase(&phrase) {
+		Ok(mnemonic) => mnemonic,
+		Err(err) => {
+			print_corrections(&phrase, &err, &mut Stdout);
+			eprintln!("Invalid mnemonic ({err:?})");
+			exit(1);
+		}
+	};
+	let json = keymaker::vectors::vectors(&mnemonic, "", &paths, include_secrets)
+		.expect("Cannot derive keys");
+	let Some(path) = arg("--out") else {
+		print!("{json}");
+		return;
+	};
+	let mut file = if include_secrets {
+		FileSink::private(&path)
+	} else {
+		FileSink::new(&path)
+	};
+	file.write_str(&json);
+	match file.save(false, &mut Stdout) {
+		Ok(()) => eprintln!("Wrote {} vectors to {path}", paths.len()),
+		Err(err) => {
+			eprintln!("Cannot write {path}: {err}");
+			exit(1);
+		}
+	}
+}
 
-	Mnemonic::from_phrase(&input).expect("Invalid mnemonic")
+/// `--paths-file FILE`: derives every path listed in the file, one per line.
+fn derive_paths_file(file: &str) {
+	let text = fs::read_to_string(file).expect("Cannot read paths file");
+	let paths = match bip32::parse_path_list(&text) {
+		Ok(paths) => paths,
+		Err((line, err)) => {
+			eprintln!("{file}:{line}: invalid path ({err:?})");
+			exit(2);
+		}
+	};
+	let root_key = prompt_root_key(&mut Terminal, &mut Stdout);
+	println!();
+	print!("{}", path_table(&root_key, &paths));
 }
 
-fn dice() -> Mnemonic {
-	println!("Generate mnemonic from dice rolls");
-	println!("Throw at least 154 dice to ensure 256 bit security");
-	print!("Enter dice rolls: ");
-	stdout().flush().unwrap();
+/// Public key and address of each path, labeled with its line in the paths
+/// file.
+fn path_table(root_key: &ExtKey<SecretKey>, paths: &[(usize, Vec<ChildNumber>)]) -> String {
+	let mut rows = vec![];
+	for (line, path) in paths {
+		let pubkey = root_key
+			.derive_path(path)
+			.expect("Cannot derive key")
+			.neuter()
+			.key()
+			.serialize();
+		rows.push(vec![
+			line.to_string(),
+			format_path(path),
+			portfolio::address(path, &pubkey).unwrap_or("-".to_string()),
+			hex::encode(pubkey),
+		]);
+		audit(|| Event::Derive {
+			wallet: Fingerprint(root_key.fingerprint()),
+			path: path.clone(),
+		});
+	}
+	util::table(&["line", "path", "address", "public key"], &rows)
+}
 
-	let mut input = String::new();
-	stdin().read_line(&mut input).unwrap();
+/// `keymaker backup --out FILE`, writes the mnemonic encrypted with a
+/// passphrase.
+fn backup() {
+	let Some(path) = std::env::args().skip_while(|a| a != "--out").nth(1) else {
+		eprintln!("Usage: keymaker backup --out seed.km");
+		exit(2);
+	};
+	let mnemonic = prompt_mnemonic();
+	let mut passphrase = loop {
+		let passphrase = read_secret("Backup passphrase (hidden): ");
+		if passphrase.is_empty() {
+			println!("The passphrase cannot be empty");
+		} else if read_secret("Repeat passphrase (hidden): ") == passphrase {
+			break passphrase;
+		} else {
+			println!("Passphrases do not match, try again");
+		}
+	};
+	let data = backup::encrypt(&mnemonic, &passphrase);
+	memory::zeroize_string(&mut passphrase);
+	let data = match data {
+		Ok(data) => data,
+		Err(err) => {
+			eprintln!("Cannot back up mnemonic: {err:?}");
+			exit(1);
+		}
+	};
+	if let Err(err) = util::write_private(&path, data) {
+		eprintln!("Cannot write {path}: {err}");
+		exit(1);
+	}
+	println!(
+		"Encrypted backup of wallet {} written to {path}",
+		mnemonic.seed("").fingerprint()
+	);
+	println!("Restore it with: keymaker restore --in {path}");
+}
 
-	let mut hasher = Sha256::new();
-	let mut warn = false;
-	let mut len = 0;
-	for mut c in input.trim_end().chars().map(|v| v as u8) {
-		if c < 49 || c > 54 {
-			warn = true;
-			continue;
+/// `keymaker restore --in FILE`, decrypts a backup and shows the mnemonic.
+fn restore() {
+	let Some(path) = std::env::args().skip_while(|a| a != "--in").nth(1) else {
+		eprintln!("Usage: keymaker restore --in seed.km");
+		exit(2);
+	};
+	let data = fs::read(&path).expect("Cannot read backup file");
+	let mut passphrase = read_secret("Backup passphrase (hidden): ");
+	let mnemonic = backup::decrypt(&data, &passphrase);
+	memory::zeroize_string(&mut passphrase);
+	match mnemonic {
+		Ok(mnemonic) => print_mnemonic(&mnemonic, &mut Terminal, &mut Stdout),
+		Err(err) => {
+			eprintln!("Cannot restore {path}: {err:?}");
+			exit(1);
 		}
-		if c == 54 {
-			c = 48; // map 6 to 0
+	}
+}
+
+/// `keymaker summary --mnemonic "..."`, prints public keys and addresses
+/// without any prompt.
+fn summary() {
+	let Some(phrase) = std::env::args().skip_while(|a| a != "--mnemonic").nth(1) else {
+		eprintln!("Usage: keymaker summary --mnemonic \"<words>\"");
+		exit(2);
+	};
+	let mnemonic = match Mnemonic::from_phrase(&phrase) {
+		Ok(mnemonic) => mnemonic,
+		Err(err) => {
+			print_corrections(&phrase, &err, &mut Stdout);
+			eprintln!("Invalid mnemonic ({err:?})");
+			exit(1);
 		}
-		hasher.update(&[c]);
-		len += 1;
+	};
+	if let Some(weakness) = blocklist::weakness(&mnemonic) {
+		eprintln!("WARNING: {weakness:?}, never send funds to this mnemonic");
 	}
+	print!(
+		"{}",
+		summary::summary(&mnemonic, "").expect("Cannot derive keys")
+	);
+}
 
-	if warn {
+/// Opens the `--session` file. An existing session is decrypted and the
+/// mnemonic re-entered to check it belongs to the same wallet, a new one is
+/// written after the first recorded step.
+fn resume_session(path: String) {
+	let mut file = SessionFile {
+		path,
+		password: None,
+		session: None,
+	};
+	if let Ok(data) = fs::read(&file.path) {
+		let password = read_secret("Session password (hidden): ");
+		let session = Session::decrypt(&data, &password)
+			.unwrap_or_else(|err| panic!("Cannot open session: {err:?}"));
+		println!("Resuming session of wallet {}", session.fingerprint);
+		for scheme in &session.schemes {
+			println!("Scheme: {scheme}");
+		}
+		for xpub in &session.xpubs {
+			println!("Xpub:   {xpub}");
+		}
+		println!("Done:   {}", session.done.join(", "));
+		println!("Re-enter the mnemonic to continue");
+		let mnemonic = prompt_mnemonic();
+		if let Err(err) = session.verify(&mnemonic, "") {
+			println!("Cannot resume: {err:?}");
+			exit(1);
+		}
+		println!("Mnemonic matches the session");
+		println!();
+		file.password = Some(password);
+		file.session = Some(session);
+	} else {
+		println!("New session, {} is written after the first step", file.path);
 		println!();
-		println!("!!!! WARNING: invalid characters, they will be discarded !!!!");
-		println!("Press ENTER to continue anyway");
-		input.clear();
-		stdin().read_line(&mut input).unwrap();
 	}
+	*SESSION.lock().unwrap() = Some(file);
+}
 
-	if len < 128 {
-		println!();
-		println!("!!!! WARNING: insufficient entropy !!!!");
-		println!("Press ENTER to continue anyway");
-		input.clear();
-		stdin().read_line(&mut input).unwrap();
+/// With `--session`, records a step for the wallet with this fingerprint and
+/// saves the session file. Steps on other wallets are not recorded.
+fn record_session(fingerprint: &str, step: &str, update: impl FnOnce(&mut Session)) {
+	let mut guard = SESSION.lock().unwrap();
+	let Some(file) = guard.as_mut() else {
+		return;
+	};
+	let session = file
+		.session
+		.get_or_insert_with(|| Session::new(fingerprint.to_string()));
+	if session.fingerprint != fingerprint {
+		println!(
+			"Not recorded, the session is for wallet {}",
+			session.fingerprint
+		);
+		return;
+	}
+	update(session);
+	session.mark_done(step);
+	let password = file.password.get_or_insert_with(|| loop {
+		let password = read_secret("New session password (hidden): ");
+		if read_secret("Repeat password (hidden): ") == password {
+			break password;
+		}
+		println!("Passwords do not match, try again");
+	});
+	let data = session.encrypt(password);
+	let tmp = format!("{}.tmp", file.path);
+	fs::remove_file(&tmp).ok();
+	util::write_private(&tmp, &data).expect("Cannot write session file");
+	fs::rename(&tmp, &file.path).expect("Cannot write session file");
+	println!("Session saved to {}", file.path);
+	audit(|| Event::Export {
+		wallet: hex::decode(fingerprint)
+			.ok()
+			.and_then(|f| f.try_into().ok())
+			.map(Fingerprint),
+		file: file.path.clone().into(),
+	});
+}
+
+/// `  [check: w1 w2 w3]` with `--check-words`, to compare `s` after copying it
+/// by hand. Empty otherwise.
+fn check_suffix(s: &str) -> String {
+	if CHECK_WORDS.load(Ordering::Relaxed) {
+		format!("  [check: {}]", util::check_words(s.as_bytes(), 3))
+	} else {
+		String::new()
+	}
+}
+
+/// Appends an event to the `--audit-log`, if any. Events are only built
+/// when logging.
+fn audit(event: impl FnOnce() -> Event) {
+	if let Some(log) = AUDIT.lock().unwrap().as_mut() {
+		if let Err(err) = log.record(&event()) {
+			println!("Cannot write audit log: {err}");
+		}
+	}
+}
+
+/// Wipes the screen and scrollback when dropped, including on panic, and on
+/// Ctrl-C. Does nothing if stdout is not a terminal.
+struct ClearOnExit;
+
+impl ClearOnExit {
+	fn new() -> Self {
+		ctrlc::set_handler(|| {
+			clear_screen();
+			std::process::exit(130);
+		})
+		.expect("Cannot install Ctrl-C handler");
+		Self
 	}
+}
+
+impl ClearOnExit {
+	fn clear() {
+		if stdout().is_terminal() {
+			println!();
+			print!("Press ENTER to clear the screen and exit");
+			stdout().flush().unwrap();
+			read_line(&mut String::new());
+			clear_screen();
+		}
+	}
+}
+
+impl Drop for ClearOnExit {
+	fn drop(&mut self) {
+		Self::clear();
+	}
+}
+
+/// `process::exit` skips destructors, so this runs `ClearOnExit` first.
+fn exit(code: i32) -> ! {
+	if CLEAR_ON_EXIT.load(Ordering::Relaxed) {
+		ClearOnExit::clear();
+	}
+	std::process::exit(code);
+}
+
+fn clear_screen() {
+	if stdout().is_terminal() {
+		// cursor home, clear screen, clear scrollback
+		print!("\x1b[H\x1b[2J\x1b[3J");
+		stdout().flush().ok();
+	}
+}
+
+/// Marks that a secret is on screen, from here on reads are subject to
+/// `--timeout`.
+fn secret_shown() {
+	SECRET_SHOWN.store(true, Ordering::Relaxed);
+}
+
+/// Reads a line from stdin and exits if it is `q` or `quit`.
+fn read_line(buf: &mut String) {
+	let start = buf.len();
+	read_input(buf);
+	if is_quit(&buf[start..]) {
+		quit();
+	}
+}
+
+/// Clears without asking, the user chose to leave.
+fn quit() -> ! {
+	if SECRET_SHOWN.load(Ordering::Relaxed) || CLEAR_ON_EXIT.load(Ordering::Relaxed) {
+		clear_screen();
+	}
+	std::process::exit(0);
+}
+
+/// Reads a line from stdin, see `timed_read`.
+fn read_input(buf: &mut String) {
+	buf.push_str(&timed_read(|| {
+		let mut line = String::new();
+		stdin().read_line(&mut line).unwrap();
+		line
+	}));
+}
+
+/// Returns the line `read` returns. Once a secret is shown and `--timeout` is
+/// set, the read happens on a separate thread and keymaker clears the screen
+/// and exits if no line arrives in time.
+fn timed_read(read: impl FnOnce() -> String + Send + 'static) -> String {
+	let timeout = match TIMEOUT.get() {
+		Some(&timeout) if SECRET_SHOWN.load(Ordering::Relaxed) => timeout,
+		_ => return read(),
+	};
+	let (tx, rx) = mpsc::channel();
+	thread::spawn(move || {
+		tx.send(read()).ok();
+	});
+	match rx.recv_timeout(timeout) {
+		Ok(line) => line,
+		Err(_) => {
+			// a hidden read may still have echo turned off
+			restore_echo();
+			clear_screen();
+			println!("No input for {} seconds, exiting", timeout.as_secs());
+			std::process::exit(124);
+		}
+	}
+}
+
+#[cfg(unix)]
+fn restore_echo() {
+	// SAFETY: termios is plain data and filled by tcgetattr before use
+	unsafe {
+		let mut termios = std::mem::zeroed::<libc::termios>();
+		if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) == 0 {
+			termios.c_lflag |= libc::ECHO;
+			libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios);
+		}
+	}
+}
 
-	#[cfg(feature = "salt")]
-	{
-		print!("Enter optional salt: ");
+#[cfg(not(unix))]
+fn restore_echo() {}
+
+/// Standard input with the `--timeout` and quit handling of `read_line`.
+struct Terminal;
+
+impl InputSource for Terminal {
+	fn read_line(&mut self, buf: &mut String) -> bool {
+		read_line(buf);
+		true
+	}
+}
+
+/// A terminal that does not echo input, the prompt is written beforehand.
+struct HiddenTerminal;
+
+impl InputSource for HiddenTerminal {
+	fn read_line(&mut self, buf: &mut String) -> bool {
 		stdout().flush().unwrap();
-		input.clear();
-		stdin().read_line(&mut input).unwrap();
-		let salt = input.trim_end();
-		if !salt.is_empty() {
-			hasher.update(salt.as_bytes());
+		let mut line = read_secret("");
+		if is_quit(&line) {
+			quit();
 		}
+		buf.push_str(&line);
+		memory::zeroize_string(&mut line);
+		true
 	}
+}
 
-	let res = hasher.finalize();
-	Mnemonic::from_entropy(res)
+/// With `--copy`, places `text` on the system clipboard. The clipboard is kept
+/// open until exit, some platforms drop its contents with it.
+#[cfg(feature = "clipboard")]
+fn copy(text: &str, secret: bool) {
+	static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+	if !COPY.load(Ordering::Relaxed) {
+		return;
+	}
+	if secret {
+		println!("!!!! WARNING: the clipboard may be read by other applications !!!!");
+	}
+	let mut clipboard = CLIPBOARD.lock().unwrap();
+	if clipboard.is_none() {
+		*clipboard = arboard::Clipboard::new()
+			.map_err(|err| println!("Cannot open clipboard: {err}"))
+			.ok();
+	}
+	if let Some(clipboard) = clipboard.as_mut() {
+		match clipboard.set_text(text) {
+			Ok(()) => println!("Copied to clipboard"),
+			Err(err) => println!("Cannot copy to clipboard: {err}"),
+		}
+	}
 }
 
-fn print_mnemonic(mnemonic: &Mnemonic) {
-	let seed = mnemonic.seed("");
-	let root_key = seed.root_key().unwrap();
-	println!();
-	println!("Mnemonic: {mnemonic}");
-	println!("Seed:     {seed}");
-	println!("Root key: {root_key}");
-	println!();
+#[cfg(not(feature = "clipboard"))]
+fn copy(_text: &str, _secret: bool) {}
+
+fn prompt_mnemonic() -> Mnemonic {
+	enter_mnemonic(&mut Terminal, &mut Stdout)
 }
 
-fn print_child_mnemonics(mnemonic: &Mnemonic) {
-	println!("Keep pressing ENTER to generate child mnemonics");
+/// Reads a mnemonic without echo, for flows that must not show it. Invalid
+/// phrases get no corrections, they would show the words.
+fn prompt_hidden_mnemonic() -> Mnemonic {
+	let mut phrase = read_secret("Enter mnemonic (hidden): ");
+	if is_quit(&phrase) {
+		quit();
+	}
+	let mnemonic = Mnemonic::from_phrase(&phrase);
+	memory::zeroize_string(&mut phrase);
+	let mnemonic = mnemonic.expect("Invalid mnemonic");
+	audit(|| Event::Wallet(Fingerprint(mnemonic.root_key("").unwrap().fingerprint())));
+	confirm_weakness(&mnemonic, &mut Terminal, &mut Stdout);
+	mnemonic
+}
 
-	let seed = mnemonic.seed("");
-	let root_key = seed.root_key().unwrap();
-	let mut input = String::new();
+/// Reads a mnemonic, a word at a time with `--word-entry` on a terminal.
+fn enter_mnemonic(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> Mnemonic {
+	let mnemonic = read_mnemonic(input, out);
+	confirm_weakness(&mnemonic, input, out);
+	mnemonic
+}
 
-	let mut i = 0;
-	loop {
-		input.clear();
-		stdin().read_line(&mut input).unwrap();
-		println!("{i}: {}", root_key.child_mnemonic(i).unwrap());
-		i += 1;
+fn read_mnemonic(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> Mnemonic {
+	#[cfg(unix)]
+	if WORD_ENTRY.load(Ordering::Relaxed) && stdin().is_terminal() {
+		return word_entry();
 	}
+
+	write!(out, "Enter mnemonic: ");
+	let mut line = String::new();
+	input.read_line(&mut line);
+	let mnemonic = parse_mnemonic(&line, out);
+	audit(|| Event::Wallet(Fingerprint(mnemonic.root_key("").unwrap().fingerprint())));
+	mnemonic
 }
 
-fn print_eth_addresses(mnemonic: &Mnemonic) {
-	println!("Derivation path: 44'/60'/X'/0/0 (Ledger Live)");
-	println!("Keep pressing ENTER to generate addresses");
+/// Disables line buffering and echo on stdin, restored on drop.
+#[cfg(unix)]
+struct RawMode(libc::termios);
 
-	let seed = mnemonic.seed("");
-	let base = seed
-		.root_key()
-		.unwrap()
-		.derive_path(&[44 + HARDENED_OFFSET, 60 + HARDENED_OFFSET])
-		.unwrap();
+#[cfg(unix)]
+impl RawMode {
+	fn enable() -> Self {
+		// SAFETY: termios is plain data and stdin is a valid descriptor
+		unsafe {
+			let mut termios = std::mem::zeroed();
+			if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+				panic!("Cannot read terminal settings");
+			}
+			let saved = termios;
+			termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+			libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios);
+			Self(saved)
+		}
+	}
+}
 
-	let mut i = 0;
-	let mut input = String::new();
+#[cfg(unix)]
+impl Drop for RawMode {
+	fn drop(&mut self) {
+		// SAFETY: restores the settings read in `enable`
+		unsafe {
+			libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+		}
+	}
+}
+
+#[cfg(unix)]
+fn read_key() -> KeyEvent {
+	use std::io::Read;
+	let mut stdin = stdin().lock();
+	let mut byte = [0];
 	loop {
-		input.clear();
-		stdin().read_line(&mut input).unwrap();
-		for _ in 0..4 {
-			let address = base
-				.derive_path(&[i + HARDENED_OFFSET, 0, 0])
-				.unwrap()
-				.address();
-			println!("{address}");
-			i += 1;
+		stdin
+			.read_exact(&mut byte)
+			.expect("Cannot read from terminal");
+		return match byte[0] {
+			b'\t' => KeyEvent::Tab,
+			b'\r' | b'\n' => KeyEvent::Enter,
+			0x08 | 0x7f => KeyEvent::Backspace,
+			// skip escape sequences such as arrow keys
+			0x1b => {
+				stdin.read_exact(&mut [0; 2]).ok();
+				continue;
+			}
+			b => KeyEvent::Char(b as char),
+		};
+	}
+}
+
+#[cfg(unix)]
+fn word_entry() -> Mnemonic {
+	println!("Type each word, TAB completes, a number picks a suggestion,");
+	println!("ENTER accepts and BACKSPACE on an empty word goes back");
+	let count = read_word_count(&mut Terminal, &mut Stdout);
+	let _raw = RawMode::enable();
+	let mut entry = WordEntry::new(count);
+	let mut error = None;
+	loop {
+		let n = entry.words().len();
+		if let Some(err) = &error {
+			print!("\r\x1b[KInvalid mnemonic: {err:?}, BACKSPACE to correct");
+		} else {
+			let candidates = entry.candidates();
+			let mut hint = String::new();
+			if candidates.len() > MAX_NUMBERED {
+				hint = format!("  ({} words)", candidates.len());
+			} else if candidates.len() > 1 {
+				for (i, word) in candidates.iter().enumerate() {
+					hint += &format!("  {}) {word}", i + 1);
+				}
+			}
+			print!("\r\x1b[KWord {:>2}: {}{hint}", n + 1, entry.current());
+		}
+		stdout().flush().unwrap();
+
+		let result = entry.handle(read_key());
+		if entry.words().len() > n {
+			print!("\r\x1b[KWord {:>2}: {}\r\n", n + 1, entry.words()[n]);
+		} else if entry.words().len() < n {
+			// back up to the reopened word
+			print!("\r\x1b[K\x1b[1A");
+		}
+		error = match result {
+			Some(Ok(mnemonic)) => return mnemonic,
+			Some(Err(err)) => Some(err),
+			None => error.filter(|_| entry.words().len() == entry.count()),
+		};
+	}
+}
+
+/// Electrum seeds are only warned about when the phrase is not also BIP39:
+/// about one BIP39 mnemonic in 240 has an Electrum version by chance.
+fn parse_mnemonic(input: &str, out: &mut dyn OutputSink) -> Mnemonic {
+	let mnemonic = match Mnemonic::from_phrase(input) {
+		Err(MnemonicError::InvalidWord) if Mnemonic::from_prefixes(input).is_ok() => {
+			writeln!(out, "Words read as their first four letters");
+			Mnemonic::from_prefixes(input)
+		}
+		mnemonic => mnemonic,
+	};
+	match electrum::seed_type(input) {
+		Some(seed_type) if mnemonic.is_ok() => {
+			writeln!(out, "Also a valid {seed_type} seed, read as BIP39");
+		}
+		Some(seed_type) => {
+			writeln!(out);
+			writeln!(
+				out,
+				"!!!! WARNING: this is an {seed_type} seed, not BIP39 !!!!"
+			);
+			writeln!(out, "Use option 9 to derive Electrum addresses");
+			writeln!(out);
+		}
+		None => {}
+	}
+	if let Err(err) = &mnemonic {
+		print_corrections(input, err, out);
+	}
+	mnemonic.expect("Invalid mnemonic")
+}
+
+/// Entering a weak mnemonic needs confirmation, it may be a test vector
+/// being inspected.
+fn confirm_weakness(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	if let Some(weakness) = blocklist::weakness(mnemonic) {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! WARNING: {weakness:?}, anyone can derive its keys !!!!"
+		);
+		writeln!(out, "!!!! Never send funds to this mnemonic !!!!");
+		writeln!(out, "Press ENTER to continue anyway");
+		input.read_line(&mut String::new());
+	}
+}
+
+fn print_corrections(phrase: &str, err: &MnemonicError, out: &mut dyn OutputSink) {
+	let words: Vec<_> = phrase.split_whitespace().collect();
+	match err {
+		MnemonicError::InvalidWord => {
+			for (i, word) in words.iter().enumerate() {
+				if words_with_prefix(word).contains(word) {
+					continue;
+				}
+				let closest = suggest::closest_words(word, 3);
+				if closest.is_empty() {
+					writeln!(out, "Word {}: unknown word {word}", i + 1);
+				} else {
+					writeln!(out, "Word {}: {word} → {}?", i + 1, closest.join(", "));
+				}
+			}
+		}
+		MnemonicError::ChecksumMismatch => {
+			writeln!(
+				out,
+				"Guesses, each changes one word so the checksum passes but may still be wrong:"
+			);
+			for (i, word) in suggest::checksum_fixes(phrase, 5) {
+				writeln!(out, "Word {}: {} → {word}", i + 1, words[i]);
+			}
+		}
+		_ => {}
+	}
+	if matches!(
+		err,
+		MnemonicError::ChecksumMismatch | MnemonicError::IncorrectLength
+	) {
+		for finding in suggest::transcription_errors(phrase) {
+			match finding {
+				Finding::Repeated { position, valid } => writeln!(
+					out,
+					"Words {} and {}: {} written twice? Without the repeat the phrase has {} words{}",
+					position + 1,
+					position + 2,
+					words[position],
+					words.len() - 1,
+					if valid { " and a valid checksum" } else { "" }
+				),
+				Finding::Swapped(position) => writeln!(
+					out,
+					"Words {} and {}: swapped? {} {} → {} {} passes the checksum",
+					position + 1,
+					position + 2,
+					words[position],
+					words[position + 1],
+					words[position + 1],
+					words[position]
+				),
+			}
+		}
+	}
+}
+
+/// Root key from a mnemonic or a pasted master xprv.
+fn prompt_root_key(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> ExtKey<SecretKey> {
+	write!(out, "Enter mnemonic or xprv: ");
+	let mut line = String::new();
+	input.read_line(&mut line);
+	let line = line.trim();
+
+	if line.starts_with("xpub") {
+		writeln!(
+			out,
+			"An xpub cannot derive hardened paths, enter the xprv or mnemonic"
+		);
+		exit(1);
+	}
+	if !line.starts_with("xprv") {
+		let mnemonic = parse_mnemonic(line, out);
+		confirm_weakness(&mnemonic, input, out);
+		print_mnemonic(&mnemonic, input, out);
+		return mnemonic.root_key("").unwrap();
+	}
+
+	let root_key: ExtKey<SecretKey> = line.parse().expect("Invalid xprv");
+	writeln!(out);
+	writeln!(out, "Pasted key: {}", root_key.describe());
+	audit(|| Event::Wallet(Fingerprint(root_key.fingerprint())));
+	if root_key.depth() > 0 {
+		writeln!(
+			out,
+			"Expected a master key, this xprv has depth {}",
+			root_key.depth()
+		);
+		exit(1);
+	}
+	print_fingerprint(&root_key, out);
+	writeln!(out);
+	root_key
+}
+
+fn dice(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> Mnemonic {
+	writeln!(out, "Generate mnemonic from dice rolls");
+	let words = read_word_count(input, out);
+	let Some(entropy) = dice_entropy(words * 4 / 3, input, out) else {
+		exit(1);
+	};
+	let mnemonic = Mnemonic::from_entropy(entropy);
+	refuse_weakness(&mnemonic, out);
+	mnemonic
+}
+
+/// Exits rather than generate a mnemonic from entropy known to be weak.
+fn refuse_weakness(mnemonic: &Mnemonic, out: &mut dyn OutputSink) {
+	if let Some(weakness) = blocklist::weakness(mnemonic) {
+		writeln!(out, "Refusing to generate a weak mnemonic: {weakness:?}");
+		exit(1);
+	}
+}
+
+fn read_word_count(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> usize {
+	write!(out, "Word count (12, 15, 18, 21 or 24) [24]: ");
+	let mut line = String::new();
+	input.read_line(&mut line);
+	match line.trim() {
+		"" => 24,
+		v => v
+			.parse()
+			.ok()
+			.filter(|n| WORD_COUNTS.contains(n))
+			.expect("Invalid word count"),
+	}
+}
+
+/// `len` bytes of entropy from dice rolls, `None` when no valid roll was
+/// entered: hashing nothing gives the same well-known entropy every time.
+fn dice_entropy(
+	len: usize,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) -> Option<Vec<u8>> {
+	let die = *DIE.get().unwrap_or(&Die::D6);
+	let bits = len * 8;
+	// D6 keeps a margin over the 100 rolls needed in theory for 256 bits
+	let (advised, minimum) = match die {
+		Die::D6 => ((154 * bits).div_ceil(256), bits / 2),
+		Die::D20 => (die.rolls_for_bits(bits), die.rolls_for_bits(bits)),
+	};
+	writeln!(
+		out,
+		"Throw at least {advised} dice to ensure {bits} bit security"
+	);
+	let mut line = if GUIDED_DICE.load(Ordering::Relaxed) {
+		guided_rolls(die, advised, input, out)
+	} else {
+		match die {
+			Die::D6 => write!(out, "Enter dice rolls: "),
+			Die::D20 => write!(out, "Enter D20 rolls (1-20) separated by spaces: "),
+		}
+		let mut line = String::new();
+		input.read_line(&mut line);
+		line
+	};
+	if entropy::looks_like_text(&line) {
+		return Some(brainwallet(&line, input, out)[..len].to_vec());
+	}
+
+	let dice_hash = *DICE_HASH.get().unwrap_or(&DiceHash::Sha256);
+	let (rolls, warn) = die.rolls(line.trim_end());
+	if rolls.is_empty() {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! ERROR: no valid dice rolls entered, refusing to derive a mnemonic !!!!"
+		);
+		return None;
+	}
+
+	if warn {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! WARNING: invalid characters, they will be discarded !!!!"
+		);
+		writeln!(out, "Press ENTER to continue anyway");
+		line.clear();
+		input.read_line(&mut line);
+	}
+
+	if low_quality(&rolls) {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! WARNING: rolls look repetitive, did you actually throw the dice? !!!!"
+		);
+		writeln!(out, "Press ENTER to continue anyway");
+		line.clear();
+		input.read_line(&mut line);
+	}
+
+	if rolls.len() < minimum {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! WARNING: insufficient entropy, {} rolls carry at most {} of {bits} bits !!!!",
+			rolls.len(),
+			die.bits(rolls.len()).min(bits)
+		);
+		writeln!(out, "Press ENTER to continue anyway");
+		line.clear();
+		input.read_line(&mut line);
+	}
+
+	let mut salt = String::new();
+	if cfg!(feature = "salt") && dice_hash != DiceHash::Raw {
+		write!(out, "Enter optional salt: ");
+		input.read_line(&mut salt);
+		salt.truncate(salt.trim_end().len());
+	}
+
+	let entropy = dice_hash.entropy_bytes(die, &rolls, salt.as_bytes(), len);
+	if entropy.is_none() {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! ERROR: rolls rejected, raw conversion would be biased, roll again !!!!"
+		);
+	}
+	entropy
+}
+
+/// Text pasted instead of dice rolls. Hashing it needs a typed confirmation,
+/// all output after that carries the weak entropy banner.
+fn brainwallet(text: &str, input: &mut dyn InputSource, out: &mut dyn OutputSink) -> [u8; 32] {
+	writeln!(out);
+	writeln!(
+		out,
+		"!!!! This looks like text, not dice rolls. Text chosen by people is not !!!!"
+	);
+	writeln!(
+		out,
+		"!!!! random, wallets made from it are found and emptied by attackers    !!!!"
+	);
+	writeln!(out);
+	writeln!(
+		out,
+		"To hash arbitrary text (NOT RECOMMENDED) type the following, anything else exits:"
+	);
+	writeln!(out, "{}", entropy::BRAINWALLET_CONFIRMATION);
+	let mut line = String::new();
+	input.read_line(&mut line);
+	let Some(entropy) = entropy::brainwallet_entropy(text, &line) else {
+		writeln!(out, "Not confirmed, roll dice instead");
+		exit(1);
+	};
+	WEAK_ENTROPY.store(true, Ordering::Relaxed);
+	entropy
+}
+
+fn print_fingerprint(root_key: &ExtKey<SecretKey>, out: &mut dyn OutputSink) {
+	writeln!(
+		out,
+		"Master fingerprint: {}",
+		hex::encode(root_key.fingerprint())
+	);
+}
+
+fn weak_entropy_banner(out: &mut dyn OutputSink) {
+	if WEAK_ENTROPY.load(Ordering::Relaxed) {
+		writeln!(out);
+		writeln!(
+			out,
+			"!!!! WEAK ENTROPY SOURCE: derived from text, not dice. Do not store funds !!!!"
+		);
+	}
+}
+
+/// Reads rolls a line at a time, reporting progress after each line, until
+/// `target` rolls have been entered or an empty line ends input early.
+fn guided_rolls(
+	die: Die,
+	target: usize,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) -> String {
+	writeln!(
+		out,
+		"Enter rolls a few at a time, an empty line finishes early"
+	);
+	let mut rolls = String::new();
+	let mut line = String::new();
+	loop {
+		write!(out, "> ");
+		line.clear();
+		input.read_line(&mut line);
+		let line = line.trim();
+		if line.is_empty() {
+			return rolls;
+		}
+		if die == Die::D20 {
+			rolls.push(' ');
+		}
+		rolls.push_str(line);
+
+		let n = die.rolls(&rolls).0.len();
+		writeln!(out, "{n} valid rolls, ~{} bits", die.bits(n));
+		if n >= target {
+			return rolls;
+		}
+	}
+}
+
+fn hex_entropy() -> Mnemonic {
+	println!("Generate mnemonic from hex entropy");
+	print!("Enter 16, 20, 24, 28 or 32 bytes of hex: ");
+	stdout().flush().unwrap();
+
+	let mut input = String::new();
+	read_line(&mut input);
+
+	let mnemonic = Mnemonic::from_hex_entropy(&input).expect("Invalid entropy");
+	refuse_weakness(&mnemonic, &mut Stdout);
+	mnemonic
+}
+
+fn print_mnemonic(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	secret_shown();
+	weak_entropy_banner(out);
+	let format = MNEMONIC_FORMAT
+		.get()
+		.copied()
+		.unwrap_or(if out.is_terminal() {
+			MnemonicFormat::Columns
+		} else {
+			MnemonicFormat::Line
+		});
+	writeln!(out);
+	out.write_str(&mnemonic_details(mnemonic, format));
+	audit(|| Event::Wallet(Fingerprint(mnemonic.root_key("").unwrap().fingerprint())));
+	if SHOW_ROOT_KEY.load(Ordering::Relaxed) {
+		let seed = mnemonic.seed("");
+		confirm_reveal("Seed", Secret::new(seed.to_hex()), input, out);
+		confirm_reveal("Root key", seed.root_key().unwrap().xprv(), input, out);
+	}
+	writeln!(out);
+}
+
+/// Mnemonic and public identifiers. The seed and root key are left out, they
+/// are only shown with `--show-root-key` after a confirmation.
+fn mnemonic_details(mnemonic: &Mnemonic, format: MnemonicFormat) -> String {
+	let seed = mnemonic.seed("");
+	let root_key = seed.root_key().unwrap();
+	let mut out = format!("Standard: BIP39, {}-bit security\n", mnemonic.strength());
+	out += &match format {
+		MnemonicFormat::Columns => format!("Mnemonic:\n{}", mnemonic.to_columns_string()),
+		MnemonicFormat::Line => format!("Mnemonic: {mnemonic}\n"),
+		MnemonicFormat::Numbered => format!("Mnemonic:\n{}", mnemonic.to_numbered_string()),
+		MnemonicFormat::Grid => format!("Mnemonic:\n{}", mnemonic.to_grid_string()),
+	};
+	out += &format!("Master fingerprint: {}\n", seed.fingerprint());
+	out += &format!("Identifier: {}\n", hex::encode(root_key.identifier()));
+	out
+}
+
+/// The one place secrets wrapped in `Secret` are printed, after the user
+/// agrees to it. Returns whether it was shown.
+fn confirm_reveal(
+	label: &str,
+	secret: Secret<String>,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) -> bool {
+	write!(out, "Show {}? [y/N]: ", label.to_lowercase());
+	let mut line = String::new();
+	input.read_line(&mut line);
+	let shown = line.trim().eq_ignore_ascii_case("y");
+	if shown {
+		writeln!(out, "{label}: {}", secret.reveal());
+	}
+	shown
+}
+
+fn confirm_mnemonic(mnemonic: &Mnemonic) {
+	println!("Write down the mnemonic, then type it back to confirm (input is hidden)");
+	loop {
+		let input = rpassword::prompt_password("Mnemonic: ")
+			.expect("Cannot read from terminal, use --no-confirm for automated runs");
+		if is_quit(&input) {
+			quit();
+		}
+		match Mnemonic::from_phrase(&input) {
+			Ok(m) if m == *mnemonic => break,
+			Ok(_) => println!("Mnemonic does not match, try again"),
+			Err(err) => println!("Mnemonic does not match ({err:?}), try again"),
+		}
+	}
+	println!("Mnemonic confirmed");
+	println!();
+}
+
+fn print_electrum(seed: &ElectrumSeed) {
+	secret_shown();
+	let seed_type = seed.seed_type();
+	let account = seed.account("").unwrap().neuter();
+	weak_entropy_banner(&mut Stdout);
+	println!();
+	println!("Standard: {seed_type}");
+	println!("Seed:     {seed}");
+	print_fingerprint(&seed.seed("").root_key().unwrap(), &mut Stdout);
+	println!("Master public key: {}", seed.master_public_key("").unwrap());
+	for (change, count) in [(false, 10), (true, 5)] {
+		println!();
+		println!(
+			"{} addresses ({seed_type}):",
+			if change { "Change" } else { "Receive" }
+		);
+		for i in 0..count {
+			println!("{i}: {}", seed_type.address(&account, change, i).unwrap());
+		}
+	}
+}
+
+fn print_monero(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	secret_shown();
+	let seed = mnemonic.seed("");
+	let root_key = seed.root_key().unwrap();
+	let mut entropy = [0; 32];
+	let app = bip85::Hex { len: 32, index: 0 };
+	let path = app.path().unwrap();
+	entropy.copy_from_slice(&root_key.derive_app(app).unwrap());
+	let keys = Keys::from_entropy(entropy);
+	writeln!(out);
+	print_fingerprint(&root_key, out);
+	writeln!(out, "Entropy: BIP85 HEX {}", format_path(&path));
+	let spend_key = Secret::new(hex::encode(keys.spend_key().reveal()));
+	confirm_reveal("Secret spend key", spend_key, input, out);
+	let view_key = Secret::new(hex::encode(keys.view_key().reveal()));
+	confirm_reveal("Secret view key", view_key, input, out);
+	let address = keys.address(monero::MAINNET);
+	writeln!(out, "Address: {address}");
+	copy(&address, false);
+	confirm_reveal("Seed", keys.mnemonic(), input, out);
+}
+
+fn print_stellar_accounts(
+	mnemonic: &Mnemonic,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) {
+	secret_shown();
+	let seed = mnemonic.seed("");
+	let root_key = Ed25519Key::from_seed(&seed);
+	writeln!(out);
+	print_fingerprint(&seed.root_key().unwrap(), out);
+	writeln!(out, "SEP-0005 accounts");
+	for i in 0..5 {
+		let path = [Hardened(44), Hardened(slip44::XLM), Hardened(i)];
+		let key = root_key.derive_path(&path).unwrap();
+		writeln!(out);
+		writeln!(out, "{}", format_path(&path));
+		let account = strkey::encode(Version::PublicKey, &key.public_key());
+		writeln!(out, "  Account: {account}");
+		let secret = Secret::new(strkey::encode(Version::SecretSeed, key.secret_key()));
+		confirm_reveal("Secret", secret, input, out);
+		if i == 0 {
+			copy(&account, false);
+		}
+	}
+}
+
+fn print_cardano_addresses(mnemonic: &Mnemonic) {
+	let path = cardano::account_path(0);
+	let account = IcarusKey::from_mnemonic(mnemonic, "")
+		.derive_path(&path)
+		.unwrap();
+	let stake_path = [&path[..], &cardano::STAKE_PATH.map(ChildNumber::from)].concat();
+	println!();
+	println!(
+		"Reward address, stake key {}: {}",
+		format_path(&stake_path),
+		account.address_keys(0).unwrap().reward_address()
+	);
+	println!();
+	println!("Icarus base addresses, {}/0/i", format_path(&path));
+	for i in 0..10 {
+		println!("{i}: {}", account.base_address(i).unwrap());
+	}
+	copy(&account.base_address(0).unwrap(), false);
+}
+
+fn print_avax_addresses(mnemonic: &Mnemonic) {
+	let root_key = mnemonic.root_key("").unwrap();
+	println!();
+	print_fingerprint(&root_key, &mut Stdout);
+	println!("X/P-chain: m/44'/9000'/0'/0/i, C-chain: m/44'/60'/0'/0/i");
+	for i in 0..5 {
+		let key = root_key.derive_path(&avax::path(i)).unwrap().neuter();
+		let pubkey = key.key().serialize();
+		println!();
+		for chain in Chain::ALL {
+			println!("{i}: {}", chain.address(&pubkey));
+		}
+		let eth = root_key.derive_path(&Scheme::Bip44.path(i)).unwrap();
+		println!("{i}: C-{}", eth.address());
+		if i == 0 {
+			copy(&Chain::X.address(&pubkey), false);
+		}
+	}
+}
+
+fn print_account_xpubs(mnemonic: &Mnemonic) {
+	let root_key = mnemonic.root_key("").unwrap();
+	println!();
+	print_fingerprint(&root_key, &mut Stdout);
+	let accounts = standard_accounts(&root_key, 0).unwrap();
+	for account in &accounts {
+		println!(
+			"{:<16} {account}{}",
+			account.name,
+			check_suffix(&account.to_string())
+		);
+	}
+	record_session(
+		&hex::encode(root_key.fingerprint()),
+		"export xpubs",
+		|session| {
+			for account in &accounts {
+				let xpub = account.to_string();
+				if !session.xpubs.contains(&xpub) {
+					session.xpubs.push(xpub);
+				}
+			}
+		},
+	);
+
+	println!();
+	println!("Receive descriptors:");
+	for address_type in AddressType::ALL {
+		let path = [
+			Hardened(address_type.purpose()),
+			Hardened(slip44::BTC),
+			Hardened(0),
+		];
+		let origin = KeyOrigin::new(&root_key, &path);
+		let xpub = root_key.derive_path(&path).unwrap().neuter();
+		let descriptor = btc::descriptor(address_type, &origin, &xpub);
+		println!("{descriptor}{}", check_suffix(&descriptor));
+		audit(|| Event::Derive {
+			wallet: Fingerprint(root_key.fingerprint()),
+			path: path.to_vec(),
+		});
+	}
+	println!();
+	unhardened_advisory("account", &mut Terminal, &mut Stdout);
+}
+
+/// Advisory for keys derived without hardening below the `parent` key: its
+/// xpub and one leaked child private key reveal every key below it.
+fn unhardened_advisory(parent: &str, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	if !STRICT_DERIVATION.load(Ordering::Relaxed) {
+		writeln!(
+			out,
+			"Note: keep the {parent} xpub private, with it one leaked child private key reveals all keys below it"
+		);
+		return;
+	}
+	writeln!(out);
+	writeln!(
+		out,
+		"!!!! WARNING: keys below {parent} are not hardened, anyone with its xpub !!!!"
+	);
+	writeln!(
+		out,
+		"!!!! and one child private key can compute all of their private keys !!!!"
+	);
+	writeln!(out, "Press ENTER to continue anyway");
+	input.read_line(&mut String::new());
+}
+
+fn inspect_ext_key() {
+	const VERSIONS: [(&str, bip32::Version); 3] = [
+		("mainnet", bip32::Version::MAINNET),
+		("testnet", bip32::Version::TESTNET),
+		("BIP84 mainnet", electrum::ZPUB),
+	];
+
+	print!("Extended key: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let input = input.trim();
+
+	let mut error = Bip32Error::InvalidVersion;
+	for (network, version) in VERSIONS {
+		let (kind, breakdown) = match (
+			ExtKey::<SecretKey>::parse_with_version(input, version),
+			ExtKey::<PublicKey>::parse_with_version(input, version),
+		) {
+			(Ok(key), _) => ("private", key.details()),
+			(_, Ok(key)) => ("public", format!("{key:#}")),
+			(Err(private), Err(public)) => {
+				// Keep the more specific error when the version matched
+				if private != Bip32Error::InvalidVersion {
+					error = private;
+				} else if public != Bip32Error::InvalidVersion {
+					error = public;
+				}
+				continue;
+			}
+		};
+		println!();
+		println!("Prefix:             {} ({network}, {kind})", &input[..4]);
+		println!("{breakdown}");
+		return;
+	}
+	println!("Invalid extended key: {error:?}");
+}
+
+/// Reads a line without echo from a terminal, or plainly from piped input.
+fn read_secret(prompt: &str) -> String {
+	if stdin().is_terminal() {
+		let prompt = prompt.to_string();
+		return timed_read(move || {
+			rpassword::prompt_password(prompt).expect("Cannot read from terminal")
+		});
+	}
+	print!("{prompt}");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_input(&mut input);
+	input.truncate(input.trim_end_matches(['\r', '\n']).len());
+	input
+}
+
+/// Handles `--passphrase-env` and `--passphrase-file`. The variable is read
+/// and removed before any thread is spawned, `remove_var` is unsound while
+/// another thread may read the environment.
+fn set_passphrase_source() {
+	let value = |flag: &str, expected: &str| {
+		let mut args = std::env::args().skip_while(|a| a != flag);
+		args.next()?;
+		let value = args.next().unwrap_or_else(|| {
+			eprintln!("{flag} expects {expected}");
+			exit(2);
+		});
+		Some(value)
+	};
+	let env = value("--passphrase-env", "a variable name");
+	let file = value("--passphrase-file", "a file path");
+	let source = match (env, file) {
+		(Some(_), Some(_)) => {
+			eprintln!("--passphrase-env and --passphrase-file are mutually exclusive");
+			exit(2);
+		}
+		(Some(var), None) => {
+			let Ok(passphrase) = std::env::var(&var) else {
+				eprintln!("Environment variable {var} is not set");
+				exit(2);
+			};
+			std::env::remove_var(&var);
+			PassphraseSource::Env(Secret::new(passphrase))
+		}
+		(None, Some(path)) => PassphraseSource::File(path),
+		(None, None) => return,
+	};
+	PASSPHRASE.set(source).ok().unwrap();
+}
+
+/// The BIP39 passphrase from `--passphrase-env` or `--passphrase-file`,
+/// otherwise from a hidden prompt. Callers zeroize it once the seed is built.
+fn prompt_passphrase(prompt: &str) -> String {
+	match PASSPHRASE.get() {
+		Some(PassphraseSource::Env(passphrase)) => passphrase.reveal().clone(),
+		Some(PassphraseSource::File(path)) => {
+			read_passphrase_file(path).expect("Cannot read passphrase file")
+		}
+		None => read_secret(prompt),
+	}
+}
+
+/// File contents without one trailing line break, other whitespace is part
+/// of the passphrase.
+fn read_passphrase_file(path: &str) -> std::io::Result<String> {
+	let mut passphrase = fs::read_to_string(path)?;
+	let len = passphrase.strip_suffix('\n').map_or(passphrase.len(), |p| {
+		p.strip_suffix('\r').unwrap_or(p).len()
+	});
+	passphrase.truncate(len);
+	Ok(passphrase)
+}
+
+fn print_passphrase_variants(mnemonic: &Mnemonic) {
+	let mut passphrase = prompt_passphrase("Passphrase (hidden): ");
+	let mut variants = passphrase_variants(&passphrase);
+	memory::zeroize_string(&mut passphrase);
+	// the literal row skips the normalization `seed` applies
+	let fingerprints: Vec<_> = variants
+		.iter()
+		.map(|(label, variant)| match *label {
+			"Literal" => mnemonic.seed_literal(variant).fingerprint(),
+			_ => mnemonic.seed(variant).fingerprint(),
+		})
+		.collect();
+	let rows: Vec<_> = variants
+		.iter()
+		.zip(&fingerprints)
+		.enumerate()
+		.map(|(i, ((label, _), fingerprint))| {
+			let same = fingerprints[..i]
+				.iter()
+				.position(|f| f == fingerprint)
+				.map_or(String::new(), |j| format!("same as {}", variants[j].0));
+			vec![label.to_string(), fingerprint.clone(), same]
+		})
+		.collect();
+	for (_, variant) in &mut variants {
+		memory::zeroize_string(variant);
+	}
+	println!();
+	print!("{}", util::table(&["Passphrase", "Fingerprint", ""], &rows));
+}
+
+fn print_passphrase_wallets(mnemonic: &Mnemonic) {
+	println!("Enter a label and passphrase for each wallet, an empty label to finish");
+	let mut wallets = vec![("(no passphrase)".to_string(), String::new())];
+	let mut input = String::new();
+	loop {
+		print!("Label: ");
+		stdout().flush().unwrap();
+		input.clear();
+		read_line(&mut input);
+		let label = input.trim();
+		if label.is_empty() {
+			break;
+		}
+		wallets.push((label.to_string(), read_secret("Passphrase (hidden): ")));
+	}
+
+	println!("First address to show:");
+	for (i, scheme) in Scheme::ALL.iter().enumerate() {
+		println!(" {}) ETH {scheme}", i + 1);
+	}
+	for (i, address_type) in AddressType::ALL.iter().enumerate() {
+		println!(
+			" {}) BTC {address_type}, m/{}'/0'/0'/0/0",
+			i + Scheme::ALL.len() + 1,
+			address_type.purpose()
+		);
+	}
+	print!("Choice [1]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let choice = match input.trim() {
+		"" => 0,
+		v => v
+			.parse::<usize>()
+			.ok()
+			.and_then(|n| n.checked_sub(1))
+			.expect("Invalid choice"),
+	};
+	let (path, btc) = match (
+		Scheme::ALL.get(choice),
+		choice.checked_sub(Scheme::ALL.len()),
+	) {
+		(Some(scheme), _) => (scheme.path(0), None),
+		(None, Some(i)) => {
+			let address_type = *AddressType::ALL.get(i).expect("Invalid choice");
+			let path = vec![
+				Hardened(address_type.purpose()),
+				Hardened(slip44::BTC),
+				Hardened(0),
+				0.into(),
+				0.into(),
+			];
+			(path, Some(address_type))
+		}
+		_ => panic!("Invalid choice"),
+	};
+
+	let rows: Vec<_> = wallets
+		.iter()
+		.map(|(label, passphrase)| {
+			let root_key = mnemonic.root_key(passphrase).unwrap();
+			let key = root_key.derive_path(&path).unwrap();
+			let address = match btc {
+				Some(address_type) => address_type
+					.address(&key.neuter().key().serialize())
+					.unwrap(),
+				None => key.address().to_string(),
+			};
+			vec![label.clone(), hex::encode(root_key.fingerprint()), address]
+		})
+		.collect();
+	println!();
+	println!("Every row is a separate wallet, only the one with funds used that passphrase");
+	print!(
+		"{}",
+		util::table(&["Label", "Fingerprint", &format_path(&path)], &rows)
+	);
+}
+
+fn print_receive_and_change() {
+	print!("Account xpub (m/84'/0'/0', m/44'/0'/0' or m/86'/0'/0'): ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let account: ExtKey<PublicKey> = input.parse().expect("Invalid xpub");
+
+	print!("Address type (1 = P2WPKH, 2 = P2PKH, 3 = P2TR) [1]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let address_type = match input.trim() {
+		"" | "1" => AddressType::P2wpkh,
+		"2" => AddressType::P2pkh,
+		"3" => AddressType::P2tr,
+		_ => panic!("Invalid address type"),
+	};
+
+	print!("Number of addresses [{DEFAULT_GAP}]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let n = match input.trim() {
+		"" => DEFAULT_GAP,
+		v => v.parse().expect("Invalid number"),
+	};
+
+	let pairs = btc::receive_and_change(&account, address_type, 0..n).unwrap();
+	let width = pairs.iter().map(|(r, _)| r.len()).max().unwrap_or(0);
+	println!();
+	println!(
+		"{:>5}  {:<width$}  {address_type} change (1/i)",
+		"Index",
+		format!("{address_type} receive (0/i)")
+	);
+	for (i, (receive, change)) in pairs.iter().enumerate() {
+		println!("{i:>5}  {receive:<width$}  {change}");
+	}
+	if address_type == AddressType::P2tr {
+		let rows: Vec<_> = (0..n)
+			.map(|i| {
+				let key = account.derive_path(&[0, i]).unwrap();
+				vec![
+					i.to_string(),
+					hex::encode(key.x_only_key()),
+					hex::encode(key.taproot_output_key().unwrap()),
+				]
+			})
+			.collect();
+		println!();
+		println!("Receive keys for tr() descriptors:");
+		print!(
+			"{}",
+			util::table(&["Index", "Internal key (x-only)", "Output key"], &rows)
+		);
+	}
+}
+
+fn prompt_language(prompt: &str) -> Language {
+	let choices: Vec<_> = Language::ALL
+		.iter()
+		.enumerate()
+		.map(|(i, l)| format!("{} = {l}", i + 1))
+		.collect();
+	print!("{prompt} ({}): ", choices.join(", "));
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	input
+		.trim()
+		.parse::<usize>()
+		.ok()
+		.and_then(|i| Language::ALL.get(i.wrapping_sub(1)))
+		.copied()
+		.expect("Invalid language")
+}
+
+fn translate_mnemonic() {
+	let source = prompt_language("Language of the mnemonic");
+	print!("Enter mnemonic: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let mnemonic = Mnemonic::from_phrase_in(&input, source).expect("Invalid mnemonic");
+	let target = prompt_language("Translate to");
+	let translated = mnemonic.translate(target);
+
+	secret_shown();
+	println!();
+	println!("{source}: {mnemonic}");
+	println!("Seed:     {}", mnemonic.seed("").to_hex());
+	println!("{target}: {translated}");
+	println!("Seed:     {}", translated.seed("").to_hex());
+	println!();
+	println!("!!!! WARNING: both phrases encode the same entropy, but BIP39 seeds are !!!!");
+	println!("!!!! derived from the words: the translated phrase is a different wallet !!!!");
+}
+
+/// Inverse of `--dice-hash raw`. Hashed conversion cannot be undone, so this
+/// is refused unless raw conversion was chosen.
+fn print_raw_rolls() {
+	if DICE_HASH.get() != Some(&DiceHash::Raw) {
+		println!("Only mnemonics made with --dice-hash raw can be expressed as rolls, hashed");
+		println!("rolls cannot be recovered. Run with --dice-hash raw to continue");
+		return;
+	}
+	let die = *DIE.get().unwrap_or(&Die::D6);
+	let mnemonic = prompt_mnemonic();
+	let rolls = entropy::raw_rolls(die, mnemonic.entropy());
+
+	secret_shown();
+	println!();
+	println!("!!!! WARNING: these rolls are as sensitive as the mnemonic !!!!");
+	println!(
+		"{} rolls, the entropy in base {} left padded with {}s:",
+		rolls.len(),
+		die.faces(),
+		die.faces()
+	);
+	match die {
+		Die::D6 => {
+			let rolls: String = rolls
+				.iter()
+				.map(|&r| if r == b'0' { '6' } else { r as char })
+				.collect();
+			println!("{rolls}");
+		}
+		Die::D20 => {
+			let rolls: Vec<_> = rolls
+				.iter()
+				.map(|&r| if r == 0 { 20 } else { r }.to_string())
+				.collect();
+			println!("{}", rolls.join(" "));
+		}
+	}
+}
+
+fn check_mnemonic() {
+	print!("Enter mnemonic to check: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	println!();
+	println!("{}", check::check(&input));
+}
+
+fn print_raw_seed(mnemonic: &Mnemonic) {
+	let mut passphrase = prompt_passphrase("Passphrase, empty for none (hidden): ");
+	let seed = mnemonic.seed(&passphrase);
+	memory::zeroize_string(&mut passphrase);
+	let hex = hex::encode(seed.as_bytes());
+
+	secret_shown();
+	println!();
+	println!("!!!! WARNING: the raw seed is as sensitive as the mnemonic, it controls !!!!");
+	println!("!!!! every wallet derived from it. Never share or store it unencrypted  !!!!");
+	println!();
+	println!("Seed: {}", seed.to_grouped_hex(8));
+	print_qr(seed.as_bytes());
+	copy(&hex, true);
+}
+
+/// Prints `data` as a QR code in byte mode, with the `qr` feature.
+#[cfg(feature = "qr")]
+fn print_qr(data: &[u8]) {
+	use qrcode::render::unicode::Dense1x2;
+	let code = qrcode::QrCode::new(data).expect("Cannot encode QR code");
+	println!("{}", code.render::<Dense1x2>().quiet_zone(true).build());
+}
+
+#[cfg(not(feature = "qr"))]
+fn print_qr(_data: &[u8]) {
+	println!("Built without the qr feature, no QR code");
+}
+
+/// Option 1: a new mnemonic from dice rolls, then its child mnemonics.
+fn generate(confirm: bool, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let mnemonic = dice(input, out);
+	print_mnemonic(&mnemonic, input, out);
+	if confirm {
+		confirm_mnemonic(&mnemonic);
+	}
+	copy(&mnemonic.to_string(), true);
+	record_session(&mnemonic.seed("").fingerprint(), "generate", |_| {});
+	print_child_mnemonics(&mnemonic, input, out);
+}
+
+/// Option 2: child mnemonics of an entered mnemonic.
+fn derive_children(input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let mnemonic = enter_mnemonic(input, out);
+	print_mnemonic(&mnemonic, input, out);
+	print_child_mnemonics(&mnemonic, input, out);
+}
+
+fn print_child_mnemonics(
+	mnemonic: &Mnemonic,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) {
+	weak_entropy_banner(out);
+	writeln!(
+		out,
+		"Keep pressing ENTER to generate child mnemonics, q to quit"
+	);
+
+	let root_key = mnemonic.root_key("").unwrap();
+	let mut line = String::new();
+	for i in 0.. {
+		line.clear();
+		if !input.read_line(&mut line) {
+			return;
+		}
+		out.write_str(&child_mnemonic_lines(&root_key, i));
+		audit(|| Event::Derive {
+			wallet: Fingerprint(root_key.fingerprint()),
+			path: bip85::Bip39 {
+				words: 24,
+				index: i,
+			}
+			.path()
+			.unwrap(),
+		});
+	}
+}
+
+/// 24 word child mnemonic `i` with its BIP85 path and lineage.
+fn child_mnemonic_lines(root_key: &ExtKey<SecretKey>, i: u32) -> String {
+	let app = bip85::Bip39 {
+		words: 24,
+		index: i,
+	};
+	let path = app.path().unwrap();
+	let child = root_key.derive_app(app).unwrap();
+	format!(
+		"{i}: {child}\n   {}\n   {}\n",
+		format_path(&path),
+		child_lineage(&child)
+	)
+}
+
+/// Fingerprint and first addresses of a child, to tell children apart later.
+fn child_lineage(child: &Mnemonic) -> String {
+	let seed = child.seed("");
+	let root_key = seed.root_key().expect("Cannot derive root key");
+	let eth = root_key
+		.derive_path(&Scheme::Bip44.path(0))
+		.map_or("-".to_string(), |key| key.address().to_string());
+	let path = [
+		Hardened(84),
+		Hardened(slip44::BTC),
+		Hardened(0),
+		0.into(),
+		0.into(),
+	];
+	let btc = root_key
+		.derive_path(&path)
+		.and_then(|key| AddressType::P2wpkh.address(&key.neuter().key().serialize()))
+		.unwrap_or("-".to_string());
+	format!("fingerprint {}, ETH {eth}, BTC {btc}", seed.fingerprint())
+}
+
+fn find_child_index() {
+	println!("Child:");
+	let child = prompt_mnemonic();
+	println!("Parent:");
+	let parent = prompt_mnemonic();
+	print!("Search indices below [1000]: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let bound: u32 = match input.trim() {
+		"" => 1000,
+		v => v.parse().expect("Invalid bound"),
+	};
+	let root_key = parent.root_key("").expect("Cannot derive root key");
+	let found = bip85::find_child_index(&root_key, &child, bound, |i| {
+		if i % 100 == 0 {
+			print!("\rSearching {i}/{bound}");
+			stdout().flush().unwrap();
+		}
+	})
+	.unwrap_or_else(|err| panic!("Search failed: {err:?}"));
+	print!("\r{:<30}\r", "");
+	match found {
+		Some(i) => println!(
+			"Child mnemonic {i} of the parent ({})",
+			child_lineage(&child)
+		),
+		None => println!("Not a BIP85 child of the parent below index {bound}"),
+	}
+}
+
+fn export_child_mnemonics(
+	mnemonic: &Mnemonic,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) {
+	weak_entropy_banner(out);
+	let mut line = String::new();
+	write!(out, "First child index [0]: ");
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let start: u32 = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid index"),
+	};
+	write!(out, "Number of children [10]: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let count: u32 = match line.trim() {
+		"" => 10,
+		v => v.parse().expect("Invalid number"),
+	};
+	let Some(format) = prompt_export_format(input, out) else {
+		return;
+	};
+	write!(out, "File: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let file = line.trim().to_string();
+
+	let end = start.checked_add(count).expect("Invalid index range");
+	let root_key = mnemonic.root_key("").expect("Cannot derive root key");
+	let export = bip85::export_children(&root_key, start..end, format)
+		.unwrap_or_else(|err| panic!("Export failed: {err:?}"));
+	write!(
+		out,
+		"{file} will contain {count} child mnemonics in plain text. Write it? [y/N]: "
+	);
+	line.clear();
+	input.read_line(&mut line);
+	if !line.trim().eq_ignore_ascii_case("y") {
+		writeln!(out, "Nothing written");
+		return;
+	}
+	let mut sink = FileSink::private(&file);
+	sink.write_str(&export);
+	save(sink, out);
+}
+
+/// Asks which coin, then which of its address schemes if it has several,
+/// with the coin's default scheme first.
+fn prompt_coin(input: &mut dyn InputSource, out: &mut dyn OutputSink) -> Option<&'static Coin> {
+	let symbols: Vec<_> = slip44::REGISTRY
+		.iter()
+		.filter(|r| !portfolio::schemes(r).is_empty())
+		.map(|r| r.symbol)
+		.collect();
+	write!(out, "Which coin? {} [{}]: ", symbols.join(", "), symbols[0]);
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return None;
+	}
+	let symbol = match line.trim() {
+		"" => symbols[0],
+		v => v,
+	};
+	let Some(registration) = slip44::find(symbol) else {
+		writeln!(out, "Unknown coin, expected one of {}", symbols.join(", "));
+		return None;
+	};
+	let schemes = portfolio::schemes(registration);
+	if schemes.is_empty() {
+		writeln!(out, "No address scheme for {}", registration.symbol);
+		return None;
+	}
+	if schemes.len() == 1 {
+		return Some(schemes[0]);
+	}
+	for (i, coin) in schemes.iter().enumerate() {
+		writeln!(out, "{}) {}", i + 1, coin.name);
+	}
+	write!(out, "Scheme [1]: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return None;
+	}
+	let scheme = match line.trim() {
+		"" => Some(schemes[0]),
+		v => v
+			.parse::<usize>()
+			.ok()
+			.and_then(|i| schemes.get(i.wrapping_sub(1)))
+			.copied(),
+	};
+	if scheme.is_none() {
+		writeln!(out, "Invalid scheme");
+	}
+	scheme
+}
+
+/// Option 35, receive addresses of the coin and scheme `prompt_coin` picks.
+fn coin_addresses(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let Some(coin) = prompt_coin(input, out) else {
+		return;
+	};
+	write!(out, "Account index [0]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let account: u32 = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid account index"),
+	};
+	write!(out, "Number of addresses [5]: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let count: u32 = match line.trim() {
+		"" => 5,
+		v => v.parse().expect("Invalid number"),
+	};
+	let root_key = mnemonic.root_key("").unwrap();
+	writeln!(out);
+	writeln!(
+		out,
+		"{} account {}",
+		coin.name,
+		format_path(&coin.account_path(account))
+	);
+	for i in 0..count {
+		let path = coin.address_path(account, i);
+		let address = root_key
+			.derive_path(&path)
+			.and_then(|key| (coin.address)(&key.neuter().key().serialize()));
+		match address {
+			Some(address) => writeln!(out, "{}  {address}", format_path(&path)),
+			None => writeln!(out, "{}  cannot derive", format_path(&path)),
+		}
+	}
+}
+
+fn export_paper_wallet(mnemonic: &Mnemonic) {
+	weak_entropy_banner(&mut Stdout);
+	println!("Note: BTC now defaults to P2WPKH (bc1...), earlier versions printed P2PKH (1...)");
+	let Some(coin) = prompt_coin(&mut Terminal, &mut Stdout) else {
+		return;
+	};
+	print!("Account index [0]: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let account = match input.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid account index"),
+	};
+	print!("Number of addresses [5]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let addresses = match input.trim() {
+		"" => 5,
+		v => v.parse().expect("Invalid number"),
+	};
+	let mut root_key = false;
+	if SHOW_ROOT_KEY.load(Ordering::Relaxed) {
+		print!("Include the root xprv below the fold? [y/N]: ");
+		stdout().flush().unwrap();
+		input.clear();
+		read_line(&mut input);
+		root_key = input.trim().eq_ignore_ascii_case("y");
+	}
+	print!("File: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+
+	let sheet = paper::Sheet {
+		mnemonic,
+		coin,
+		account,
+		addresses,
+		root_key,
+	};
+	let html = paper::render(&sheet).expect("Cannot derive addresses");
+	let file = input.trim().to_string();
+	print!("{file} will contain the mnemonic in plain text. Write it? [y/N]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	if !input.trim().eq_ignore_ascii_case("y") {
+		println!("Nothing written");
+		return;
+	}
+	let mut sink = FileSink::private(&file);
+	sink.write_str(&html);
+	save(sink, &mut Stdout);
+}
+
+fn print_portfolio(mnemonic: &Mnemonic) {
+	let mut passphrase = prompt_passphrase("Passphrase, empty for none (hidden): ");
+	print!("Account index [0]: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let account = match input.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid account index"),
+	};
+	let portfolio = portfolio::portfolio(mnemonic, &passphrase, account);
+	memory::zeroize_string(&mut passphrase);
+	println!();
+	print!("{}", portfolio.expect("Cannot derive accounts"));
+}
+
+fn inspect_private_key() {
+	print!("Enter private key: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	secret_shown();
+	let key = match ImportedKey::parse(&input) {
+		Ok(key) => key,
+		Err(err) => {
+			println!("Invalid private key: {err:?}");
+			exit(1);
+		}
+	};
+	memory::zeroize_string(&mut input);
+	println!();
+	print!("{}", key.describe());
+}
+
+/// Option 30: Seed XOR, compatible with Coldcard.
+fn seed_xor(input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	writeln!(out, "1) Split a 24-word mnemonic into parts");
+	writeln!(out, "2) Combine parts into the mnemonic");
+	write!(out, "Choice [1]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	match line.trim() {
+		"" | "1" => split_seed_xor(input, out),
+		"2" => combine_seed_xor(input, out),
+		_ => writeln!(out, "Unknown choice"),
+	}
+}
+
+fn split_seed_xor(input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let mnemonic = enter_mnemonic(input, out);
+	write!(out, "Number of parts, 2 to {MAX_PARTS} [3]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let count = match line.trim() {
+		"" => 3,
+		v => v.parse().expect("Invalid number of parts"),
+	};
+	let parts = match seedxor::split(&mnemonic, count) {
+		Ok(parts) => parts,
+		Err(err) => {
+			writeln!(out, "Cannot split mnemonic: {err:?}");
+			return;
+		}
+	};
+	secret_shown();
+	weak_entropy_banner(out);
+	writeln!(out);
+	for (part, label) in parts.iter().zip('A'..) {
+		writeln!(out, "Part {label}: {part}");
+	}
+	writeln!(out);
+	writeln!(
+		out,
+		"All {count} parts are needed to restore, in any order. Each part alone is a valid, empty wallet"
+	);
+}
+
+fn combine_seed_xor(input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let mut parts = vec![];
+	let mut line = String::new();
+	for label in ('A'..).take(MAX_PARTS) {
+		if parts.len() < 2 {
+			write!(out, "Part {label}: ");
+		} else {
+			write!(out, "Part {label}, empty when done: ");
+		}
+		line.clear();
+		if !input.read_line(&mut line) {
+			return;
+		}
+		if parts.len() >= 2 && line.trim().is_empty() {
+			break;
+		}
+		parts.push(parse_mnemonic(line.trim(), out));
+	}
+	memory::zeroize_string(&mut line);
+	let mnemonic = match seedxor::combine(&parts) {
+		Ok(mnemonic) => mnemonic,
+		Err(err) => {
+			writeln!(out, "Cannot combine parts: {err:?}");
+			return;
+		}
+	};
+	confirm_weakness(&mnemonic, input, out);
+	print_mnemonic(&mnemonic, input, out);
+}
+
+#[cfg(feature = "rsa")]
+fn print_rsa_key(mnemonic: &Mnemonic) {
+	weak_entropy_banner(&mut Stdout);
+	let mut input = String::new();
+	print!("Key size, 1024 2048 3072 or 4096 bits [2048]: ");
+	stdout().flush().unwrap();
+	read_line(&mut input);
+	let bits: u32 = match input.trim() {
+		"" => 2048,
+		v => v.parse().expect("Invalid key size"),
+	};
+	print!("Key index [0]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let index: u32 = match input.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid index"),
+	};
+	println!("Generating key, this can take a while");
+	let root_key = mnemonic.root_key("").expect("Cannot derive root key");
+	let app = bip85::Rsa { bits, index };
+	let path = app.path().expect("Invalid key size");
+	let pem = root_key.derive_app(app).expect("Invalid key size");
+	secret_shown();
+	println!("{}", format_path(&path));
+	audit(|| Event::Derive {
+		wallet: Fingerprint(root_key.fingerprint()),
+		path,
+	});
+	let shown = Secret::new(format!("\n{}", pem.trim_end()));
+	if confirm_reveal("Private key", shown, &mut Terminal, &mut Stdout) {
+		copy(&pem, true);
+	}
+}
+
+#[cfg(not(feature = "rsa"))]
+fn print_rsa_key(_mnemonic: &Mnemonic) {
+	println!("Built without the rsa feature, no RSA keys");
+}
+
+/// Option 31: the same label, length and index always give the same secret.
+fn labeled_secret(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	weak_entropy_banner(out);
+	write!(out, "Label, e.g. a site or service name: ");
+	let mut label = String::new();
+	if !input.read_line(&mut label) {
+		return;
+	}
+	let label = label.trim();
+	let mut line = String::new();
+	write!(out, "Length in bytes [32]: ");
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let len = match line.trim() {
+		"" => 32,
+		v => v.parse().expect("Invalid length"),
+	};
+	write!(out, "Encoding, hex or base64 [hex]: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let base64 = match line.trim() {
+		"" | "hex" => false,
+		"base64" => true,
+		_ => panic!("Invalid encoding"),
+	};
+	write!(out, "Index [0]: ");
+	line.clear();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let index = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid index"),
+	};
+
+	let root_key = mnemonic.root_key("").unwrap();
+	let app = bip85::Labeled { label, len, index };
+	let Some(path) = app.path() else {
+		writeln!(out, "Length must be 1 to 8160 bytes");
+		return;
+	};
+	let secret = root_key.derive_app(app).unwrap();
+	let mut secret = if base64 {
+		util::base64(&secret)
+	} else {
+		hex::encode(secret)
+	};
+	secret_shown();
+	writeln!(out);
+	writeln!(
+		out,
+		"Entropy: {}, HKDF-SHA256 info {label:?}",
+		format_path(&path)
+	);
+	audit(|| Event::Derive {
+		wallet: Fingerprint(root_key.fingerprint()),
+		path,
+	});
+	if confirm_reveal("Secret", Secret::new(secret.clone()), input, out) {
+		copy(&secret, true);
+	}
+	memory::zeroize_string(&mut secret);
+}
+
+/// Option 34: an age X25519 identity and its recipient.
+fn age_identity(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	weak_entropy_banner(out);
+	write!(out, "Index [0]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let index = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid index"),
+	};
+
+	let root_key = Ed25519Key::from_seed(mnemonic.seed(""));
+	let Some(identity) = AgeIdentity::new(&root_key, index) else {
+		writeln!(out, "Index must be below 2^31");
+		return;
+	};
+	let secret = identity.identity();
+	secret_shown();
+	writeln!(out);
+	writeln!(out, "Path:      {}", format_path(&age::path(index)));
+	writeln!(out, "Recipient: {}", identity.recipient());
+	writeln!(out, "Identity:  {}", secret.reveal());
+	copy(secret.reveal(), true);
+}
+
+/// Option 33: the same user ID and index always give the same key and
+/// fingerprint.
+fn openpgp_key(mnemonic: &Mnemonic, input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	weak_entropy_banner(out);
+	write!(out, "User ID, e.g. Name <email>: ");
+	let mut user_id = String::new();
+	if !input.read_line(&mut user_id) {
+		return;
+	}
+	let user_id = user_id.trim();
+	write!(out, "Index [0]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return;
+	}
+	let index = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid index"),
+	};
+
+	let root_key = Ed25519Key::from_seed(mnemonic.seed(""));
+	let Some(key) = OpenPgpKey::new(&root_key, user_id, index) else {
+		writeln!(out, "Index must be below 2^31");
+		return;
+	};
+	let block = key.secret_key_block();
+	secret_shown();
+	writeln!(out);
+	writeln!(
+		out,
+		"Signing key:    {} {}",
+		format_path(&openpgp::path(index, false)),
+		hex::encode_upper(key.fingerprint())
+	);
+	writeln!(
+		out,
+		"Encryption key: {} {}",
+		format_path(&openpgp::path(index, true)),
+		hex::encode_upper(key.subkey_fingerprint())
+	);
+	writeln!(
+		out,
+		"Import with gpg --import, then set a passphrase with gpg --edit-key"
+	);
+	writeln!(out);
+	write!(out, "{block}");
+	copy(&block, true);
+}
+
+/// Option 32: asks for the words in a random order, as Ledger's recovery
+/// check does. Input is hidden.
+fn recovery_check(mnemonic: &Mnemonic) {
+	let order = util::shuffle(mnemonic.to_string().split_whitespace().count());
+	let strict = STRICT_CHECK.load(Ordering::Relaxed);
+	check_words_in_order(mnemonic, &order, strict, &mut HiddenTerminal, &mut Stdout);
+}
+
+/// Only the overall result is shown, so an observer does not learn which
+/// word was wrong, unless `strict`. Typed words are NFKD normalized, as the
+/// word lists are.
+fn check_words_in_order(
+	mnemonic: &Mnemonic,
+	order: &[usize],
+	strict: bool,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) -> bool {
+	let phrase = mnemonic.to_string();
+	let words: Vec<_> = phrase.split_whitespace().collect();
+	let mut correct = vec![false; words.len()];
+	let mut line = String::new();
+	for &i in order {
+		write!(out, "Enter word #{}: ", i + 1);
+		line.clear();
+		if !input.read_line(&mut line) {
+			memory::zeroize_string(&mut line);
+			return false;
+		}
+		correct[i] = line.trim().to_lowercase().nfkd().eq(words[i].chars());
+	}
+	memory::zeroize_string(&mut line);
+	let pass = correct.iter().all(|&c| c);
+	writeln!(out);
+	if strict {
+		for (i, c) in correct.iter().enumerate() {
+			writeln!(out, "#{}: {}", i + 1, if *c { "ok" } else { "wrong" });
+		}
+	}
+	if pass {
+		writeln!(out, "PASS: all {} words match", words.len());
+	} else {
+		writeln!(out, "FAIL: the words do not match the mnemonic");
+	}
+	pass
+}
+
+/// Writes an export file, with `--dry-run` only shows where and what.
+fn save(file: FileSink, out: &mut dyn OutputSink) {
+	let dry_run = DRY_RUN.load(Ordering::Relaxed);
+	let path = file.path().to_string();
+	file.save(dry_run, out).expect("Cannot write export file");
+	if !dry_run {
+		writeln!(out, "Saved {path}");
+		audit(|| Event::Export {
+			wallet: None,
+			file: path.into(),
+		});
+	}
+}
+
+fn prompt_export_format(
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) -> Option<ExportFormat> {
+	write!(out, "Format, 1) CSV 2) JSON [1]: ");
+	let mut line = String::new();
+	if !input.read_line(&mut line) {
+		return None;
+	}
+	match line.trim() {
+		"" | "1" => Some(ExportFormat::Csv),
+		"2" => Some(ExportFormat::Json),
+		_ => panic!("Invalid format"),
+	}
+}
+
+fn compare_ledger_metamask(root_key: &ExtKey<SecretKey>) {
+	print!("Number of indices [10]: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let count: u32 = match input.trim() {
+		"" => 10,
+		v => v.parse().expect("Invalid number"),
+	};
+	let rows = eth::compare_ledger_metamask(root_key, 0..count);
+	println!();
+	print!(
+		"{}",
+		util::table(
+			&[
+				"#",
+				"Ledger Live path",
+				"Address",
+				"MetaMask path",
+				"Address"
+			],
+			&rows
+		)
+	);
+
+	println!();
+	print!("Export to file (leave empty to skip): ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let file = input.trim().to_string();
+	if !file.is_empty() {
+		let Some(format) = prompt_export_format(&mut Terminal, &mut Stdout) else {
+			return;
+		};
+		let mut sink = FileSink::new(&file);
+		sink.write_str(&util::export(
+			format,
+			&eth::COMPARE_HEADER,
+			&["index"],
+			&rows,
+		));
+		save(sink, &mut Stdout);
+	}
+}
+
+/// Option 3, prompts for a mnemonic or xprv and lists addresses until input
+/// ends.
+fn eth_addresses(input: &mut dyn InputSource, out: &mut dyn OutputSink) {
+	let root_key = prompt_root_key(input, out);
+	print_eth_addresses(&root_key, input, out);
+}
+
+fn print_eth_addresses(
+	root_key: &ExtKey<SecretKey>,
+	input: &mut dyn InputSource,
+	out: &mut dyn OutputSink,
+) {
+	let scheme = Scheme::LedgerLive;
+	write!(out, "Account index [0]: ");
+	let mut line = String::new();
+	input.read_line(&mut line);
+	let account = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid account index"),
+	};
+	write!(out, "Start index [0]: ");
+	line.clear();
+	input.read_line(&mut line);
+	let start = match line.trim() {
+		"" => 0,
+		v => v.parse().expect("Invalid start index"),
+	};
+	writeln!(out, "Scheme: {scheme}");
+	let path = scheme.account_path(account, start);
+	audit(|| Event::Derive {
+		wallet: Fingerprint(root_key.fingerprint()),
+		path: path.clone(),
+	});
+	writeln!(out, "Derivation path: {}", format_path(&path));
+	if let Some(parent) = bip32::unhardened_parent(&path) {
+		unhardened_advisory(&format_path(parent), input, out);
+	}
+	record_session(
+		&hex::encode(root_key.fingerprint()),
+		"eth addresses",
+		|session| {
+			let scheme = format!("ETH {scheme}");
+			if !session.schemes.contains(&scheme) {
+				session.schemes.push(scheme);
+			}
+		},
+	);
+	writeln!(out, "Keep pressing ENTER to generate addresses, q to quit");
+
+	let mut i = start;
+	loop {
+		line.clear();
+		if !input.read_line(&mut line) {
+			return;
+		}
+		let end = i.saturating_add(4);
+		for (i, address) in eth::addresses(root_key, scheme, account, i..end) {
+			match address {
+				Some(address) => {
+					writeln!(out, "{address}{}", check_suffix(&address.to_string()));
+					if i == start {
+						copy(&address.to_string(), false);
+					}
+				}
+				None => writeln!(
+					out,
+					"{}: no key at this path, skipped",
+					format_path(&scheme.account_path(account, i))
+				),
+			}
+		}
+		if end == u32::MAX {
+			return;
+		}
+		i = end;
+	}
+}
+
+fn verify_mnemonic(mnemonic: &Mnemonic) {
+	println!(
+		"Enter expected values as '<path> <xpub|ypub|zpub|address>', e.g. m/44'/60'/0'/0/0 0x..."
+	);
+	println!("Enter an empty line to finish");
+
+	let mut expected = Vec::new();
+	let mut input = String::new();
+	loop {
+		input.clear();
+		read_line(&mut input);
+		if input.trim().is_empty() {
+			break;
+		}
+		match input.parse::<Expectation>() {
+			Ok(exp) => expected.push(exp),
+			Err(err) => println!("Ignored: {err:?}"),
+		}
+	}
+
+	for parent in &expected {
+		for child in &expected {
+			let (Artifact::ExtKey(pk, _), Artifact::ExtKey(ck, _)) =
+				(&parent.artifact, &child.artifact)
+			else {
+				continue;
+			};
+			// xpubs cannot derive hardened children, those are not checked
+			if child.path.len() == parent.path.len() + 1
+				&& child.path.starts_with(&parent.path)
+				&& matches!(ck.number(), ChildNumber::Normal(_))
+				&& ck.verify_child_of(pk).is_err()
+			{
+				println!(
+					"Warning: xpub at {} is not a child of the xpub at {}",
+					format_path(&child.path),
+					format_path(&parent.path)
+				);
+			}
+		}
+	}
+
+	let root_key = mnemonic.root_key("").unwrap();
+	let report = verify(&root_key, &expected);
+	println!();
+	print_fingerprint(&root_key, &mut Stdout);
+	print!("{report}");
+	if report.is_match() {
+		println!("All {} checks match", expected.len());
+		record_session(&hex::encode(root_key.fingerprint()), "verify", |_| {});
+	} else {
+		println!("!!!! WARNING: mnemonic does not match all expected values !!!!");
+	}
+}
+
+fn scan_addresses(mnemonic: &Mnemonic) {
+	print!("Address list file (one address per line): ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let contents = fs::read_to_string(input.trim()).expect("Cannot read address list");
+
+	let mut addresses = HashSet::new();
+	for (n, line) in contents.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		match line.parse::<Address>() {
+			Ok(address) => {
+				addresses.insert(address);
+			}
+			Err(err) => println!("Line {}: ignored ({err:?})", n + 1),
+		}
+	}
+
+	print!("Gap limit [{DEFAULT_GAP}]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let gap = match input.trim() {
+		"" => DEFAULT_GAP,
+		v => v.parse().expect("Invalid gap limit"),
+	};
+
+	let root_key = mnemonic.root_key("").unwrap();
+	println!();
+	print_fingerprint(&root_key, &mut Stdout);
+	println!(
+		"Scanning {} addresses with gap limit {gap}",
+		addresses.len()
+	);
+	for branch in scan(&root_key, &addresses, gap) {
+		println!();
+		println!("{}", branch.scheme);
+		for &i in &branch.used {
+			println!("  {}", format_path(&branch.scheme.path(i)));
+		}
+		match branch.highest() {
+			Some(i) => println!("  Highest used index: {i}"),
+			None => println!("  No matches"),
+		}
+	}
+}
+
+fn multisig_wallet() {
+	print!("Number of cosigners: ");
+	stdout().flush().unwrap();
+	let mut input = String::new();
+	read_line(&mut input);
+	let n: usize = input.trim().parse().expect("Invalid number of cosigners");
+
+	print!("Threshold: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let threshold: usize = input.trim().parse().expect("Invalid threshold");
+
+	print!("Script type (1 = P2WSH, 2 = P2SH-P2WSH) [1]: ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let script_type = match input.trim() {
+		"" | "1" => ScriptType::P2wsh,
+		"2" => ScriptType::P2shP2wsh,
+		_ => panic!("Invalid script type"),
+	};
+	let path = script_type.path(0);
+
+	let mut cosigners = Vec::new();
+	for i in 1..=n {
+		println!();
+		println!("Cosigner {i}: enter mnemonic or '[fingerprint/path]xpub'");
+		print!("Leave empty to generate a new mnemonic from dice rolls: ");
+		stdout().flush().unwrap();
+		input.clear();
+		read_line(&mut input);
+		let cosigner = match input.trim() {
+			"" => {
+				let mnemonic = dice(&mut Terminal, &mut Stdout);
+				print_mnemonic(&mnemonic, &mut Terminal, &mut Stdout);
+				let root_key = mnemonic.root_key("").unwrap();
+				Cosigner::from_root(&root_key, &path).unwrap()
+			}
+			s if s.starts_with('[') => s.parse().expect("Invalid cosigner"),
+			s => {
+				let mnemonic = Mnemonic::from_phrase(s).expect("Invalid mnemonic");
+				let root_key = mnemonic.root_key("").unwrap();
+				Cosigner::from_root(&root_key, &path).unwrap()
+			}
+		};
+		println!("Key origin: {cosigner}");
+		cosigners.push(cosigner);
+	}
+
+	let wallet = Multisig::new(threshold, script_type, cosigners).expect("Invalid wallet");
+	println!();
+	println!("Policy: {threshold} of {n} {script_type}");
+	let (receive, change) = (wallet.descriptor(false), wallet.descriptor(true));
+	println!("Receive: {receive}{}", check_suffix(&receive));
+	copy(&receive, false);
+	println!("Change:  {change}{}", check_suffix(&change));
+	println!();
+	for i in 0..10 {
+		let address = wallet.address(false, i).unwrap();
+		println!("{i}: {address}{}", check_suffix(&address));
+	}
+
+	println!();
+	print!("Save setup file for Sparrow/Specter (leave empty to skip): ");
+	stdout().flush().unwrap();
+	input.clear();
+	read_line(&mut input);
+	let file = input.trim();
+	if !file.is_empty() {
+		let mut sink = FileSink::new(file);
+		sink.write_str(&wallet.export("Keymaker multisig"));
+		save(sink, &mut Stdout);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use keymaker::console::{Buffer, Script};
+
+	#[test]
+	fn mnemonic_details() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let xprv = mnemonic.root_key("").unwrap().xprv();
+		for format in [
+			MnemonicFormat::Columns,
+			MnemonicFormat::Line,
+			MnemonicFormat::Numbered,
+			MnemonicFormat::Grid,
+		] {
+			let out = super::mnemonic_details(&mnemonic, format);
+			assert!(out.contains("Master fingerprint: 73c5da0a\n"));
+			assert!(!out.contains(xprv.reveal().as_str()));
+			assert!(!out.contains("xprv"));
+			assert!(!out.contains(&mnemonic.seed("").to_hex()));
+		}
+		assert_eq!(
+			super::mnemonic_details(&mnemonic, MnemonicFormat::Line),
+			"Standard: BIP39, 128-bit security
+Mnemonic: abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about
+Master fingerprint: 73c5da0a
+Identifier: 73c5da0a03d2d0803b731f04242bb40ced2f8bbc
+"
+		);
+	}
+
+	#[test]
+	fn dice_entropy_without_rolls() {
+		for rolls in ["789 789", "", "0000"] {
+			let mut input = Script::new(&[rolls, ""]);
+			let mut out = Buffer::default();
+			assert_eq!(
+				super::dice_entropy(32, &mut input, &mut out),
+				None,
+				"{rolls:?}"
+			);
+			assert_eq!(
+				out.0,
+				"Throw at least 154 dice to ensure 256 bit security
+Enter dice rolls: 
+!!!! ERROR: no valid dice rolls entered, refusing to derive a mnemonic !!!!
+"
+			);
+		}
+		let mut input = Script::new(&["7891", "", "", ""]);
+		assert!(super::dice_entropy(16, &mut input, &mut Buffer::default()).is_some());
+	}
+
+	#[test]
+	fn generate() {
+		let rolls = "123456".repeat(17);
+		let mut lines = vec!["12", &rolls];
+		if cfg!(feature = "salt") {
+			lines.push("");
+		}
+		lines.extend(["", "", "q"]);
+		let mut out = Buffer::default();
+		super::generate(false, &mut Script::new(&lines), &mut out);
+		let salt = if cfg!(feature = "salt") {
+			"Enter optional salt: "
+		} else {
+			""
+		};
+		assert_eq!(
+			out.0,
+			format!(
+				"Generate mnemonic from dice rolls
+Word count (12, 15, 18, 21 or 24) [24]: Throw at least 77 dice to ensure 128 bit security
+Enter dice rolls: {salt}
+Standard: BIP39, 128-bit security
+Mnemonic: plate spider disagree sort today invite despair strategy stuff raven casual normal
+Master fingerprint: 617e4703
+Identifier: 617e4703b46c6d0484b15d3ea643d8e10ff717e0
+
+Keep pressing ENTER to generate child mnemonics, q to quit
+0: swarm bargain display sudden abandon blue timber verify giraffe ill butter true canyon raise select artefact mind thing grocery enemy snow pen exchange control
+   m/83696968'/39'/0'/24'/0'
+   fingerprint 7d3e8cdd, ETH 0xaf680d31871bc7a91fe625F9120BE648Ea385373, BTC bc1q2av6wh5fzzhnp6n45lprce4w5zrh7yzgm3xd3p
+1: food lock decrease chat moral middle sweet focus energy outside curious fiscal squirrel loyal memory project effort avoid vessel aspect local reopen dinosaur token
+   m/83696968'/39'/0'/24'/1'
+   fingerprint 357f2ee2, ETH 0x7D2788704724E3B669b027db046F4c6E897D8C6e, BTC bc1qyh8dk9wrgwnjgc4u909jv0f28rdmhkynxddpaz
+"
+			)
+		);
+	}
+
+	#[test]
+	fn path_table() {
+		let root_key = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let paths = bip32::parse_path_list("# batch\nm/44'/60'/0'/0/0\n\nm/84'/0'/0'/0/0\nm/0/1\n")
+			.unwrap();
+		let exp = "\
+line  path              address                                     public key
+----  ----------------  ------------------------------------------  ------------------------------------------------------------------
+2     m/44'/60'/0'/0/0  0x9858EfFD232B4033E47d90003D41EC34EcaEda94  0237b0bb7a8288d38ed49a524b5dc98cff3eb5ca824c9f9dc0dfdb3d9cd600f299
+4     m/84'/0'/0'/0/0   bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu  0330d54fd0dd420a6e5f8d3624f5f3482cae350f79d5f0753bf5beef9c2d91af3c
+5     m/0/1             -                                           03446801102d378f09aa200debc1acdff0f6fcf1c6d9bc1e2c7e14076d5fbc740e
+";
+		assert_eq!(super::path_table(&root_key, &paths), exp);
+	}
+
+	#[test]
+	fn seed_xor() {
+		let parts = [
+			"romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room",
+			"lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge",
+			"vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate",
+		];
+		let mut input = Script::new(&["2", parts[0], parts[1], parts[2], ""]);
+		let mut out = Buffer::default();
+		super::seed_xor(&mut input, &mut out);
+		assert_eq!(
+			out.0,
+			"1) Split a 24-word mnemonic into parts
+2) Combine parts into the mnemonic
+Choice [1]: Part A: Part B: Part C, empty when done: Part D, empty when done: 
+Standard: BIP39, 256-bit security
+Mnemonic: silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor
+Master fingerprint: e335e9c4
+Identifier: e335e9c43bb1cd4050aaeec58ab8971787cdd8b1
+
+"
+		);
+
+		let phrase = "silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor";
+		let mut input = Script::new(&["", phrase, "2"]);
+		let mut out = Buffer::default();
+		super::seed_xor(&mut input, &mut out);
+		let split: Vec<_> = out
+			.0
+			.lines()
+			.filter_map(|l| l.strip_prefix("Part "))
+			.map(|l| Mnemonic::from_phrase(&l[3..]).unwrap())
+			.collect();
+		assert_eq!(split.len(), 2);
+		assert_eq!(seedxor::combine(&split).unwrap().to_string(), phrase);
+	}
+
+	#[test]
+	fn labeled_secret() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let data = [
+			(
+				["github.com", "", "", "", "y"],
+				"Entropy: m/83696968'/4997708'/0', HKDF-SHA256 info \"github.com\"
+Show secret? [y/N]: Secret: f4cbb91fad90f37db44e3391d0da02a553dda3221404feafe82ec03ffde15690
+",
+			),
+			(
+				["api token", "16", "base64", "1", "y"],
+				"Entropy: m/83696968'/4997708'/1', HKDF-SHA256 info \"api token\"
+Show secret? [y/N]: Secret: XaJyWtp8CfYOAQ8GmDi6vg==
+",
+			),
+			(
+				["api token", "16", "base64", "1", ""],
+				"Entropy: m/83696968'/4997708'/1', HKDF-SHA256 info \"api token\"
+Show secret? [y/N]: ",
+			),
+		];
+		for (script, exp) in data {
+			let mut input = Script::new(&script);
+			let mut out = Buffer::default();
+			super::labeled_secret(&mnemonic, &mut input, &mut out);
+			assert_eq!(
+				out.0,
+				format!("Label, e.g. a site or service name: Length in bytes [32]: Encoding, hex or base64 [hex]: Index [0]: \n{exp}")
+			);
+		}
+
+		let mut input = Script::new(&["github.com", "0", "", ""]);
+		let mut out = Buffer::default();
+		super::labeled_secret(&mnemonic, &mut input, &mut out);
+		assert!(out.0.ends_with("Length must be 1 to 8160 bytes\n"));
+	}
+
+	#[test]
+	fn prompt_coin() {
+		let data: [(&[&str], &str, &str); 5] = [
+			(&["", ""], "m/84'/0'/0'", "BTC P2WPKH"),
+			(&["btc", "4"], "m/86'/0'/0'", "BTC P2TR"),
+			(&["eth", ""], "m/44'/60'/0'", "ETH BIP44 (MetaMask)"),
+			(&["ETH", "3"], "m/44'/60'/0'", "ETH Ledger legacy (MEW)"),
+			(&["avax"], "m/44'/9000'/0'", "AVAX X-chain"),
+		];
+		for (script, path, name) in data {
+			let mut input = Script::new(script);
+			let mut out = Buffer::default();
+			let coin = super::prompt_coin(&mut input, &mut out).unwrap();
+			assert_eq!(format_path(&coin.account_path(0)), path);
+			assert_eq!(coin.name, name);
+			assert!(out.0.starts_with("Which coin? BTC, ETH, AVAX [BTC]: "));
+		}
+
+		let mut input = Script::new(&["eth", ""]);
+		let mut out = Buffer::default();
+		super::prompt_coin(&mut input, &mut out);
+		assert_eq!(
+			out.0,
+			"Which coin? BTC, ETH, AVAX [BTC]: 1) ETH BIP44 (MetaMask)
+2) ETH Ledger Live
+3) ETH Ledger legacy (MEW)
+Scheme [1]: "
+		);
+
+		let data = [
+			(["xlm", ""], "No address scheme for XLM\n"),
+			(
+				["doge", ""],
+				"Unknown coin, expected one of BTC, ETH, AVAX\n",
+			),
+			(["btc", "5"], "Invalid scheme\n"),
+		];
+		for (script, exp) in data {
+			let mut input = Script::new(&script);
+			let mut out = Buffer::default();
+			assert!(super::prompt_coin(&mut input, &mut out).is_none());
+			assert!(out.0.ends_with(exp), "{}", out.0);
+		}
+	}
+
+	#[test]
+	fn coin_addresses() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let mut input = Script::new(&["btc", "", "", "2"]);
+		let mut out = Buffer::default();
+		super::coin_addresses(&mnemonic, &mut input, &mut out);
+		// BIP84 test vectors
+		assert!(out.0.ends_with(
+			"
+BTC P2WPKH account m/84'/0'/0'
+m/84'/0'/0'/0/0  bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu
+m/84'/0'/0'/0/1  bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g
+"
+		));
+
+		let mut input = Script::new(&["eth", "2", "", "2"]);
+		let mut out = Buffer::default();
+		super::coin_addresses(&mnemonic, &mut input, &mut out);
+		assert!(out.0.ends_with(
+			"
+ETH Ledger Live account m/44'/60'/0'
+m/44'/60'/0'/0/0  0x9858EfFD232B4033E47d90003D41EC34EcaEda94
+m/44'/60'/1'/0/0  0x78839F6054d7ed13918bAe0473BA31b1Ca9D7265
+"
+		));
+	}
+
+	#[test]
+	fn age_identity() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let data = [
+			(
+				"",
+				"Index [0]: 
+Path:      m/4278085'/0'
+Recipient: age15lnr3zqsym3zs379vugwtsyv7zdq9eswm4a4hpvup3xqug5gmvmqsx70ff
+Identity:  AGE-SECRET-KEY-1RPGYUKLVZK52ZNVK9WP6RQCHKH7Z9J6YV6WMC02G4MY49P55J34QTF7D4Z
+",
+			),
+			("2147483648", "Index [0]: Index must be below 2^31\n"),
+		];
+		for (index, exp) in data {
+			let mut input = Script::new(&[index]);
+			let mut out = Buffer::default();
+			super::age_identity(&mnemonic, &mut input, &mut out);
+			assert_eq!(out.0, exp);
+		}
+	}
+
+	#[test]
+	fn export_child_mnemonics() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let mut input = Script::new(&["0", "2", "", "children.csv", ""]);
+		let mut out = Buffer::default();
+		super::export_child_mnemonics(&mnemonic, &mut input, &mut out);
+		assert!(out.0.ends_with(
+			"File: children.csv will contain 2 child mnemonics in plain text. Write it? [y/N]: Nothing written\n"
+		));
+	}
+
+	#[test]
+	fn parse_mnemonic_prefixes() {
+		let mut out = Buffer::default();
+		let mnemonic = super::parse_mnemonic(&("aban ".repeat(11) + "abou"), &mut out);
+		assert_eq!(mnemonic.to_string(), "abandon ".repeat(11) + "about");
+		assert!(out.0.contains("Words read as their first four letters"));
+	}
+
+	#[test]
+	fn parse_mnemonic_electrum() {
+		// valid BIP39 with an Electrum standard version by chance
+		let phrase = "abandon ".repeat(11) + "mass";
+		assert!(electrum::seed_type(&phrase).is_some());
+		let mut out = Buffer::default();
+		let mnemonic = super::parse_mnemonic(&phrase, &mut out);
+		assert_eq!(mnemonic.to_string(), phrase);
+		assert_eq!(
+			out.0,
+			"Also a valid Electrum standard seed, read as BIP39\n"
+		);
+	}
+
+	#[test]
+	fn print_stellar_accounts() {
+		let mnemonic =
+			Mnemonic::from_entropy(hex::decode("713a3ae074e60e56c6bd0557c4984af1").unwrap());
+		let mut input = Script::new(&["y", "n", "", "", ""]);
+		let mut out = Buffer::default();
+		super::print_stellar_accounts(&mnemonic, &mut input, &mut out);
+		assert!(out.0.contains(
+			"m/44'/148'/0'
+  Account: GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6
+Show secret? [y/N]: Secret: SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN
+"
+		));
+		assert_eq!(out.0.matches("  Account: ").count(), 5);
+		assert_eq!(out.0.matches("Show secret? [y/N]: ").count(), 5);
+		assert_eq!(out.0.matches("Secret: S").count(), 1);
+	}
+
+	#[test]
+	fn openpgp_key() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let mut input = Script::new(&["Satoshi <satoshi@example.com>", ""]);
+		let mut out = Buffer::default();
+		super::openpgp_key(&mnemonic, &mut input, &mut out);
+		let (head, block) = out.0.split_once("\n\n-----").unwrap();
+		assert_eq!(
+			head,
+			"User ID, e.g. Name <email>: Index [0]: 
+Signing key:    m/5261136'/0'/0' 9F70153EF04D3789E9FEC52E23B19B3E3CC06A87
+Encryption key: m/5261136'/0'/1' EE642A9CDC4A3E30381B53EE19BE0D59376FE800
+Import with gpg --import, then set a passphrase with gpg --edit-key"
+		);
+		assert!(block.starts_with("BEGIN PGP PRIVATE KEY BLOCK-----\n\nxVgESV+rKRYJ"));
+		assert!(block.ends_with("-----END PGP PRIVATE KEY BLOCK-----\n"));
+
+		let mut input = Script::new(&["Satoshi", "2147483648"]);
+		let mut out = Buffer::default();
+		super::openpgp_key(&mnemonic, &mut input, &mut out);
+		assert!(out.0.ends_with("Index must be below 2^31\n"));
+	}
+
+	#[test]
+	fn check_words_in_order() {
+		let mnemonic = Mnemonic::from_phrase(
+			"legal winner thank year wave sausage worth useful legal winner thank yellow",
+		)
+		.unwrap();
+		let order = [11, 0, 5, 3, 7, 1, 10, 2, 9, 4, 8, 6];
+		let prompts = "Enter word #12: Enter word #1: Enter word #6: Enter word #4: Enter word #8: Enter word #2: Enter word #11: Enter word #3: Enter word #10: Enter word #5: Enter word #9: Enter word #7: \n";
+		let right = [
+			"yellow", "legal", "sausage", "year", "useful", "winner", "thank", "thank", "winner",
+			"wave", "legal", "worth",
+		];
+		let mut wrong = right;
+		wrong[2] = "salad";
+		wrong[8] = "legal";
+		let data = [
+			(right, false, true, "PASS: all 12 words match\n".to_string()),
+			(
+				wrong,
+				false,
+				false,
+				"FAIL: the words do not match the mnemonic\n".to_string(),
+			),
+			(
+				wrong,
+				true,
+				false,
+				(1..=12)
+					.map(|i| {
+						format!(
+							"#{i}: {}\n",
+							if [6, 10].contains(&i) { "wrong" } else { "ok" }
+						)
+					})
+					.collect::<String>()
+					+ "FAIL: the words do not match the mnemonic\n",
+			),
+		];
+		for (words, strict, pass, exp) in data {
+			let mut input = Script::new(&words);
+			let mut out = Buffer::default();
+			assert_eq!(
+				super::check_words_in_order(&mnemonic, &order, strict, &mut input, &mut out),
+				pass
+			);
+			assert_eq!(out.0, format!("{prompts}{exp}"));
+		}
+
+		let mut input = Script::new(&[" Yellow "]);
+		let mut out = Buffer::default();
+		assert!(!super::check_words_in_order(
+			&mnemonic, &order, false, &mut input, &mut out
+		));
+		assert_eq!(out.0, "Enter word #12: Enter word #1: ");
+
+		// typed words are usually composed, the lists are decomposed
+		let spanish = Mnemonic::from_entropy([0; 16]).translate(Language::Spanish);
+		let typed: Vec<String> = spanish
+			.to_string()
+			.split(' ')
+			.map(|w| w.nfc().collect())
+			.collect();
+		assert_eq!(typed[0], "\u{e1}baco");
+		let typed: Vec<&str> = typed.iter().map(String::as_str).collect();
+		let mut input = Script::new(&typed);
+		let mut out = Buffer::default();
+		let order: Vec<usize> = (0..12).collect();
+		assert!(super::check_words_in_order(
+			&spanish, &order, false, &mut input, &mut out
+		));
+	}
+
+	#[test]
+	fn derive_children() {
+		let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+		let mut input = Script::new(&[phrase, "", "", "", "q"]);
+		let mut out = Buffer::default();
+		super::derive_children(&mut input, &mut out);
+		assert_eq!(
+			out.0,
+			format!(
+				"Enter mnemonic: 
+!!!! WARNING: entropy is all zeros or all ones, anyone can derive its keys !!!!
+!!!! Never send funds to this mnemonic !!!!
+Press ENTER to continue anyway
+
+Standard: BIP39, 128-bit security
+Mnemonic: {phrase}
+Master fingerprint: 73c5da0a
+Identifier: 73c5da0a03d2d0803b731f04242bb40ced2f8bbc
+
+Keep pressing ENTER to generate child mnemonics, q to quit
+0: stick exact spice sock filter ginger museum horse kit multiply manual wear grief demand derive alert quiz fault december lava picture immune decade jaguar
+   m/83696968'/39'/0'/24'/0'
+   fingerprint c2f2dd51, ETH 0x3E1D248C842521886525604a313c08c31d9a5E16, BTC bc1qyefv95c3rt39q78pev25fc7zc5d7zzsg7ju980
+1: trade clock mom turtle clutch love surge truth bus reward hover truck palm paddle fossil near group cactus alley gas borrow amateur learn leisure
+   m/83696968'/39'/0'/24'/1'
+   fingerprint 98a91742, ETH 0x82D36c5b528f9ff927F7069A145645B1DC7C1f7E, BTC bc1qakcgc6j9yd2lmev3km64ka2wzfr205rywxfftv
+"
+			)
+		);
+	}
+
+	#[test]
+	fn eth_addresses() {
+		let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+		let mut input = Script::new(&[phrase, "", "", "", "", "", "q", ""]);
+		let mut out = Buffer::default();
+		super::eth_addresses(&mut input, &mut out);
+		assert_eq!(
+			out.0,
+			format!(
+				"Enter mnemonic or xprv: 
+!!!! WARNING: entropy is all zeros or all ones, anyone can derive its keys !!!!
+!!!! Never send funds to this mnemonic !!!!
+Press ENTER to continue anyway
+
+Standard: BIP39, 128-bit security
+Mnemonic: {phrase}
+Master fingerprint: 73c5da0a
+Identifier: 73c5da0a03d2d0803b731f04242bb40ced2f8bbc
+
+Account index [0]: Start index [0]: Scheme: 44'/60'/X'/0/0 (Ledger Live)
+Derivation path: m/44'/60'/0'/0/0
+Note: keep the m/44'/60'/0' xpub private, with it one leaked child private key reveals all keys below it
+Keep pressing ENTER to generate addresses, q to quit
+0x9858EfFD232B4033E47d90003D41EC34EcaEda94
+0x78839F6054d7ed13918bAe0473BA31b1Ca9D7265
+0x07B5FdfEB4E11826D233403Fe8Db0611CCF4c231
+0x8559A4270Db933caC30830f2be9D099fD477A51D
+0xB9506F020318c2ECA294e0557222511f1e5333CB
+0xE465D679e57f918e9c5357122a51687984e8B550
+0x4a809C7D06985824f2Cc05741A8D4f8427f3ba26
+0x8BAE832C68d7c90C11bCA5EB8Bd0f14d49bd9e8F
+"
+			)
+		);
+
+		let xprv = Mnemonic::from_phrase(phrase)
+			.unwrap()
+			.root_key("")
+			.unwrap()
+			.xprv();
+		let mut input = Script::new(&[xprv.reveal(), "", "", ""]);
+		let mut out = Buffer::default();
+		super::eth_addresses(&mut input, &mut out);
+		assert!(out.0.starts_with(
+			"Enter mnemonic or xprv: 
+Pasted key: xpub661MyMwAqRbcFkPHucMnrGNzDwb6teAX1RbKQmqtEF8kK3Z7LZ59qafCjB9eCRLiTVG3uxBxgKvRgbubRhqSKXnGGb1aoaqLrpMBDrVxga8 (depth 0, child 0, parent 00000000)
+Master fingerprint: 73c5da0a
+
+Account index [0]: "
+		));
+		assert!(out.0.ends_with("0x9858EfFD232B4033E47d90003D41EC34EcaEda94\n0x78839F6054d7ed13918bAe0473BA31b1Ca9D7265\n0x07B5FdfEB4E11826D233403Fe8Db0611CCF4c231\n0x8559A4270Db933caC30830f2be9D099fD477A51D\n"));
+
+		let mut input = Script::new(&[phrase, "", "2", "", ""]);
+		let mut out = Buffer::default();
+		super::eth_addresses(&mut input, &mut out);
+		assert!(out.0.ends_with(
+			"Derivation path: m/44'/60'/2'/0/0
+Note: keep the m/44'/60'/2' xpub private, with it one leaked child private key reveals all keys below it
+Keep pressing ENTER to generate addresses, q to quit
+0x07B5FdfEB4E11826D233403Fe8Db0611CCF4c231
+0x8559A4270Db933caC30830f2be9D099fD477A51D
+0xB9506F020318c2ECA294e0557222511f1e5333CB
+0xE465D679e57f918e9c5357122a51687984e8B550
+"
+		));
+
+		let mut input = Script::new(&[phrase, "", "", "5", ""]);
+		let mut out = Buffer::default();
+		super::eth_addresses(&mut input, &mut out);
+		assert!(out.0.ends_with(
+			"Account index [0]: Start index [0]: Scheme: 44'/60'/X'/0/0 (Ledger Live)
+Derivation path: m/44'/60'/5'/0/0
+Note: keep the m/44'/60'/5' xpub private, with it one leaked child private key reveals all keys below it
+Keep pressing ENTER to generate addresses, q to quit
+0xE465D679e57f918e9c5357122a51687984e8B550
+0x4a809C7D06985824f2Cc05741A8D4f8427f3ba26
+0x8BAE832C68d7c90C11bCA5EB8Bd0f14d49bd9e8F
+0x1bf8DDB62D4302fb457639Fb68617C77FfAd2554
+"
+		));
+	}
+
+	#[test]
+	fn read_passphrase_file() {
+		let path = std::env::temp_dir().join(format!("keymaker-pass-{}", std::process::id()));
+		let path = path.to_str().unwrap();
+		let data = [
+			("TREZOR", "TREZOR"),
+			("TREZOR\n", "TREZOR"),
+			("TREZOR\r\n", "TREZOR"),
+			(" two words \n\n", " two words \n"),
+			("\n", ""),
+			("", ""),
+		];
+		for (contents, exp) in data {
+			fs::write(path, contents).unwrap();
+			assert_eq!(super::read_passphrase_file(path).unwrap(), exp);
 		}
+		fs::remove_file(path).unwrap();
+		assert!(super::read_passphrase_file(path).is_err());
 	}
 }
@@ -0,0 +1,255 @@
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+/// Keeps pages out of swap.
+pub trait MemoryLock: Sync {
+	/// Locks `len` bytes at `ptr` into RAM, false if the platform refused.
+	/// `Locked` passes whole pages.
+	fn lock(&self, ptr: *const u8, len: usize) -> bool;
+	fn unlock(&self, ptr: *const u8, len: usize);
+}
+
+/// mlock on unix, VirtualLock on Windows, nothing elsewhere.
+pub struct Platform;
+
+impl MemoryLock for Platform {
+	#[cfg(unix)]
+	fn lock(&self, ptr: *const u8, len: usize) -> bool {
+		// SAFETY: the range belongs to a live allocation of the caller
+		unsafe {
+			#[cfg(target_os = "linux")]
+			if libc::madvise(ptr as *mut _, len, libc::MADV_DONTDUMP) != 0 {
+				return false;
+			}
+			libc::mlock(ptr as *const _, len) == 0
+		}
+	}
+
+	#[cfg(windows)]
+	fn lock(&self, ptr: *const u8, len: usize) -> bool {
+		// SAFETY: the range belongs to a live allocation of the caller
+		unsafe { windows_sys::Win32::System::Memory::VirtualLock(ptr as *const _, len) != 0 }
+	}
+
+	#[cfg(not(any(unix, windows)))]
+	fn lock(&self, _: *const u8, _: usize) -> bool {
+		false
+	}
+
+	#[cfg(unix)]
+	fn unlock(&self, ptr: *const u8, len: usize) {
+		// SAFETY: as in `lock`
+		unsafe {
+			libc::munlock(ptr as *const _, len);
+		}
+	}
+
+	#[cfg(windows)]
+	fn unlock(&self, ptr: *const u8, len: usize) {
+		// SAFETY: as in `lock`
+		unsafe {
+			windows_sys::Win32::System::Memory::VirtualUnlock(ptr as *const _, len);
+		}
+	}
+
+	#[cfg(not(any(unix, windows)))]
+	fn unlock(&self, _: *const u8, _: usize) {}
+}
+
+/// Never locks, for platforms without support and for tests.
+pub struct NoLock;
+
+impl MemoryLock for NoLock {
+	fn lock(&self, _: *const u8, _: usize) -> bool {
+		false
+	}
+
+	fn unlock(&self, _: *const u8, _: usize) {}
+}
+
+#[cfg(unix)]
+fn page_size() -> usize {
+	// SAFETY: plain syscall
+	match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+		size if size > 0 => size as usize,
+		_ => 4096,
+	}
+}
+
+#[cfg(not(unix))]
+fn page_size() -> usize {
+	4096
+}
+
+/// Heap buffer locked into RAM when the platform allows it, zeroed on drop.
+/// Each buffer has pages of its own, so unlocking one never unlocks a page
+/// another still uses.
+pub struct Locked<const N: usize> {
+	buf: NonNull<[u8; N]>,
+	layout: Layout,
+	lock: &'static dyn MemoryLock,
+	locked: bool,
+}
+
+// SAFETY: `Locked` owns its allocation like a `Box`
+unsafe impl<const N: usize> Send for Locked<N> {}
+unsafe impl<const N: usize> Sync for Locked<N> {}
+
+impl<const N: usize> Locked<N> {
+	pub fn new() -> Self {
+		Self::with_lock(&Platform)
+	}
+
+	pub fn with_lock(lock: &'static dyn MemoryLock) -> Self {
+		let page = page_size();
+		let layout = Layout::from_size_align(N.max(1).next_multiple_of(page), page)
+			.expect("Invalid buffer size");
+		// SAFETY: the layout has a non-zero size
+		let ptr = unsafe { alloc::alloc_zeroed(layout) };
+		let Some(buf) = NonNull::new(ptr as *mut [u8; N]) else {
+			alloc::handle_alloc_error(layout);
+		};
+		let locked = lock.lock(ptr, layout.size());
+		Self {
+			buf,
+			layout,
+			lock,
+			locked,
+		}
+	}
+
+	/// False if locking failed, usually because of `ulimit -l`.
+	pub fn is_locked(&self) -> bool {
+		self.locked
+	}
+}
+
+impl<const N: usize> Default for Locked<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const N: usize> Deref for Locked<N> {
+	type Target = [u8; N];
+
+	fn deref(&self) -> &[u8; N] {
+		// SAFETY: the allocation is zeroed, owned and at least N bytes
+		unsafe { self.buf.as_ref() }
+	}
+}
+
+impl<const N: usize> DerefMut for Locked<N> {
+	fn deref_mut(&mut self) -> &mut [u8; N] {
+		// SAFETY: as in `deref`, and `&mut self` is unique
+		unsafe { self.buf.as_mut() }
+	}
+}
+
+impl<const N: usize> AsRef<[u8]> for Locked<N> {
+	fn as_ref(&self) -> &[u8] {
+		&self[..]
+	}
+}
+
+impl<const N: usize> Drop for Locked<N> {
+	fn drop(&mut self) {
+		zeroize(&mut self[..]);
+		let ptr = self.buf.as_ptr() as *mut u8;
+		if self.locked {
+			self.lock.unlock(ptr, self.layout.size());
+		}
+		// SAFETY: allocated in `with_lock` with this layout
+		unsafe { alloc::dealloc(ptr, self.layout) };
+	}
+}
+
+/// Zeroes `buf` with writes the compiler cannot elide.
+pub fn zeroize(buf: &mut [u8]) {
+	for byte in buf.iter_mut() {
+		// SAFETY: valid, aligned pointer into the buffer
+		unsafe { ptr::write_volatile(byte, 0) };
+	}
+}
+
+/// Zeroes and clears a string. Copies left behind by earlier reallocations
+/// are not reached.
+pub fn zeroize_string(s: &mut String) {
+	// SAFETY: all zero bytes are valid UTF-8
+	zeroize(unsafe { s.as_bytes_mut() });
+	s.clear();
+}
+
+/// Best effort at keeping this process out of core dumps.
+pub fn disable_core_dumps() {
+	#[cfg(unix)]
+	{
+		let limit = libc::rlimit {
+			rlim_cur: 0,
+			rlim_max: 0,
+		};
+		// SAFETY: plain syscalls with valid arguments
+		unsafe {
+			#[cfg(target_os = "linux")]
+			libc::prctl(libc::PR_SET_DUMPABLE, 0);
+			libc::setrlimit(libc::RLIMIT_CORE, &limit);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fallback() {
+		let mut buf = Locked::<64>::with_lock(&NoLock);
+		assert!(!buf.is_locked());
+		buf[..3].copy_from_slice(b"abc");
+		assert_eq!(&buf.as_ref()[..4], b"abc\0");
+		assert_eq!(buf.len(), 64);
+	}
+
+	/// Records the ranges it is asked to lock.
+	struct Recorder(std::sync::Mutex<Vec<(usize, usize)>>);
+
+	impl MemoryLock for Recorder {
+		fn lock(&self, ptr: *const u8, len: usize) -> bool {
+			self.0.lock().unwrap().push((ptr as usize, len));
+			true
+		}
+
+		fn unlock(&self, _: *const u8, _: usize) {}
+	}
+
+	#[test]
+	fn own_pages() {
+		static RECORDER: Recorder = Recorder(std::sync::Mutex::new(vec![]));
+		let page = page_size();
+		let a = Locked::<64>::with_lock(&RECORDER);
+		let b = Locked::<64>::with_lock(&RECORDER);
+		let c = Locked::<0>::with_lock(&RECORDER);
+		assert!(a.is_locked() && b.is_locked() && c.is_locked());
+		let ranges = RECORDER.0.lock().unwrap().clone();
+		assert_eq!(ranges.len(), 3);
+		for (start, len) in &ranges {
+			assert_eq!((start % page, *len), (0, page));
+		}
+		assert_ne!(ranges[0].0, ranges[1].0);
+		assert_eq!(ranges[0].0, a.as_ptr() as usize);
+	}
+
+	#[test]
+	fn zeroize_string() {
+		let mut s = "secret words".to_string();
+		let ptr = s.as_ptr();
+		let capacity = s.capacity();
+		super::zeroize_string(&mut s);
+		assert!(s.is_empty());
+		assert_eq!(s.capacity(), capacity);
+		// SAFETY: the allocation is still owned by `s`
+		let bytes = unsafe { std::slice::from_raw_parts(ptr, 12) };
+		assert_eq!(bytes, [0; 12]);
+	}
+}
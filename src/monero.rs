@@ -0,0 +1,165 @@
+use crate::base58;
+use crate::hash::crc32;
+use crate::util::Secret;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use once_cell::sync::Lazy;
+use sha3::{Digest, Keccak256};
+
+pub const MAINNET: u8 = 0x12;
+pub const WORD_LIST_LEN: usize = 1626;
+
+static RAW_WORD_LIST: &str = include_str!("../monero_english.txt");
+static WORD_LIST: Lazy<Vec<&'static str>> = Lazy::new(|| RAW_WORD_LIST.lines().collect());
+
+pub struct Keys {
+	spend: Scalar,
+	view: Scalar,
+}
+
+impl Keys {
+	/// Reduces `entropy` mod l into the spend key, the view key is its
+	/// reduced Keccak-256 hash.
+	pub fn from_entropy(entropy: [u8; 32]) -> Self {
+		let spend = Scalar::from_bytes_mod_order(entropy);
+		let view = Scalar::from_bytes_mod_order(Keccak256::digest(spend.as_bytes()).into());
+		Self { spend, view }
+	}
+
+	pub fn spend_key(&self) -> Secret<[u8; 32]> {
+		Secret::new(self.spend.to_bytes())
+	}
+
+	pub fn view_key(&self) -> Secret<[u8; 32]> {
+		Secret::new(self.view.to_bytes())
+	}
+
+	pub fn public_spend_key(&self) -> [u8; 32] {
+		EdwardsPoint::mul_base(&self.spend).compress().to_bytes()
+	}
+
+	pub fn public_view_key(&self) -> [u8; 32] {
+		EdwardsPoint::mul_base(&self.view).compress().to_bytes()
+	}
+
+	pub fn address(&self, network: u8) -> String {
+		let mut data = vec![network];
+		data.extend_from_slice(&self.public_spend_key());
+		data.extend_from_slice(&self.public_view_key());
+		let checksum = Keccak256::digest(&data);
+		data.extend_from_slice(&checksum[..4]);
+		base58::encode_blocks(data)
+	}
+
+	/// 25 word seed of the spend key, in Monero's English word list.
+	pub fn mnemonic(&self) -> Secret<String> {
+		let n = WORD_LIST_LEN as u32;
+		let mut words: Vec<&str> = self
+			.spend
+			.as_bytes()
+			.chunks(4)
+			.flat_map(|chunk| {
+				let x = u32::from_le_bytes(chunk.try_into().unwrap());
+				let w1 = x % n;
+				let w2 = (x / n + w1) % n;
+				let w3 = (x / n / n + w2) % n;
+				[w1, w2, w3].map(|w| WORD_LIST[w as usize])
+			})
+			.collect();
+		words.push(words[checksum_index(&words)]);
+		Secret::new(words.join(" "))
+	}
+}
+
+/// Position of the checksum word among the first 24 words.
+pub fn checksum_index(words: &[&str]) -> usize {
+	let prefixes: String = words.iter().flat_map(|w| w.chars().take(3)).collect();
+	crc32(prefixes) as usize % words.len()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	#[test]
+	fn keys() {
+		let mut entropy = [0; 32];
+		hex::decode_to_slice(
+			"77fadbe52830d30438ff3da7c3d0ea4c7c3ea6cd1fb8ee73e88f35c91a24bf1f",
+			&mut entropy,
+		)
+		.unwrap();
+		let keys = Keys::from_entropy(entropy);
+		let data = [
+			(
+				hex::encode(keys.spend_key().reveal()),
+				"8a26e6880ecdc0ac61624604e5d60b387c3ea6cd1fb8ee73e88f35c91a24bf0f",
+			),
+			(
+				hex::encode(keys.view_key().reveal()),
+				"2fc7a477f3c76b16e92d5383a4ae9f761a0fac873c977ce60a575e528d358e0c",
+			),
+			(
+				hex::encode(keys.public_spend_key()),
+				"0bafc96a374fc806e3a5ab290700f75491c5702741174eac08c283451fd7ced8",
+			),
+			(
+				hex::encode(keys.public_view_key()),
+				"b02baf901c581ebad28698f9cfc63cc65e807998075038c932fced1561f89db7",
+			),
+		];
+		for (key, exp) in data {
+			assert_eq!(key, exp);
+		}
+		assert_eq!(keys.address(MAINNET), "424o5EQqD1929qPfJVzcw8F9RuAUUDCmTVmwx6nNav9sdF9WLHbwnE9YFQqXQMmLM9aBRtuJ8wWYXaetGmrkeMrUMiMMSuo");
+	}
+
+	#[test]
+	fn word_list() {
+		assert_eq!(WORD_LIST.len(), WORD_LIST_LEN);
+		assert!(WORD_LIST.windows(2).all(|w| w[0] < w[1]));
+		let prefixes: HashSet<&str> = WORD_LIST.iter().map(|w| &w[..3]).collect();
+		assert_eq!(prefixes.len(), WORD_LIST_LEN);
+	}
+
+	#[test]
+	fn mnemonic() {
+		// wallets of Monero's functional tests, made by monero-wallet-rpc
+		let data = [
+			(
+				"148d78d2aba7dbca5cd8f6abcfb0b3c009ffbdbea1ff373d50ed94d78286640e",
+				"velvet lymph giddy number token physics poetry unquoted nibs useful sabotage limits benches lifestyle eden nitrogen anvil fewest avoid batch vials washing fences goat unquoted",
+				"42ey1afDFnn4886T7196doS9GPMzexD9gXpsZJDwVjeRVdFCSoHnv7KPbBeGpzJBzHRCAs9UxqeoyFQMYbqSWYTfJJQAWDm",
+			),
+			(
+				"609ae8e228a871c37b61292ff898dd144db5d784804cc4a971bf74aff3acb70a",
+				"peeled mixture ionic radar utopia puddle buying illness nuns gadget river spout cavernous bounced paradise drunk looking cottage jump tequila melting went winter adjust spout",
+				"44Kbx4sJ7JDRDV5aAhLJzQCjDz2ViLRduE3ijDZu3osWKBjMGkV1XPk4pfDUMqt1Aiezvephdqm6YD19GKFD9ZcXVUTp6BW",
+			),
+			(
+				"930d5eca868a20e194ab914da9bd17cb5fd02e5562f9f474468725a17e2de60d",
+				"dilute gutter certain antics pamphlet macro enjoy left slid guarded bogeys upload nineteen bomb jubilee enhanced irritate turnip eggs swung jukebox loudly reduce sedan slid",
+				"46r4nYSevkfBUMhuykdK3gQ98XDqDTYW1hNLaXNvjpsJaSbNtdXh1sKMsdVgqkaihChAzEy29zEDPMR3NHQvGoZCLGwTerK",
+			),
+		];
+		for (spend_key, seed, address) in data {
+			let mut entropy = [0; 32];
+			hex::decode_to_slice(spend_key, &mut entropy).unwrap();
+			let keys = Keys::from_entropy(entropy);
+			assert_eq!(hex::encode(keys.spend_key().reveal()), spend_key);
+			assert_eq!(*keys.mnemonic().reveal(), seed);
+			assert_eq!(keys.address(MAINNET), address);
+		}
+		assert_eq!(
+			*Keys::from_entropy([0; 32]).mnemonic().reveal(),
+			vec!["abbey"; 25].join(" ")
+		);
+	}
+
+	#[test]
+	fn checksum() {
+		let words = "sequence atlas unveil summon pebbles tuesday beer rudely snake rockets different fuselage woven tagged bested dented vegan hover rapid fawns obvious muppet randomly seasons";
+		let words: Vec<_> = words.split(' ').collect();
+		assert_eq!(checksum_index(&words), 22);
+	}
+}
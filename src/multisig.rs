@@ -0,0 +1,326 @@
+use crate::bip32::{format_path, ChildNumber, ExtKey, Key};
+use crate::btc::{self, KeyOrigin};
+use crate::slip44;
+use k256::{PublicKey, SecretKey};
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_COSIGNERS: usize = 15;
+
+#[derive(PartialEq)]
+pub enum MultisigError {
+	InvalidThreshold,
+	TooManyCosigners,
+	DuplicateCosigner,
+	InvalidCosigner,
+}
+
+impl fmt::Debug for MultisigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidThreshold => {
+				f.write_str("threshold must be between 1 and the number of cosigners")
+			}
+			Self::TooManyCosigners => write!(f, "at most {MAX_COSIGNERS} cosigners are supported"),
+			Self::DuplicateCosigner => f.write_str("duplicate cosigner"),
+			Self::InvalidCosigner => f.write_str("expected '[fingerprint/path]xpub'"),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptType {
+	P2wsh,
+	P2shP2wsh,
+}
+
+impl ScriptType {
+	pub const ALL: [ScriptType; 2] = [Self::P2wsh, Self::P2shP2wsh];
+
+	/// BIP48 account path.
+	pub fn path(&self, account: u32) -> Vec<ChildNumber> {
+		use ChildNumber::Hardened;
+		let script = match self {
+			Self::P2wsh => 2,
+			Self::P2shP2wsh => 1,
+		};
+		vec![
+			Hardened(48),
+			Hardened(slip44::BTC),
+			Hardened(account),
+			Hardened(script),
+		]
+	}
+
+	fn wrap(&self, inner: &str) -> String {
+		match self {
+			Self::P2wsh => format!("wsh({inner})"),
+			Self::P2shP2wsh => format!("sh(wsh({inner}))"),
+		}
+	}
+
+	fn address(&self, script: &[u8]) -> String {
+		match self {
+			Self::P2wsh => btc::p2wsh_address(script),
+			Self::P2shP2wsh => btc::p2sh_address(&btc::p2wsh_script(script)),
+		}
+	}
+}
+
+impl fmt::Display for ScriptType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::P2wsh => "P2WSH",
+			Self::P2shP2wsh => "P2SH-P2WSH",
+		})
+	}
+}
+
+#[derive(Clone)]
+pub struct Cosigner {
+	pub origin: KeyOrigin,
+	pub xpub: ExtKey<PublicKey>,
+}
+
+impl Cosigner {
+	pub fn from_root(root: &ExtKey<SecretKey>, path: &[ChildNumber]) -> Option<Self> {
+		Some(Self {
+			origin: KeyOrigin::new(root, path),
+			xpub: root.derive_path(path)?.neuter(),
+		})
+	}
+}
+
+impl fmt::Display for Cosigner {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", self.origin, self.xpub)
+	}
+}
+
+impl FromStr for Cosigner {
+	type Err = MultisigError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		let (origin, xpub) = s
+			.find(']')
+			.map(|i| s.split_at(i + 1))
+			.ok_or(MultisigError::InvalidCosigner)?;
+		Ok(Self {
+			origin: origin.parse().map_err(|_| MultisigError::InvalidCosigner)?,
+			xpub: xpub.parse().map_err(|_| MultisigError::InvalidCosigner)?,
+		})
+	}
+}
+
+pub struct Multisig {
+	threshold: usize,
+	script_type: ScriptType,
+	cosigners: Vec<Cosigner>,
+}
+
+impl Multisig {
+	pub fn new(
+		threshold: usize,
+		script_type: ScriptType,
+		cosigners: Vec<Cosigner>,
+	) -> Result<Self, MultisigError> {
+		if cosigners.len() > MAX_COSIGNERS {
+			return Err(MultisigError::TooManyCosigners);
+		}
+		if threshold == 0 || threshold > cosigners.len() {
+			return Err(MultisigError::InvalidThreshold);
+		}
+		for (i, c) in cosigners.iter().enumerate() {
+			if cosigners[..i].iter().any(|o| o.xpub.key() == c.xpub.key()) {
+				return Err(MultisigError::DuplicateCosigner);
+			}
+		}
+		Ok(Self {
+			threshold,
+			script_type,
+			cosigners,
+		})
+	}
+
+	pub fn threshold(&self) -> usize {
+		self.threshold
+	}
+
+	pub fn script_type(&self) -> ScriptType {
+		self.script_type
+	}
+
+	pub fn cosigners(&self) -> &[Cosigner] {
+		&self.cosigners
+	}
+
+	/// `sortedmulti` descriptor for the receive or change branch, with checksum.
+	pub fn descriptor(&self, change: bool) -> String {
+		let branch = change as u8;
+		let keys: String = self
+			.cosigners
+			.iter()
+			.map(|c| format!(",{c}/{branch}/*"))
+			.collect();
+		let inner = format!("sortedmulti({}{keys})", self.threshold);
+		btc::with_checksum(&self.script_type.wrap(&inner)).unwrap()
+	}
+
+	/// Witness script for the given branch and index.
+	pub fn script(&self, change: bool, i: u32) -> Option<Vec<u8>> {
+		let path = [change as u32, i];
+		let mut keys = self
+			.cosigners
+			.iter()
+			.map(|c| Some(c.xpub.derive_path(&path)?.key().serialize()))
+			.collect::<Option<Vec<_>>>()?;
+		keys.sort();
+
+		let mut out = vec![0x50 + self.threshold as u8];
+		for key in keys {
+			out.push(33);
+			out.extend_from_slice(&key);
+		}
+		out.push(0x50 + self.cosigners.len() as u8);
+		out.push(0xae);
+		Some(out)
+	}
+
+	pub fn address(&self, change: bool, i: u32) -> Option<String> {
+		Some(self.script_type.address(&self.script(change, i)?))
+	}
+
+	/// Coldcard-style setup file, importable by Sparrow and Specter.
+	pub fn export(&self, name: &str) -> String {
+		let mut out = String::new();
+		out.push_str("# Keymaker multisig setup file\n");
+		out.push_str(&format!("# Receive: {}\n", self.descriptor(false)));
+		out.push_str(&format!("# Change: {}\n", self.descriptor(true)));
+		out.push_str(&format!("Name: {name}\n"));
+		out.push_str(&format!(
+			"Policy: {} of {}\n",
+			self.threshold,
+			self.cosigners.len()
+		));
+		out.push_str(&format!("Format: {}\n", self.script_type));
+		for c in &self.cosigners {
+			out.push('\n');
+			out.push_str(&format!("Derivation: {}\n", format_path(&c.origin.path)));
+			out.push_str(&format!(
+				"{}: {}\n",
+				hex::encode_upper(c.origin.fingerprint),
+				c.xpub
+			));
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+
+	fn cosigner(entropy: u8, script_type: ScriptType) -> Cosigner {
+		let root = Mnemonic::from_entropy([entropy; 16]).root_key("").unwrap();
+		Cosigner::from_root(&root, &script_type.path(0)).unwrap()
+	}
+
+	// abandon..about, legal winner..yellow, letter advice..above
+	fn fixture(script_type: ScriptType) -> Multisig {
+		let cosigners = [0x00, 0x7f, 0x80]
+			.map(|e| cosigner(e, script_type))
+			.to_vec();
+		Multisig::new(2, script_type, cosigners).unwrap()
+	}
+
+	#[test]
+	fn parse() {
+		let c = cosigner(0, ScriptType::P2wsh);
+		let s = c.to_string();
+		assert!(s.starts_with("[73c5da0a/48'/0'/0'/2']xpub"));
+		assert_eq!(s.parse::<Cosigner>().unwrap().to_string(), s);
+		for s in [
+			"",
+			"xpub",
+			"[73c5da0a/48'/0'/0'/2']",
+			"73c5da0a/48'/0'/0'/2'",
+		] {
+			assert_eq!(
+				s.parse::<Cosigner>().err(),
+				Some(MultisigError::InvalidCosigner)
+			);
+		}
+	}
+
+	#[test]
+	fn new() {
+		use MultisigError::*;
+		let c = |e| cosigner(e, ScriptType::P2wsh);
+		let data = [
+			(0, vec![c(0), c(1)], InvalidThreshold),
+			(3, vec![c(0), c(1)], InvalidThreshold),
+			(1, vec![c(0), c(0)], DuplicateCosigner),
+			(1, vec![c(0); 16], TooManyCosigners),
+		];
+		for (threshold, cosigners, err) in data {
+			let res = Multisig::new(threshold, ScriptType::P2wsh, cosigners);
+			assert_eq!(res.err(), Some(err));
+		}
+	}
+
+	#[test]
+	fn wallet() {
+		let wallet = fixture(ScriptType::P2wsh);
+		let receive = wallet.descriptor(false);
+		assert!(receive.starts_with("wsh(sortedmulti(2,[73c5da0a/48'/0'/0'/2']xpub6DkFAXWQ2dHx"));
+		assert!(receive.ends_with("/0/*))#h0y6lcv6"));
+		assert!(wallet.descriptor(true).ends_with("/1/*))#j60qp7uj"));
+		let origins: Vec<_> = wallet
+			.cosigners()
+			.iter()
+			.map(|c| c.origin.to_string())
+			.collect();
+		assert_eq!(
+			origins,
+			[
+				"[73c5da0a/48'/0'/0'/2']",
+				"[b8688df1/48'/0'/0'/2']",
+				"[28645006/48'/0'/0'/2']",
+			]
+		);
+		let data = [
+			(
+				false,
+				0,
+				"bc1qm43n7nnev58aj3nrznz2xscgv98t7gxycq5pmp20a5vzfp5t0q2s7r6twa",
+			),
+			(
+				false,
+				9,
+				"bc1qrm0ukxgmkeq6kpuqlwqn6jarzpyel525r4wghtdclj30rrllwheqp9fqk4",
+			),
+			(
+				true,
+				0,
+				"bc1qy0qa9lx04k0lk9lrv4542ndehul9spkcafdtcpdv38qx6ft5km2ssnce5d",
+			),
+		];
+		for (change, i, exp) in data {
+			assert_eq!(wallet.address(change, i).unwrap(), exp);
+		}
+
+		let export = wallet.export("fixture");
+		assert!(export.contains("Name: fixture\nPolicy: 2 of 3\nFormat: P2WSH\n"));
+		assert!(export.contains("\nDerivation: m/48'/0'/0'/2'\nB8688DF1: xpub6FQya7zGhR92"));
+		assert!(export.contains(&format!("# Receive: {receive}\n")));
+
+		let wallet = fixture(ScriptType::P2shP2wsh);
+		assert!(wallet.descriptor(false).ends_with(")))#8cdffplj"));
+		assert_eq!(
+			wallet.address(false, 0).unwrap(),
+			"354ZuUuwopSS5CsTjYzXwgQLxVeeN2eQFJ"
+		);
+	}
+}
@@ -0,0 +1,152 @@
+use crate::bip32::{format_path, Key};
+use crate::bip39::Mnemonic;
+use crate::portfolio::Coin;
+use std::fmt::Write;
+
+/// Everything a printed wallet sheet shows.
+pub struct Sheet<'a> {
+	pub mnemonic: &'a Mnemonic,
+	pub coin: &'a Coin,
+	pub account: u32,
+	/// Number of receive addresses listed.
+	pub addresses: u32,
+	/// Also print the root xprv in the secret section.
+	pub root_key: bool,
+}
+
+const STYLE: &str = "body { font-family: monospace; max-width: 48em; margin: 2em auto; }
+table { border-collapse: collapse; }
+td { padding: 0.2em 1em 0.2em 0; }
+.fold { border-top: 2px dashed black; margin: 3em 0 2em; padding-top: 0.5em; text-align: center; }
+.secret { page-break-inside: avoid; }
+";
+
+/// A self-contained HTML page for printing. Public data is at the top, the
+/// mnemonic and root key below a fold line, so folding the sheet hides them.
+pub fn render(sheet: &Sheet) -> Option<String> {
+	let root = sheet.mnemonic.root_key("").ok()?;
+	let fingerprint = hex::encode(root.fingerprint());
+	let coin = sheet.coin;
+	let mut out = String::new();
+	writeln!(out, "<!DOCTYPE html>").unwrap();
+	writeln!(out, "<html>").unwrap();
+	writeln!(out, "<head>").unwrap();
+	writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+	writeln!(out, "<title>Wallet {fingerprint}</title>").unwrap();
+	write!(out, "<style>\n{STYLE}</style>\n").unwrap();
+	writeln!(out, "</head>").unwrap();
+	writeln!(out, "<body>").unwrap();
+	writeln!(out, "<h1>Keymaker wallet</h1>").unwrap();
+	writeln!(out, "<p>Master fingerprint: {fingerprint}<br>").unwrap();
+	writeln!(out, "Scheme: {}<br>", coin.name).unwrap();
+	writeln!(
+		out,
+		"Account: {}</p>",
+		format_path(&coin.account_path(sheet.account))
+	)
+	.unwrap();
+	writeln!(out, "<table>").unwrap();
+	for i in 0..sheet.addresses {
+		let path = coin.address_path(sheet.account, i);
+		let key = root.derive_path(&path)?.neuter();
+		let address = (coin.address)(&key.key().serialize())?;
+		writeln!(
+			out,
+			"<tr><td>{}</td><td>{address}</td></tr>",
+			format_path(&path)
+		)
+		.unwrap();
+	}
+	writeln!(out, "</table>").unwrap();
+
+	writeln!(
+		out,
+		"<div class=\"fold\">Fold here, keep the part below secret</div>"
+	)
+	.unwrap();
+	writeln!(out, "<div class=\"secret\">").unwrap();
+	writeln!(
+		out,
+		"<h2>Mnemonic, BIP39 {} bits</h2>",
+		sheet.mnemonic.strength()
+	)
+	.unwrap();
+	write!(out, "<pre>\n{}</pre>\n", sheet.mnemonic.to_grid_string()).unwrap();
+	if sheet.root_key {
+		writeln!(out, "<h2>Root key</h2>").unwrap();
+		writeln!(out, "<p>{}</p>", root.xprv().reveal()).unwrap();
+	}
+	writeln!(out, "</div>").unwrap();
+	writeln!(out, "</body>").unwrap();
+	writeln!(out, "</html>").unwrap();
+	Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::portfolio::COINS;
+
+	#[test]
+	fn render() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let sheet = Sheet {
+			mnemonic: &mnemonic,
+			coin: &COINS[2],
+			account: 0,
+			addresses: 3,
+			root_key: false,
+		};
+		let exp = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Wallet 73c5da0a</title>
+<style>
+body { font-family: monospace; max-width: 48em; margin: 2em auto; }
+table { border-collapse: collapse; }
+td { padding: 0.2em 1em 0.2em 0; }
+.fold { border-top: 2px dashed black; margin: 3em 0 2em; padding-top: 0.5em; text-align: center; }
+.secret { page-break-inside: avoid; }
+</style>
+</head>
+<body>
+<h1>Keymaker wallet</h1>
+<p>Master fingerprint: 73c5da0a<br>
+Scheme: BTC P2WPKH<br>
+Account: m/84'/0'/0'</p>
+<table>
+<tr><td>m/84'/0'/0'/0/0</td><td>bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu</td></tr>
+<tr><td>m/84'/0'/0'/0/1</td><td>bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g</td></tr>
+<tr><td>m/84'/0'/0'/0/2</td><td>bc1qp59yckz4ae5c4efgw2s5wfyvrz0ala7rgvuz8z</td></tr>
+</table>
+<div class="fold">Fold here, keep the part below secret</div>
+<div class="secret">
+<h2>Mnemonic, BIP39 128 bits</h2>
+<pre>
+ 1. abandon   2. abandon   3. abandon   4. abandon
+ 5. abandon   6. abandon   7. abandon   8. abandon
+ 9. abandon  10. abandon  11. abandon  12. about
+</pre>
+</div>
+</body>
+</html>
+"#;
+		assert_eq!(super::render(&sheet).unwrap(), exp);
+
+		let sheet = Sheet {
+			coin: &COINS[5],
+			account: 1,
+			root_key: true,
+			..sheet
+		};
+		let html = super::render(&sheet).unwrap();
+		let (public, secret) = html.split_once("class=\"fold\"").unwrap();
+		assert!(public.contains(
+			"<tr><td>m/44'/60'/3'/0/0</td><td>0x8559A4270Db933caC30830f2be9D099fD477A51D</td></tr>"
+		));
+		assert!(!public.contains("abandon"));
+		assert!(!public.contains("xprv"));
+		assert!(secret.contains("xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu"));
+	}
+}
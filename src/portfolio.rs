@@ -0,0 +1,358 @@
+use crate::accounts::Account;
+use crate::avax::Chain;
+use crate::bip32::ChildNumber::{self, Hardened, Normal};
+use crate::bip32::{format_path, Key};
+use crate::bip39::Mnemonic;
+use crate::bip85::{self, Bip85, Bip85App};
+use crate::btc::{self, KeyOrigin};
+use crate::cardano::{self, IcarusKey};
+use crate::eth::ToAddress;
+use crate::memory;
+use crate::monero;
+use crate::slip10::Ed25519Key;
+use crate::slip44::{self, Registration};
+use crate::strkey;
+use k256::PublicKey;
+use std::fmt::Write;
+
+/// A coin and address scheme with an account key at
+/// `m/purpose'/coin_type'/account'`.
+pub struct Coin {
+	pub name: &'static str,
+	pub purpose: u32,
+	pub coin_type: u32,
+	/// Path of the first receive address below the account key.
+	pub receive: &'static [u32],
+	/// Every address is a new account, as in Ledger Live.
+	pub account_per_address: bool,
+	pub address: fn(&[u8; 33]) -> Option<String>,
+}
+
+impl Coin {
+	pub fn account_path(&self, account: u32) -> [ChildNumber; 3] {
+		[
+			Hardened(self.purpose),
+			Hardened(self.coin_type),
+			Hardened(account),
+		]
+	}
+
+	pub fn receive_path(&self, account: u32) -> Vec<ChildNumber> {
+		self.account_path(account)
+			.into_iter()
+			.chain(self.receive.iter().map(|&i| i.into()))
+			.collect()
+	}
+
+	/// Path of receive address `i`, counting up the last index of `receive`
+	/// or the account.
+	pub fn address_path(&self, account: u32, i: u32) -> Vec<ChildNumber> {
+		if self.account_per_address {
+			return self.receive_path(account.saturating_add(i));
+		}
+		let mut path = self.receive_path(account);
+		*path.last_mut().unwrap() = Normal(i);
+		path
+	}
+}
+
+fn eth_address(pubkey: &[u8; 33]) -> Option<String> {
+	Some(
+		PublicKey::from_sec1_bytes(pubkey)
+			.ok()?
+			.address()
+			.to_string(),
+	)
+}
+
+pub const COINS: [Coin; 8] = [
+	Coin {
+		name: "BTC P2PKH",
+		purpose: 44,
+		coin_type: slip44::BTC,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: |k| Some(btc::p2pkh_address(k)),
+	},
+	Coin {
+		name: "BTC P2SH-P2WPKH",
+		purpose: 49,
+		coin_type: slip44::BTC,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: |k| Some(btc::p2sh_p2wpkh_address(k)),
+	},
+	Coin {
+		name: "BTC P2WPKH",
+		purpose: 84,
+		coin_type: slip44::BTC,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: |k| Some(btc::p2wpkh_address(k)),
+	},
+	Coin {
+		name: "BTC P2TR",
+		purpose: 86,
+		coin_type: slip44::BTC,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: btc::p2tr_address,
+	},
+	Coin {
+		name: "ETH BIP44 (MetaMask)",
+		purpose: 44,
+		coin_type: slip44::ETH,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: eth_address,
+	},
+	Coin {
+		name: "ETH Ledger Live",
+		purpose: 44,
+		coin_type: slip44::ETH,
+		receive: &[0, 0],
+		account_per_address: true,
+		address: eth_address,
+	},
+	Coin {
+		name: "ETH Ledger legacy (MEW)",
+		purpose: 44,
+		coin_type: slip44::ETH,
+		receive: &[0],
+		account_per_address: false,
+		address: eth_address,
+	},
+	Coin {
+		name: "AVAX X-chain",
+		purpose: 44,
+		coin_type: slip44::AVAX,
+		receive: &[0, 0],
+		account_per_address: false,
+		address: |k| Some(Chain::X.address(k)),
+	},
+];
+
+/// Schemes of a registered coin, its default scheme first. Empty for coins
+/// without secp256k1 address schemes.
+pub fn schemes(coin: &Registration) -> Vec<&'static Coin> {
+	let mut schemes: Vec<_> = COINS
+		.iter()
+		.filter(|c| c.coin_type == coin.coin_type)
+		.collect();
+	if let Some(i) = schemes.iter().position(|c| c.purpose == coin.purpose) {
+		let default = schemes.remove(i);
+		schemes.insert(0, default);
+	}
+	schemes
+}
+
+/// Address of the key at `path` for the first coin with its purpose and coin
+/// type, `None` for other paths.
+pub fn address(path: &[ChildNumber], pubkey: &[u8; 33]) -> Option<String> {
+	let coin = COINS
+		.iter()
+		.find(|c| path.starts_with(&[Hardened(c.purpose), Hardened(c.coin_type)]))?;
+	(coin.address)(pubkey)
+}
+
+/// One block per coin with the account xpub and first receive address, to
+/// check a restore against every wallet at once. Schemes sharing an account
+/// key share a block. Contains no private keys.
+pub fn portfolio(mnemonic: &Mnemonic, passphrase: &str, account: u32) -> Option<String> {
+	let seed = mnemonic.seed(passphrase);
+	let root = seed.root_key().ok()?;
+	let mut out = format!("Master fingerprint: {}\n", seed.fingerprint());
+	for group in COINS.chunk_by(|a, b| a.account_path(0) == b.account_path(0)) {
+		let path = group[0].account_path(account);
+		let key = root.derive_path(&path)?.neuter();
+		let xpub = Account {
+			name: group[0].name,
+			origin: KeyOrigin::new(&root, &path),
+			xpub: key.clone(),
+		};
+		let name = match group {
+			[coin] => coin.name,
+			_ => {
+				slip44::REGISTRY
+					.iter()
+					.find(|r| r.coin_type == group[0].coin_type)?
+					.symbol
+			}
+		};
+		writeln!(out).unwrap();
+		writeln!(out, "{name}").unwrap();
+		writeln!(out, "  Account: {}", format_path(&path)).unwrap();
+		writeln!(out, "  xpub:    {xpub}").unwrap();
+		let mut receives: Vec<(Vec<ChildNumber>, Vec<&Coin>)> = vec![];
+		for coin in group {
+			let path = coin.receive_path(account);
+			match receives.iter_mut().find(|(p, _)| *p == path) {
+				Some((_, coins)) => coins.push(coin),
+				None => receives.push((path, vec![coin])),
+			}
+		}
+		for (receive, coins) in receives {
+			let address = (coins[0].address)(&key.derive_path(&receive[3..])?.key().serialize())?;
+			write!(out, "  Receive: {} {address}", format_path(&receive)).unwrap();
+			if group.len() > 1 {
+				let names: Vec<_> = coins
+					.iter()
+					.map(|c| c.name.trim_start_matches(name).trim())
+					.collect();
+				write!(out, "  {}", names.join(", ")).unwrap();
+			}
+			writeln!(out).unwrap();
+		}
+	}
+
+	let path = cardano::account_path(account);
+	let keys = IcarusKey::from_mnemonic(mnemonic, passphrase)
+		.derive_path(&path)?
+		.address_keys(0)?;
+	writeln!(out).unwrap();
+	writeln!(out, "ADA Icarus").unwrap();
+	writeln!(out, "  Account: {}", format_path(&path)).unwrap();
+	writeln!(out, "  Stake:   {}", keys.reward_address()).unwrap();
+	writeln!(
+		out,
+		"  Receive: {}/0/0 {}",
+		format_path(&path),
+		keys.base_address()
+	)
+	.unwrap();
+
+	let path = [Hardened(44), Hardened(slip44::XLM), Hardened(account)];
+	let key = Ed25519Key::from_seed(&seed).derive_path(&path)?;
+	writeln!(out).unwrap();
+	writeln!(out, "XLM SEP-0005").unwrap();
+	writeln!(
+		out,
+		"  Receive: {} {}",
+		format_path(&path),
+		strkey::encode(strkey::Version::PublicKey, &key.public_key())
+	)
+	.unwrap();
+
+	// the same single account as option 10, whatever `account` is
+	let app = bip85::Hex { len: 32, index: 0 };
+	let path = app.path()?;
+	let mut entropy = [0; 32];
+	entropy.copy_from_slice(&root.derive_app(app)?);
+	let keys = monero::Keys::from_entropy(entropy);
+	memory::zeroize(&mut entropy);
+	writeln!(out).unwrap();
+	writeln!(out, "XMR").unwrap();
+	writeln!(out, "  Entropy: BIP85 HEX {}", format_path(&path)).unwrap();
+	writeln!(out, "  Receive: {}", keys.address(monero::MAINNET)).unwrap();
+	Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+	use crate::eth::Scheme;
+
+	#[test]
+	fn coins() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let schemes = [Scheme::Bip44, Scheme::LedgerLive, Scheme::LedgerLegacy];
+		for (coin, scheme) in COINS[4..7].iter().zip(schemes) {
+			for account in [0, 3] {
+				for i in [1, 7] {
+					assert_eq!(
+						coin.address_path(account, i),
+						scheme.account_path(account, i),
+						"{}",
+						coin.name
+					);
+				}
+				let path = scheme.account_path(account, 0);
+				assert_eq!(coin.receive_path(account), path, "{}", coin.name);
+				let key = root.derive_path(&path).unwrap();
+				assert_eq!(
+					(coin.address)(&key.neuter().key().serialize()),
+					Some(key.address().to_string())
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn schemes() {
+		let data = [
+			(
+				"BTC",
+				vec!["BTC P2WPKH", "BTC P2PKH", "BTC P2SH-P2WPKH", "BTC P2TR"],
+			),
+			(
+				"ETH",
+				vec![
+					"ETH BIP44 (MetaMask)",
+					"ETH Ledger Live",
+					"ETH Ledger legacy (MEW)",
+				],
+			),
+			("AVAX", vec!["AVAX X-chain"]),
+			("XLM", vec![]),
+		];
+		for (symbol, exp) in data {
+			let schemes: Vec<_> = super::schemes(slip44::find(symbol).unwrap())
+				.iter()
+				.map(|c| c.name)
+				.collect();
+			assert_eq!(schemes, exp);
+		}
+	}
+
+	#[test]
+	fn portfolio() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let exp = "Master fingerprint: 73c5da0a
+
+BTC P2PKH
+  Account: m/44'/0'/0'
+  xpub:    [73c5da0a/44'/0'/0']xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj
+  Receive: m/44'/0'/0'/0/0 1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA
+
+BTC P2SH-P2WPKH
+  Account: m/49'/0'/0'
+  xpub:    [73c5da0a/49'/0'/0']xpub6C6nQwHaWbSrzs5tZ1q7m5R9cPK9eYpNMFesiXsYrgc1P8bvLLAet9JfHjYXKjToD8cBRswJXXbbFpXgwsswVPAZzKMa1jUp2kVkGVUaJa7
+  Receive: m/49'/0'/0'/0/0 37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf
+
+BTC P2WPKH
+  Account: m/84'/0'/0'
+  xpub:    [73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V
+  Receive: m/84'/0'/0'/0/0 bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu
+
+BTC P2TR
+  Account: m/86'/0'/0'
+  xpub:    [73c5da0a/86'/0'/0']xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ
+  Receive: m/86'/0'/0'/0/0 bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr
+
+ETH
+  Account: m/44'/60'/0'
+  xpub:    [73c5da0a/44'/60'/0']xpub6DCoCpSuQZB2jawqnGMEPS63ePKWkwWPH4TU45Q7LPXWuNd8TMtVxRrgjtEshuqpK3mdhaWHPFsBngh5GFZaM6si3yZdUsT8ddYM3PwnATt
+  Receive: m/44'/60'/0'/0/0 0x9858EfFD232B4033E47d90003D41EC34EcaEda94  BIP44 (MetaMask), Ledger Live
+  Receive: m/44'/60'/0'/0 0xB8Fd42000d00202DCbCF5e18d6640d656345FD6A  Ledger legacy (MEW)
+
+AVAX X-chain
+  Account: m/44'/9000'/0'
+  xpub:    [73c5da0a/44'/9000'/0']xpub6BqVigHfL2TNNs8HyeEHn4JFFyTw1vL8kC5ZhzBhrhDbQ3FhgakpivT97Cd7oVCJiAwiqWu313vKMZMwCghXgSVDnYR3FrYzTz24yY3nFHR
+  Receive: m/44'/9000'/0'/0/0 X-avax1p9575chzhvcwvmvzaqh7yeld76r3af0ha56phl
+
+ADA Icarus
+  Account: m/1852'/1815'/0'
+  Stake:   stake1u8j40zgr2gy4788kl54h6x3gu0pukq5lfr8nflufpg5dzaskqlx2l
+  Receive: m/1852'/1815'/0'/0/0 addr1qy8ac7qqy0vtulyl7wntmsxc6wex80gvcyjy33qffrhm7sh927ysx5sftuw0dlft05dz3c7revpf7jx0xnlcjz3g69mq4afdhv
+
+XLM SEP-0005
+  Receive: m/44'/148'/0' GB3JDWCQJCWMJ3IILWIGDTQJJC5567PGVEVXSCVPEQOTDN64VJBDQBYX
+
+XMR
+  Entropy: BIP85 HEX m/83696968'/128169'/32'/0'
+  Receive: 44Vc5UsSmbAfcc1ihHrSgKd1KaLBF5CBUc5hy4KDDWqri3Weuwkg2rNXELbnNsmajm7qnxZwk1FeYAf6PMtCT8skUAYyxuj
+";
+		assert_eq!(super::portfolio(&mnemonic, "", 0).unwrap(), exp);
+	}
+}
@@ -0,0 +1,131 @@
+use crate::bip32::{ExtKey, Key};
+use crate::eth::{Address, Scheme, ToAddress};
+use k256::SecretKey;
+use std::collections::HashSet;
+
+pub const DEFAULT_GAP: u32 = 20;
+
+pub struct Branch {
+	pub scheme: Scheme,
+	pub used: Vec<u32>,
+}
+
+impl Branch {
+	pub fn highest(&self) -> Option<u32> {
+		self.used.last().copied()
+	}
+}
+
+/// Walks every standard scheme until `gap` consecutive indices miss the set.
+pub fn scan(root: &ExtKey<SecretKey>, addresses: &HashSet<Address>, gap: u32) -> Vec<Branch> {
+	Scheme::ALL
+		.iter()
+		.map(|&scheme| {
+			let mut used = Vec::new();
+			let mut misses = 0;
+			let mut i = 0;
+			while misses < gap {
+				let hit = root
+					.derive_path(&scheme.path(i))
+					.is_some_and(|key| addresses.contains(&key.address()));
+				if hit {
+					used.push(i);
+					misses = 0;
+				} else {
+					misses += 1;
+				}
+				i += 1;
+			}
+			Branch { scheme, used }
+		})
+		.collect()
+}
+
+/// Derives the addresses `base/i` until `gap` consecutive ones are unused,
+/// for watch-only imports. Keymaker is offline, so `is_used` answers from
+/// whatever the caller knows. Includes the trailing unused addresses.
+pub fn scan_gap<K: Key>(
+	base: &ExtKey<K>,
+	gap: u32,
+	is_used: impl Fn(&Address) -> bool,
+) -> Vec<(u32, Address)> {
+	let mut addresses = Vec::new();
+	let mut misses = 0;
+	let mut i = 0;
+	while misses < gap {
+		match base.derive(i).map(|key| key.key().public().address()) {
+			Some(address) if is_used(&address) => {
+				addresses.push((i, address));
+				misses = 0;
+			}
+			Some(address) => {
+				addresses.push((i, address));
+				misses += 1;
+			}
+			None => misses += 1,
+		}
+		i += 1;
+	}
+	addresses
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn scan() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let address = |scheme: Scheme, i| root.derive_path(&scheme.path(i)).unwrap().address();
+
+		let mut addresses = HashSet::new();
+		addresses.insert(address(Scheme::LedgerLive, 0));
+		addresses.insert(address(Scheme::LedgerLive, 3));
+		addresses.insert(address(Scheme::Bip44, 1));
+		addresses.insert(address(Scheme::Bip44, 10));
+		addresses.insert(address(Scheme::Bip44, 35));
+		addresses.insert(address(Scheme::LedgerLegacy, 21));
+		addresses.insert(
+			"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+				.parse()
+				.unwrap(),
+		);
+
+		// index 0 of Ledger Live and BIP44 is the same path
+		let branches = super::scan(&root, &addresses, DEFAULT_GAP);
+		let used: Vec<_> = branches.iter().map(|b| b.used.clone()).collect();
+		assert_eq!(used, [vec![0, 3], vec![0, 1, 10], vec![]]);
+		assert_eq!(branches[1].highest(), Some(10));
+		assert_eq!(branches[2].highest(), None);
+
+		let branches = super::scan(&root, &addresses, 25);
+		let used: Vec<_> = branches.iter().map(|b| b.used.clone()).collect();
+		assert_eq!(used, [vec![0, 3], vec![0, 1, 10, 35], vec![21]]);
+	}
+
+	#[test]
+	fn scan_gap() {
+		let root = Mnemonic::from_entropy([0; 16]).root_key("").unwrap();
+		let path = Scheme::Bip44.path(0);
+		let base = root.derive_path(&path[..4]).unwrap().neuter();
+		let address = |i| root.derive_path(&Scheme::Bip44.path(i)).unwrap().address();
+
+		let data: [(&[u32], u32, u32); 5] = [
+			(&[], 20, 20),
+			(&[], 1, 1),
+			(&[0, 5], 20, 26),
+			(&[0, 5, 24], 20, 45),
+			(&[0, 5, 24], 5, 11),
+		];
+		for (used, gap, count) in data {
+			let used: HashSet<_> = used.iter().map(|&i| address(i)).collect();
+			let addresses = super::scan_gap(&base, gap, |a| used.contains(a));
+			assert_eq!(addresses.len() as u32, count, "{gap}");
+			for (n, (i, a)) in addresses.into_iter().enumerate() {
+				assert_eq!(i, n as u32);
+				assert!(a == address(i));
+			}
+		}
+	}
+}
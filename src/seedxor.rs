@@ -0,0 +1,142 @@
+use crate::bip39::Mnemonic;
+use std::fmt;
+
+/// Coldcard combines up to four parts.
+pub const MAX_PARTS: usize = 4;
+
+#[derive(PartialEq)]
+pub enum SeedXorError {
+	/// Fewer than two or more than `MAX_PARTS` parts.
+	PartCount(usize),
+	/// A mnemonic other than 24 words.
+	Length,
+}
+
+impl fmt::Debug for SeedXorError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::PartCount(n) => write!(f, "{n} parts, expected 2 to {MAX_PARTS}"),
+			Self::Length => f.write_str("Seed XOR parts are 24-word mnemonics"),
+		}
+	}
+}
+
+fn entropy(mnemonic: &Mnemonic) -> Result<[u8; 32], SeedXorError> {
+	mnemonic
+		.entropy()
+		.try_into()
+		.map_err(|_| SeedXorError::Length)
+}
+
+/// Splits a 24-word mnemonic into `parts` random mnemonics whose entropy
+/// XORs to the original, as Coldcard Seed XOR does. Every part is a valid
+/// mnemonic with its own checksum.
+pub fn split(mnemonic: &Mnemonic, parts: usize) -> Result<Vec<Mnemonic>, SeedXorError> {
+	if !(2..=MAX_PARTS).contains(&parts) {
+		return Err(SeedXorError::PartCount(parts));
+	}
+	let mut random = vec![[0; 32]; parts - 1];
+	for part in &mut random {
+		getrandom::getrandom(part).expect("Cannot read random bytes");
+	}
+	split_with(mnemonic, &random)
+}
+
+/// All parts but the last are `random`, the last one makes up the XOR.
+fn split_with(mnemonic: &Mnemonic, random: &[[u8; 32]]) -> Result<Vec<Mnemonic>, SeedXorError> {
+	let mut last = entropy(mnemonic)?;
+	let mut parts = vec![];
+	for part in random {
+		last.iter_mut().zip(part).for_each(|(l, p)| *l ^= p);
+		parts.push(Mnemonic::from_entropy_with(part, mnemonic.wordlist()));
+	}
+	parts.push(Mnemonic::from_entropy_with(last, mnemonic.wordlist()));
+	Ok(parts)
+}
+
+/// XOR of the parts' entropy in any order, with the checksum recomputed.
+pub fn combine(parts: &[Mnemonic]) -> Result<Mnemonic, SeedXorError> {
+	if !(2..=MAX_PARTS).contains(&parts.len()) {
+		return Err(SeedXorError::PartCount(parts.len()));
+	}
+	let mut combined = [0; 32];
+	for part in parts {
+		let entropy = entropy(part)?;
+		combined.iter_mut().zip(entropy).for_each(|(c, e)| *c ^= e);
+	}
+	Ok(Mnemonic::from_entropy_with(combined, parts[0].wordlist()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Example from the Coldcard Seed XOR documentation
+	const PARTS: [&str; 3] = [
+		"romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room",
+		"lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge",
+		"vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate",
+	];
+	const COMBINED: &str = "silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor";
+
+	fn parts(phrases: &[&str]) -> Vec<Mnemonic> {
+		phrases
+			.iter()
+			.map(|p| Mnemonic::from_phrase(p).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn combine() {
+		let orders = [[0, 1, 2], [2, 0, 1], [1, 2, 0]];
+		for order in orders {
+			let phrases: Vec<_> = order.iter().map(|&i| PARTS[i]).collect();
+			let mnemonic = super::combine(&parts(&phrases)).unwrap();
+			assert_eq!(mnemonic.to_string(), COMBINED);
+		}
+
+		let data = [
+			(&PARTS[..1], SeedXorError::PartCount(1)),
+			(&[PARTS[0]; 5][..], SeedXorError::PartCount(5)),
+		];
+		for (phrases, err) in data {
+			assert_eq!(super::combine(&parts(phrases)).err(), Some(err));
+		}
+		let short = [
+			PARTS[0],
+			"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+		];
+		assert_eq!(
+			super::combine(&parts(&short)).err(),
+			Some(SeedXorError::Length)
+		);
+	}
+
+	#[test]
+	fn split() {
+		let mnemonic = Mnemonic::from_phrase(COMBINED).unwrap();
+		let random: Vec<_> = parts(&PARTS[..2])
+			.iter()
+			.map(|m| entropy(m).unwrap())
+			.collect();
+		let split: Vec<_> = split_with(&mnemonic, &random)
+			.unwrap()
+			.iter()
+			.map(|m| m.to_string())
+			.collect();
+		assert_eq!(split, PARTS);
+
+		for n in 2..=MAX_PARTS {
+			let split = super::split(&mnemonic, n).unwrap();
+			assert_eq!(split.len(), n);
+			assert!(split.iter().all(|m| m.to_string() != COMBINED));
+			assert_eq!(super::combine(&split).unwrap().to_string(), COMBINED);
+		}
+		assert_eq!(
+			super::split(&mnemonic, 1).err(),
+			Some(SeedXorError::PartCount(1))
+		);
+		let short = Mnemonic::from_entropy([7; 16]);
+		assert_eq!(super::split(&short, 2).err(), Some(SeedXorError::Length));
+	}
+}
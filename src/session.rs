@@ -0,0 +1,253 @@
+use crate::bip39::Mnemonic;
+use crate::memory::Locked;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305;
+use std::fmt;
+
+const MAGIC: &[u8; 8] = b"KMSESSN\n";
+pub const VERSION: u8 = 1;
+/// scrypt cost of new files, stored in the header.
+pub const LOG_N: u8 = 15;
+const MAX_LOG_N: u8 = 20;
+const HEADER_LEN: usize = MAGIC.len() + 2 + 16 + 12;
+
+#[derive(PartialEq)]
+pub enum SessionError {
+	InvalidFormat,
+	UnsupportedVersion(u8),
+	/// Wrong password, or the file was modified.
+	Decryption,
+	FingerprintMismatch,
+}
+
+impl fmt::Debug for SessionError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidFormat => f.write_str("not a keymaker session file"),
+			Self::UnsupportedVersion(v) => write!(f, "unsupported session version {v}"),
+			Self::Decryption => f.write_str("wrong password or modified file"),
+			Self::FingerprintMismatch => f.write_str("mnemonic does not match the session"),
+		}
+	}
+}
+
+/// Progress of a multi-step setup. Holds public data only, the wallet is
+/// identified by its master fingerprint and the mnemonic is re-entered on
+/// resume.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Session {
+	pub fingerprint: String,
+	pub schemes: Vec<String>,
+	pub xpubs: Vec<String>,
+	/// Completed steps.
+	pub done: Vec<String>,
+}
+
+impl Session {
+	pub fn new(fingerprint: String) -> Self {
+		Self {
+			fingerprint,
+			..Self::default()
+		}
+	}
+
+	pub fn mark_done(&mut self, step: &str) {
+		if !self.done.iter().any(|s| s == step) {
+			self.done.push(step.to_string());
+		}
+	}
+
+	/// Checks a re-entered mnemonic against the stored fingerprint.
+	pub fn verify(&self, mnemonic: &Mnemonic, passphrase: &str) -> Result<(), SessionError> {
+		if mnemonic.seed(passphrase).fingerprint() == self.fingerprint {
+			Ok(())
+		} else {
+			Err(SessionError::FingerprintMismatch)
+		}
+	}
+
+	/// `magic | version | log_n | salt | nonce | ciphertext`, the header is
+	/// authenticated with the contents. The key is scrypt of the password.
+	pub fn encrypt(&self, password: &str) -> Vec<u8> {
+		let mut random = [0; 28];
+		getrandom::getrandom(&mut random).expect("Cannot read random bytes");
+		let (salt, nonce) = random.split_at(16);
+		self.encrypt_with(
+			password,
+			LOG_N,
+			salt.try_into().unwrap(),
+			nonce.try_into().unwrap(),
+		)
+	}
+
+	fn encrypt_with(&self, password: &str, log_n: u8, salt: [u8; 16], nonce: [u8; 12]) -> Vec<u8> {
+		let mut out = MAGIC.to_vec();
+		out.extend([VERSION, log_n]);
+		out.extend(salt);
+		out.extend(nonce);
+		let ciphertext = cipher(password, log_n, &salt)
+			.expect("Invalid scrypt parameters")
+			.encrypt(
+				&nonce.into(),
+				Payload {
+					msg: self.encode().as_bytes(),
+					aad: &out,
+				},
+			)
+			.unwrap();
+		out.extend(ciphertext);
+		out
+	}
+
+	pub fn decrypt(data: &[u8], password: &str) -> Result<Self, SessionError> {
+		if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+			return Err(SessionError::InvalidFormat);
+		}
+		let (header, ciphertext) = data.split_at(HEADER_LEN);
+		let version = header[MAGIC.len()];
+		if version != VERSION {
+			return Err(SessionError::UnsupportedVersion(version));
+		}
+		let log_n = header[MAGIC.len() + 1];
+		let salt = &header[MAGIC.len() + 2..MAGIC.len() + 18];
+		let nonce = &header[MAGIC.len() + 18..];
+		let plaintext = cipher(password, log_n, salt)
+			.ok_or(SessionError::InvalidFormat)?
+			.decrypt(
+				nonce.into(),
+				Payload {
+					msg: ciphertext,
+					aad: header,
+				},
+			)
+			.map_err(|_| SessionError::Decryption)?;
+		let text = String::from_utf8(plaintext).map_err(|_| SessionError::InvalidFormat)?;
+		Self::decode(&text)
+	}
+
+	fn encode(&self) -> String {
+		let mut out = format!("fingerprint {}\n", self.fingerprint);
+		for (key, values) in [
+			("scheme", &self.schemes),
+			("xpub", &self.xpubs),
+			("done", &self.done),
+		] {
+			for value in values {
+				out += &format!("{key} {value}\n");
+			}
+		}
+		out
+	}
+
+	fn decode(s: &str) -> Result<Self, SessionError> {
+		let mut session = Self::default();
+		for line in s.lines() {
+			let (key, value) = line.split_once(' ').ok_or(SessionError::InvalidFormat)?;
+			let value = value.to_string();
+			match key {
+				"fingerprint" => session.fingerprint = value,
+				"scheme" => session.schemes.push(value),
+				"xpub" => session.xpubs.push(value),
+				"done" => session.done.push(value),
+				_ => return Err(SessionError::InvalidFormat),
+			}
+		}
+		Ok(session)
+	}
+}
+
+/// Costs above `MAX_LOG_N` are refused, a modified header could ask for
+/// gigabytes of memory.
+pub(crate) fn cipher(password: &str, log_n: u8, salt: &[u8]) -> Option<ChaCha20Poly1305> {
+	if log_n > MAX_LOG_N {
+		return None;
+	}
+	let params = scrypt::Params::new(log_n, 8, 1, 32).ok()?;
+	let mut key = Locked::<32>::new();
+	scrypt::scrypt(password.as_bytes(), salt, &params, &mut key[..]).ok()?;
+	Some(ChaCha20Poly1305::new_from_slice(&key[..]).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn session() -> Session {
+		let mut session = Session::new("73c5da0a".to_string());
+		session.schemes.push("BIP44".to_string());
+		session.xpubs.push("[73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V".to_string());
+		session.mark_done("generate");
+		session.mark_done("verify");
+		session.mark_done("generate");
+		session
+	}
+
+	#[test]
+	fn round_trip() {
+		let session = session();
+		assert_eq!(session.done, ["generate", "verify"]);
+		let data = session.encrypt_with("pw", 4, [1; 16], [2; 12]);
+		assert_eq!(data[..10], *b"KMSESSN\n\x01\x04");
+		assert_eq!(Session::decrypt(&data, "pw"), Ok(session.clone()));
+		assert_eq!(
+			Session::decrypt(&data, "other"),
+			Err(SessionError::Decryption)
+		);
+		// fresh salt and nonce every time
+		assert_ne!(session.encrypt("pw")[10..], session.encrypt("pw")[10..]);
+	}
+
+	#[test]
+	fn tamper() {
+		let data = session().encrypt_with("pw", 4, [1; 16], [2; 12]);
+		for i in MAGIC.len() + 1..data.len() {
+			let mut data = data.clone();
+			data[i] ^= 1;
+			assert_eq!(
+				Session::decrypt(&data, "pw"),
+				Err(SessionError::Decryption),
+				"byte {i}"
+			);
+		}
+		let mut other = data.clone();
+		other[MAGIC.len()] = 2;
+		assert_eq!(
+			Session::decrypt(&other, "pw"),
+			Err(SessionError::UnsupportedVersion(2))
+		);
+		let mut other = data.clone();
+		other[MAGIC.len() + 1] = 40;
+		assert_eq!(
+			Session::decrypt(&other, "pw"),
+			Err(SessionError::InvalidFormat)
+		);
+		let mut other = data.clone();
+		other[0] ^= 1;
+		assert_eq!(
+			Session::decrypt(&other, "pw"),
+			Err(SessionError::InvalidFormat)
+		);
+		assert_eq!(
+			Session::decrypt(&data[..HEADER_LEN + 15], "pw"),
+			Err(SessionError::Decryption)
+		);
+		assert_eq!(
+			Session::decrypt(&data[..HEADER_LEN - 1], "pw"),
+			Err(SessionError::InvalidFormat)
+		);
+	}
+
+	#[test]
+	fn verify() {
+		let session = session();
+		assert_eq!(session.verify(&Mnemonic::from_entropy([0; 16]), ""), Ok(()));
+		assert_eq!(
+			session.verify(&Mnemonic::from_entropy([0; 16]), "TREZOR"),
+			Err(SessionError::FingerprintMismatch)
+		);
+		assert_eq!(
+			session.verify(&Mnemonic::from_entropy([1; 16]), ""),
+			Err(SessionError::FingerprintMismatch)
+		);
+	}
+}
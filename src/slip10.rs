@@ -0,0 +1,145 @@
+use crate::bip32::ChildNumber;
+use crate::hash::hmac_sha512;
+use curve25519_dalek::scalar::clamp_integer;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use sha2::{Digest, Sha512};
+
+/// SLIP-0010 ed25519 key. Only hardened derivation is defined for ed25519.
+#[derive(Clone)]
+pub struct Ed25519Key {
+	key: [u8; 32],
+	chain_code: [u8; 32],
+}
+
+impl Ed25519Key {
+	pub fn from_seed<T: AsRef<[u8]>>(seed: T) -> Self {
+		Self::from_hmac(b"ed25519 seed", seed.as_ref())
+	}
+
+	fn from_hmac(key: &[u8], data: &[u8]) -> Self {
+		let res = hmac_sha512(key, data);
+		let mut out = Self {
+			key: [0; 32],
+			chain_code: [0; 32],
+		};
+		out.key.copy_from_slice(&res[..32]);
+		out.chain_code.copy_from_slice(&res[32..]);
+		out
+	}
+
+	pub fn derive(&self, i: ChildNumber) -> Option<Self> {
+		if !i.is_hardened() {
+			return None;
+		}
+		let mut data = [0; 37];
+		data[1..33].copy_from_slice(&self.key);
+		data[33..].copy_from_slice(&u32::try_from(i).ok()?.to_be_bytes());
+		Some(Self::from_hmac(&self.chain_code, &data))
+	}
+
+	pub fn derive_path(&self, path: &[ChildNumber]) -> Option<Self> {
+		path.iter().try_fold(self.clone(), |key, &i| key.derive(i))
+	}
+
+	pub fn secret_key(&self) -> &[u8; 32] {
+		&self.key
+	}
+
+	pub fn chain_code(&self) -> &[u8; 32] {
+		&self.chain_code
+	}
+
+	pub fn public_key(&self) -> [u8; 32] {
+		let mut scalar = [0; 32];
+		scalar.copy_from_slice(&Sha512::digest(self.key)[..32]);
+		EdwardsPoint::mul_base_clamped(scalar).compress().to_bytes()
+	}
+
+	/// RFC 8032 Ed25519 signature of `message`.
+	pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+		let hash = Sha512::digest(self.key);
+		let a = Scalar::from_bytes_mod_order(clamp_integer(hash[..32].try_into().unwrap()));
+		let digest = |parts: &[&[u8]]| {
+			let mut hasher = Sha512::new();
+			parts.iter().for_each(|p| hasher.update(p));
+			Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+		};
+		let r = digest(&[&hash[32..], message]);
+		let big_r = EdwardsPoint::mul_base(&r).compress().to_bytes();
+		let k = digest(&[&big_r, &self.public_key(), message]);
+		let mut signature = [0; 64];
+		signature[..32].copy_from_slice(&big_r);
+		signature[32..].copy_from_slice((r + k * a).as_bytes());
+		signature
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::parse_path;
+
+	#[test]
+	fn derive() {
+		// SLIP-0010 test vector 1 for ed25519
+		let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+		let data = [
+			(
+				"m",
+				"90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+				"2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+				"a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed",
+			),
+			(
+				"m/0'",
+				"8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+				"68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+				"8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c",
+			),
+			(
+				"m/0'/1'/2'/2'/1000000000'",
+				"68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+				"8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+				"3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a",
+			),
+		];
+		let root = Ed25519Key::from_seed(seed);
+		for (path, chain_code, key, public_key) in data {
+			let key_ = root.derive_path(&parse_path(path).unwrap()).unwrap();
+			assert_eq!(hex::encode(key_.chain_code()), chain_code);
+			assert_eq!(hex::encode(key_.secret_key()), key);
+			assert_eq!(hex::encode(key_.public_key()), public_key);
+		}
+		assert!(root.derive(ChildNumber::Normal(0)).is_none());
+	}
+
+	#[test]
+	fn sign() {
+		// RFC 8032 test vectors 1 to 3
+		let data = [
+			(
+				"9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+				"",
+				"e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+			),
+			(
+				"4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+				"72",
+				"92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+			),
+			(
+				"c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+				"af82",
+				"6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+			),
+		];
+		for (key, message, signature) in data {
+			let key = Ed25519Key {
+				key: hex::decode(key).unwrap().try_into().unwrap(),
+				chain_code: [0; 32],
+			};
+			let message = hex::decode(message).unwrap();
+			assert_eq!(hex::encode(key.sign(&message)), signature);
+		}
+	}
+}
@@ -0,0 +1,78 @@
+/// SLIP-0044 coin types, the second level of BIP44 paths.
+pub const BTC: u32 = 0;
+pub const ETH: u32 = 60;
+pub const XLM: u32 = 148;
+pub const ADA: u32 = 1815;
+pub const AVAX: u32 = 9000;
+
+pub struct Registration {
+	pub symbol: &'static str,
+	pub name: &'static str,
+	pub coin_type: u32,
+	/// Purpose of the default address scheme.
+	pub purpose: u32,
+}
+
+pub const REGISTRY: [Registration; 5] = [
+	Registration {
+		symbol: "BTC",
+		name: "Bitcoin",
+		coin_type: BTC,
+		purpose: 84,
+	},
+	Registration {
+		symbol: "ETH",
+		name: "Ethereum",
+		coin_type: ETH,
+		purpose: 44,
+	},
+	Registration {
+		symbol: "XLM",
+		name: "Stellar",
+		coin_type: XLM,
+		purpose: 44,
+	},
+	Registration {
+		symbol: "ADA",
+		name: "Cardano",
+		coin_type: ADA,
+		purpose: 1852,
+	},
+	Registration {
+		symbol: "AVAX",
+		name: "Avalanche",
+		coin_type: AVAX,
+		purpose: 44,
+	},
+];
+
+/// The registered coin with `symbol`, in any case.
+pub fn find(symbol: &str) -> Option<&'static Registration> {
+	REGISTRY
+		.iter()
+		.find(|r| r.symbol.eq_ignore_ascii_case(symbol))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn find() {
+		let data = [
+			("BTC", Some((BTC, 84))),
+			("eth", Some((ETH, 44))),
+			("Ada", Some((ADA, 1852))),
+			("avax", Some((AVAX, 44))),
+			("DOGE", None),
+			("", None),
+		];
+		for (symbol, exp) in data {
+			assert_eq!(
+				super::find(symbol).map(|r| (r.coin_type, r.purpose)),
+				exp,
+				"{symbol}"
+			);
+		}
+	}
+}
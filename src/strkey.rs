@@ -0,0 +1,162 @@
+use crate::bech32::to_base32;
+use crate::util::{BitsN, IterExt};
+use std::fmt;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(PartialEq)]
+pub enum StrKeyError {
+	InvalidEncoding,
+	InvalidVersion,
+	ChecksumMismatch,
+}
+
+impl fmt::Debug for StrKeyError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidEncoding => f.write_str("invalid encoding"),
+			Self::InvalidVersion => f.write_str("invalid version"),
+			Self::ChecksumMismatch => f.write_str("checksum mismatch"),
+		}
+	}
+}
+
+/// Stellar strkey types, `G...` account ids and `S...` secret seeds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Version {
+	PublicKey,
+	SecretSeed,
+}
+
+impl Version {
+	fn byte(&self) -> u8 {
+		match self {
+			Self::PublicKey => 6 << 3,
+			Self::SecretSeed => 18 << 3,
+		}
+	}
+}
+
+/// CRC16-XModem.
+fn crc16(data: &[u8]) -> u16 {
+	data.iter().fold(0, |crc, &b| {
+		(0..8).fold(crc ^ (b as u16) << 8, |crc, _| {
+			if crc & 0x8000 != 0 {
+				crc << 1 ^ 0x1021
+			} else {
+				crc << 1
+			}
+		})
+	})
+}
+
+pub fn encode(version: Version, payload: &[u8; 32]) -> String {
+	let mut data = vec![version.byte()];
+	data.extend_from_slice(payload);
+	data.extend_from_slice(&crc16(&data).to_le_bytes());
+	to_base32(data)
+		.into_iter()
+		.map(|v| ALPHABET[v as usize] as char)
+		.collect()
+}
+
+pub fn decode(s: &str) -> Result<(Version, [u8; 32]), StrKeyError> {
+	if s.len() != 56 {
+		return Err(StrKeyError::InvalidEncoding);
+	}
+	let data: Vec<u8> = s
+		.bytes()
+		.map(|c| {
+			let v = ALPHABET.iter().position(|&a| a == c)?;
+			BitsN::<5>::try_from(v).ok()
+		})
+		.collect::<Option<Vec<_>>>()
+		.ok_or(StrKeyError::InvalidEncoding)?
+		.into_iter()
+		.bytes()
+		.map(|v| v as u8)
+		.collect();
+	let version = [Version::PublicKey, Version::SecretSeed]
+		.into_iter()
+		.find(|v| v.byte() == data[0])
+		.ok_or(StrKeyError::InvalidVersion)?;
+	if data[33..] != crc16(&data[..33]).to_le_bytes() {
+		return Err(StrKeyError::ChecksumMismatch);
+	}
+	let mut payload = [0; 32];
+	payload.copy_from_slice(&data[1..33]);
+	Ok((version, payload))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::parse_path;
+	use crate::bip39::Mnemonic;
+	use crate::slip10::Ed25519Key;
+
+	#[test]
+	fn crc16() {
+		assert_eq!(super::crc16(b"123456789"), 0x31c3);
+	}
+
+	#[test]
+	fn sep0005() {
+		// illness spike retreat truth genius clock brain pass fit cave bargain toe
+		let seed = Mnemonic::from_entropy(hex::decode("713a3ae074e60e56c6bd0557c4984af1").unwrap())
+			.seed("");
+		let root = Ed25519Key::from_seed(seed);
+		let data = [
+			(
+				"m/44'/148'/0'",
+				"GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+				"SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN",
+			),
+			(
+				"m/44'/148'/1'",
+				"GBAW5XGWORWVFE2XTJYDTLDHXTY2Q2MO73HYCGB3XMFMQ562Q2W2GJQX",
+				"SCEPFFWGAG5P2VX5DHIYK3XEMZYLTYWIPWYEKXFHSK25RVMIUNJ7CTIS",
+			),
+		];
+		for (path, account, secret) in data {
+			let key = root.derive_path(&parse_path(path).unwrap()).unwrap();
+			assert_eq!(encode(Version::PublicKey, &key.public_key()), account);
+			assert_eq!(encode(Version::SecretSeed, key.secret_key()), secret);
+			assert_eq!(
+				super::decode(account),
+				Ok((Version::PublicKey, key.public_key()))
+			);
+			assert_eq!(
+				super::decode(secret),
+				Ok((Version::SecretSeed, *key.secret_key()))
+			);
+		}
+	}
+
+	#[test]
+	fn decode() {
+		use StrKeyError::*;
+		let data = [
+			("", InvalidEncoding),
+			(
+				"GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ",
+				InvalidEncoding,
+			),
+			(
+				"GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ1",
+				InvalidEncoding,
+			),
+			(
+				"GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ7",
+				ChecksumMismatch,
+			),
+			(
+				"MDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6",
+				InvalidVersion,
+			),
+		];
+		for (s, err) in data {
+			assert_eq!(super::decode(s).err(), Some(err));
+		}
+	}
+}
@@ -0,0 +1,219 @@
+use crate::bip39::{checksum_valid, WORD_COUNTS, WORD_LIST};
+
+/// Largest edit distance considered a typo.
+pub const MAX_DISTANCE: usize = 2;
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of
+/// adjacent letters.
+fn distance(a: &str, b: &str) -> usize {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+	let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in d.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	d[0] = (0..=b.len()).collect();
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = (a[i - 1] != b[j - 1]) as usize;
+			d[i][j] = (d[i - 1][j] + 1)
+				.min(d[i][j - 1] + 1)
+				.min(d[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+			}
+		}
+	}
+	d[a.len()][b.len()]
+}
+
+/// Up to `max` words closest to `word`, nearest first.
+pub fn closest_words(word: &str, max: usize) -> Vec<&'static str> {
+	let mut words: Vec<_> = WORD_LIST
+		.iter()
+		.map(|w| (distance(word, w), *w))
+		.filter(|(d, _)| *d <= MAX_DISTANCE)
+		.collect();
+	words.sort_by_key(|(d, _)| *d);
+	words.into_iter().take(max).map(|(_, w)| w).collect()
+}
+
+/// Single word substitutions `(position, word)` that make a phrase of known
+/// words pass the checksum, closest to the replaced word first. These are
+/// guesses: roughly one in 256 substitutions passes by chance.
+pub fn checksum_fixes(phrase: &str, max: usize) -> Vec<(usize, &'static str)> {
+	let Ok(mut indices) = phrase
+		.split_whitespace()
+		.map(|w| WORD_LIST.binary_search(&w))
+		.collect::<Result<Vec<_>, _>>()
+	else {
+		return vec![];
+	};
+
+	let mut fixes = Vec::new();
+	for pos in 0..indices.len() {
+		let original = indices[pos];
+		for i in (0..WORD_LIST.len()).filter(|&i| i != original) {
+			indices[pos] = i;
+			if checksum_valid(&indices) {
+				fixes.push((
+					distance(WORD_LIST[original], WORD_LIST[i]),
+					pos,
+					WORD_LIST[i],
+				));
+			}
+		}
+		indices[pos] = original;
+	}
+	fixes.sort_by_key(|(d, _, _)| *d);
+	fixes
+		.into_iter()
+		.take(max)
+		.map(|(_, pos, word)| (pos, word))
+		.collect()
+}
+
+/// A common backup mistake found in a phrase of known words.
+#[derive(Debug, PartialEq)]
+pub enum Finding {
+	/// Words `position` and `position + 1` are the same and dropping one
+	/// gives a standard length, `valid` if also a valid checksum.
+	Repeated { position: usize, valid: bool },
+	/// Swapping words `position` and `position + 1` gives a valid checksum.
+	Swapped(usize),
+}
+
+/// Checks for a word written twice and for adjacent words swapped, for a
+/// phrase that fails validation. Findings are suggestions, a swap can pass
+/// the checksum by chance.
+pub fn transcription_errors(phrase: &str) -> Vec<Finding> {
+	let Ok(mut indices) = phrase
+		.split_whitespace()
+		.map(|w| WORD_LIST.binary_search(&w))
+		.collect::<Result<Vec<_>, _>>()
+	else {
+		return vec![];
+	};
+
+	let mut findings = Vec::new();
+	for position in 1..indices.len() {
+		// once per run of the same word, dropping any of them is the same
+		if indices[position - 1] != indices[position]
+			|| position > 1 && indices[position - 2] == indices[position]
+		{
+			continue;
+		}
+		let mut dropped = indices.clone();
+		dropped.remove(position);
+		if WORD_COUNTS.contains(&dropped.len()) {
+			findings.push(Finding::Repeated {
+				position: position - 1,
+				valid: checksum_valid(&dropped),
+			});
+		}
+	}
+	if !checksum_valid(&indices) {
+		for position in 1..indices.len() {
+			if indices[position - 1] == indices[position] {
+				continue;
+			}
+			indices.swap(position - 1, position);
+			if checksum_valid(&indices) {
+				findings.push(Finding::Swapped(position - 1));
+			}
+			indices.swap(position - 1, position);
+		}
+	}
+	findings
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn closest_words() {
+		let data: [(&str, &[&str]); 6] = [
+			("abandom", &["abandon"]),
+			("expalin", &["explain"]),
+			("zooo", &["zoo"]),
+			("lettre", &["letter"]),
+			("abandon", &["abandon"]),
+			("qqqqqqqq", &[]),
+		];
+		for (word, exp) in data {
+			assert_eq!(&super::closest_words(word, 3)[..exp.len()], exp, "{word}");
+		}
+		assert!(super::closest_words("qqqqqqqq", 3).is_empty());
+	}
+
+	#[test]
+	fn checksum_fixes() {
+		let phrase = "abandon ".repeat(23) + "zoo";
+		let fixes = super::checksum_fixes(&phrase, 100);
+		assert_eq!(fixes.len(), 100);
+		assert!(fixes.contains(&(23, "art")));
+		for (pos, word) in fixes {
+			let mut words: Vec<_> = phrase.split_whitespace().collect();
+			words[pos] = word;
+			assert!(Mnemonic::validate(&words.join(" ")));
+		}
+		assert!(super::checksum_fixes("abandon zooo", 10).is_empty());
+	}
+
+	#[test]
+	fn transcription_errors() {
+		use super::Finding::*;
+		let words: Vec<String> = Mnemonic::from_entropy([7; 16])
+			.to_string()
+			.split(' ')
+			.map(String::from)
+			.collect();
+		let phrase = |words: &[String]| words.join(" ");
+
+		let mut swapped = words.clone();
+		swapped.swap(4, 5);
+		assert!(!Mnemonic::validate(&phrase(&swapped)));
+		assert!(super::transcription_errors(&phrase(&swapped)).contains(&Swapped(4)));
+
+		let mut repeated = words.clone();
+		repeated.insert(7, words[7].clone());
+		assert!(
+			super::transcription_errors(&phrase(&repeated)).contains(&Repeated {
+				position: 7,
+				valid: true,
+			})
+		);
+
+		let mut both = repeated.clone();
+		both.swap(1, 2);
+		let findings = super::transcription_errors(&phrase(&both));
+		assert!(findings.contains(&Repeated {
+			position: 7,
+			valid: false,
+		}));
+
+		// a repeat at a standard length is not flagged
+		let abandon = "abandon ".repeat(11) + "about";
+		assert_eq!(super::transcription_errors(&abandon), []);
+		assert_eq!(super::transcription_errors("abandon abandon zooo"), []);
+		let data = [
+			(
+				"abandon ".repeat(11) + "about about",
+				vec![
+					Repeated {
+						position: 0,
+						valid: false,
+					},
+					Repeated {
+						position: 11,
+						valid: true,
+					},
+				],
+			),
+			("abandon ".repeat(10) + "about abandon", vec![Swapped(10)]),
+		];
+		for (phrase, exp) in data {
+			assert_eq!(super::transcription_errors(&phrase), exp, "{phrase}");
+		}
+	}
+}
@@ -0,0 +1,117 @@
+use crate::accounts::standard_accounts;
+use crate::avax::{self, Chain};
+use crate::bip32::ChildNumber::Hardened;
+use crate::bip32::{format_path, Bip32Error, Key};
+use crate::bip39::Mnemonic;
+use crate::btc::AddressType;
+use crate::cardano::{self, IcarusKey};
+use crate::eth::{Scheme, ToAddress};
+use crate::slip10::Ed25519Key;
+use crate::slip44;
+use crate::strkey::{self, Version};
+use std::fmt::Write;
+
+/// Public overview of a wallet: fingerprint, account xpubs and the first
+/// receive address of every supported coin. Contains no private keys.
+pub fn summary(mnemonic: &Mnemonic, passphrase: &str) -> Result<String, Bip32Error> {
+	let seed = mnemonic.seed(passphrase);
+	let root = seed.root_key()?;
+	let mut out = String::new();
+	writeln!(out, "Strength:           {} bits", mnemonic.strength()).unwrap();
+	writeln!(out, "Master fingerprint: {}", seed.fingerprint()).unwrap();
+
+	writeln!(out).unwrap();
+	writeln!(out, "Account xpubs:").unwrap();
+	for account in standard_accounts(&root, 0).ok_or(Bip32Error::InvalidKey)? {
+		writeln!(out, "{:<16} {account}", account.name).unwrap();
+	}
+
+	writeln!(out).unwrap();
+	writeln!(out, "First receive addresses:").unwrap();
+	let mut address = |name: &str, path: &str, address: String| {
+		writeln!(out, "{name:<16} {path:<20} {address}").unwrap();
+	};
+	for address_type in AddressType::ALL {
+		let path = [
+			Hardened(address_type.purpose()),
+			Hardened(slip44::BTC),
+			Hardened(0),
+			0.into(),
+			0.into(),
+		];
+		let key = root.derive_path(&path).ok_or(Bip32Error::InvalidKey)?;
+		let pubkey = key.neuter().key().serialize();
+		address(
+			&format!("BTC {address_type}"),
+			&format_path(&path),
+			address_type
+				.address(&pubkey)
+				.ok_or(Bip32Error::InvalidKey)?,
+		);
+	}
+
+	let path = Scheme::Bip44.path(0);
+	let key = root.derive_path(&path).ok_or(Bip32Error::InvalidKey)?;
+	address("ETH", &format_path(&path), key.address().to_string());
+
+	let path = avax::path(0);
+	let key = root.derive_path(&path).ok_or(Bip32Error::InvalidKey)?;
+	let pubkey = key.neuter().key().serialize();
+	address(
+		"AVAX X-chain",
+		&format_path(&path),
+		Chain::X.address(&pubkey),
+	);
+
+	let path = [Hardened(44), Hardened(slip44::XLM), Hardened(0)];
+	let key = Ed25519Key::from_seed(&seed)
+		.derive_path(&path)
+		.ok_or(Bip32Error::InvalidKey)?;
+	address(
+		"XLM",
+		&format_path(&path),
+		strkey::encode(Version::PublicKey, &key.public_key()),
+	);
+
+	let path = cardano::account_path(0);
+	let account = IcarusKey::from_mnemonic(mnemonic, passphrase)
+		.derive_path(&path)
+		.ok_or(Bip32Error::InvalidKey)?;
+	address(
+		"ADA",
+		&format!("{}/0/0", format_path(&path)),
+		account.base_address(0).ok_or(Bip32Error::InvalidKey)?,
+	);
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::bip39::Mnemonic;
+
+	#[test]
+	fn summary() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let exp = "Strength:           128 bits
+Master fingerprint: 73c5da0a
+
+Account xpubs:
+BTC P2PKH        [73c5da0a/44'/0'/0']xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj
+BTC P2SH-P2WPKH  [73c5da0a/49'/0'/0']xpub6C6nQwHaWbSrzs5tZ1q7m5R9cPK9eYpNMFesiXsYrgc1P8bvLLAet9JfHjYXKjToD8cBRswJXXbbFpXgwsswVPAZzKMa1jUp2kVkGVUaJa7
+BTC P2WPKH       [73c5da0a/84'/0'/0']xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V
+BTC P2TR         [73c5da0a/86'/0'/0']xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ
+ETH              [73c5da0a/44'/60'/0']xpub6DCoCpSuQZB2jawqnGMEPS63ePKWkwWPH4TU45Q7LPXWuNd8TMtVxRrgjtEshuqpK3mdhaWHPFsBngh5GFZaM6si3yZdUsT8ddYM3PwnATt
+AVAX X/P         [73c5da0a/44'/9000'/0']xpub6BqVigHfL2TNNs8HyeEHn4JFFyTw1vL8kC5ZhzBhrhDbQ3FhgakpivT97Cd7oVCJiAwiqWu313vKMZMwCghXgSVDnYR3FrYzTz24yY3nFHR
+
+First receive addresses:
+BTC P2WPKH       m/84'/0'/0'/0/0      bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu
+BTC P2PKH        m/44'/0'/0'/0/0      1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA
+BTC P2TR         m/86'/0'/0'/0/0      bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr
+ETH              m/44'/60'/0'/0/0     0x9858EfFD232B4033E47d90003D41EC34EcaEda94
+AVAX X-chain     m/44'/9000'/0'/0/0   X-avax1p9575chzhvcwvmvzaqh7yeld76r3af0ha56phl
+XLM              m/44'/148'/0'        GB3JDWCQJCWMJ3IILWIGDTQJJC5567PGVEVXSCVPEQOTDN64VJBDQBYX
+ADA              m/1852'/1815'/0'/0/0 addr1qy8ac7qqy0vtulyl7wntmsxc6wex80gvcyjy33qffrhm7sh927ysx5sftuw0dlft05dz3c7revpf7jx0xnlcjz3g69mq4afdhv
+";
+		assert_eq!(super::summary(&mnemonic, "").unwrap(), exp);
+	}
+}
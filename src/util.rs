@@ -1,3 +1,8 @@
+use crate::bip39::Mnemonic;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
 use std::iter::Peekable;
 
 pub trait Bits {
@@ -74,9 +79,7 @@ where
 	type Item = I::Item;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		if self.iter.peek().is_none() {
-			return None;
-		}
+		self.iter.peek()?;
 		self.toggle = !self.toggle;
 		if self.toggle {
 			Some(self.separator.clone())
@@ -86,6 +89,26 @@ where
 	}
 }
 
+pub struct Chunks<I, const N: usize> {
+	iter: I,
+}
+
+impl<I, const N: usize> Iterator for Chunks<I, N>
+where
+	I: Iterator,
+{
+	type Item = Vec<I::Item>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let chunk: Vec<_> = self.iter.by_ref().take(N).collect();
+		if chunk.is_empty() {
+			None
+		} else {
+			Some(chunk)
+		}
+	}
+}
+
 pub trait IterExt: Iterator + Sized {
 	fn bits<const N: usize>(self) -> BitIter<Self, N>
 	where
@@ -105,6 +128,11 @@ pub trait IterExt: Iterator + Sized {
 		self.bits()
 	}
 
+	/// Groups of `N` items, the last one may be shorter.
+	fn chunks<const N: usize>(self) -> Chunks<Self, N> {
+		Chunks { iter: self }
+	}
+
 	fn fold_mut<A, F>(self, mut init: A, mut f: F) -> A
 	where
 		F: FnMut(&mut A, Self::Item),
@@ -128,3 +156,321 @@ pub trait IterExt: Iterator + Sized {
 }
 
 impl<T> IterExt for T where T: Iterator {}
+
+/// Private material rendered for output. There is no `Display` and `Debug` is
+/// redacted, the value is only reachable through `reveal`.
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+	pub fn new(value: T) -> Self {
+		Self(value)
+	}
+
+	pub fn reveal(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T> fmt::Debug for Secret<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Secret(..)")
+	}
+}
+
+/// Writes a new file readable only by the owner on unix. Fails if `path`
+/// exists, secrets are never written over another file.
+pub fn write_private<T: AsRef<[u8]>>(path: &str, contents: T) -> io::Result<()> {
+	let mut options = OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	let mut file = options.open(path)?;
+	file.write_all(contents.as_ref())?;
+	file.sync_all()
+}
+
+/// The first `n` English BIP39 words of SHA256(data), at most 23. A short
+/// digest to compare a long string copied by hand.
+pub fn check_words(data: &[u8], n: usize) -> String {
+	Sha256::digest(data)
+		.iter()
+		.bits::<11>()
+		.take(n.min(23))
+		.map(|i| Mnemonic::word_at(i as u16).unwrap())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Left aligned columns two spaces apart, with a rule under the header.
+pub fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+	let mut widths: Vec<_> = header.iter().map(|h| h.chars().count()).collect();
+	for row in rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.chars().count());
+		}
+	}
+	let line = |cells: &mut dyn Iterator<Item = &str>| {
+		let line: Vec<_> = cells
+			.zip(&widths)
+			.map(|(cell, &width)| format!("{cell:<width$}"))
+			.collect();
+		line.join("  ").trim_end().to_string() + "\n"
+	};
+	let rule: Vec<_> = widths.iter().map(|&w| "-".repeat(w)).collect();
+	let mut out = line(&mut header.iter().copied());
+	out += &line(&mut rule.iter().map(String::as_str));
+	for row in rows {
+		out += &line(&mut row.iter().map(String::as_str));
+	}
+	out
+}
+
+/// RFC 4648 base64 with padding.
+pub fn base64(data: &[u8]) -> String {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut out = String::new();
+	for chunk in data.iter().chunks::<3>() {
+		let mut group = [0; 3];
+		group.iter_mut().zip(&chunk).for_each(|(g, &&b)| *g = b);
+		out.extend(
+			group
+				.iter()
+				.bits::<6>()
+				.take(chunk.len() + 1)
+				.map(|i| ALPHABET[i] as char),
+		);
+		out.extend(std::iter::repeat_n('=', 3 - chunk.len()));
+	}
+	out
+}
+
+/// `0..n` in a uniformly random order from OS randomness.
+pub fn shuffle(n: usize) -> Vec<usize> {
+	let mut order: Vec<_> = (0..n).collect();
+	for i in (1..n).rev() {
+		order.swap(i, random_below(i as u32 + 1) as usize);
+	}
+	order
+}
+
+/// A uniform random number below `n`, rejecting the biased top of the range.
+fn random_below(n: u32) -> u32 {
+	let limit = u32::MAX - u32::MAX % n;
+	loop {
+		let mut bytes = [0; 4];
+		getrandom::getrandom(&mut bytes).expect("Cannot read random bytes");
+		let value = u32::from_le_bytes(bytes);
+		if value < limit {
+			return value % n;
+		}
+	}
+}
+
+/// `s` as a quoted JSON string.
+pub fn json_string(s: &str) -> String {
+	format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+	Csv,
+	Json,
+}
+
+/// Rows as CSV with a header line, or as a JSON array with one object per
+/// row keyed by the header. JSON values are strings, except in the `numeric`
+/// columns which hold numbers.
+pub fn export(
+	format: ExportFormat,
+	header: &[&str],
+	numeric: &[&str],
+	rows: &[Vec<String>],
+) -> String {
+	match format {
+		ExportFormat::Csv => {
+			let csv = |cell: &str| {
+				if cell.contains([',', '"', '\n']) {
+					format!("\"{}\"", cell.replace('"', "\"\""))
+				} else {
+					cell.to_string()
+				}
+			};
+			let mut out = header.join(",") + "\n";
+			for row in rows {
+				let cells: Vec<_> = row.iter().map(|c| csv(c)).collect();
+				out += &(cells.join(",") + "\n");
+			}
+			out
+		}
+		ExportFormat::Json => {
+			let objects: Vec<_> = rows
+				.iter()
+				.map(|row| {
+					let fields: Vec<_> = header
+						.iter()
+						.zip(row)
+						.map(|(key, value)| {
+							let value = if numeric.contains(key) {
+								value.clone()
+							} else {
+								json_string(value)
+							};
+							format!("{}: {value}", json_string(key))
+						})
+						.collect();
+					format!("  {{{}}}", fields.join(", "))
+				})
+				.collect();
+			if objects.is_empty() {
+				"[]\n".to_string()
+			} else {
+				format!("[\n{}\n]\n", objects.join(",\n"))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn secret() {
+		let secret = Secret::new("xprv9s21ZrQH143K".to_string());
+		assert_eq!(format!("{secret:?}"), "Secret(..)");
+		assert_eq!(format!("{secret:#?}"), "Secret(..)");
+		assert_eq!(format!("{:?}", Some(&secret)), "Some(Secret(..))");
+		assert_eq!(secret.reveal(), "xprv9s21ZrQH143K");
+	}
+
+	#[test]
+	fn table() {
+		let rows = [
+			vec![
+				"(none)".to_string(),
+				"73c5da0a".to_string(),
+				"0x9858".to_string(),
+			],
+			vec!["#1".to_string(), "a1b2c3d4".to_string(), "".to_string()],
+			vec![
+				"#10 ábaco".to_string(),
+				"ffffffff".to_string(),
+				"0x1".to_string(),
+			],
+		];
+		let exp = "\
+Wallet     Fingerprint  Address
+---------  -----------  -------
+(none)     73c5da0a     0x9858
+#1         a1b2c3d4
+#10 ábaco  ffffffff     0x1
+";
+		assert_eq!(
+			super::table(&["Wallet", "Fingerprint", "Address"], &rows),
+			exp
+		);
+		assert_eq!(super::table(&["A", "B"], &[]), "A  B\n-  -\n");
+	}
+
+	#[test]
+	fn base64() {
+		let data = [
+			("", ""),
+			("f", "Zg=="),
+			("fo", "Zm8="),
+			("foo", "Zm9v"),
+			("foob", "Zm9vYg=="),
+			("fooba", "Zm9vYmE="),
+			("foobar", "Zm9vYmFy"),
+		];
+		for (input, exp) in data {
+			assert_eq!(super::base64(input.as_bytes()), exp);
+		}
+		assert_eq!(super::base64(&[0xff; 4]), "/////w==");
+	}
+
+	#[test]
+	fn shuffle() {
+		for n in [0, 1, 12, 24] {
+			let mut order = super::shuffle(n);
+			order.sort();
+			assert_eq!(order, (0..n).collect::<Vec<_>>());
+		}
+		let orders: Vec<_> = (0..8).map(|_| super::shuffle(24)).collect();
+		assert!(orders.iter().any(|o| *o != orders[0]));
+		assert!((0..1000).all(|_| random_below(3) < 3));
+	}
+
+	#[test]
+	fn chunks() {
+		let chunks: Vec<_> = (0..10).chunks::<3>().collect();
+		assert_eq!(
+			chunks,
+			[vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9]]
+		);
+		assert_eq!((0..6).chunks::<3>().count(), 2);
+		assert_eq!((0..0).chunks::<3>().count(), 0);
+	}
+
+	#[test]
+	fn check_words() {
+		let xpub = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+		let data = [
+			("", 3, "together mail awful"),
+			("abc", 2, "risk scheme"),
+			("abc", 3, "risk scheme quiz"),
+			(xpub, 3, "hen stomach congress"),
+			("abc", 23, "risk scheme quiz bulk brother vivid agree chimney tattoo uniform mass electric ability radio mixture seed kind toss aerobic yard busy abandon find"),
+			("abc", 30, "risk scheme quiz bulk brother vivid agree chimney tattoo uniform mass electric ability radio mixture seed kind toss aerobic yard busy abandon find"),
+			("abc", 0, ""),
+		];
+		for (input, n, exp) in data {
+			assert_eq!(super::check_words(input.as_bytes(), n), exp);
+		}
+	}
+
+	#[test]
+	fn write_private() {
+		let path = std::env::temp_dir().join(format!("keymaker-{}", std::process::id()));
+		let path = path.to_str().unwrap();
+		super::write_private(path, "secret").unwrap();
+		assert_eq!(std::fs::read_to_string(path).unwrap(), "secret");
+		assert!(super::write_private(path, "other").is_err());
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(path).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn export() {
+		let header = ["index", "path"];
+		let rows = [
+			vec!["0".to_string(), "m/44'/60'/0'/0/0".to_string()],
+			vec!["1".to_string(), "a,\"b\"\\".to_string()],
+		];
+		assert_eq!(
+			super::export(ExportFormat::Csv, &header, &["index"], &rows),
+			"index,path\n0,m/44'/60'/0'/0/0\n1,\"a,\"\"b\"\"\\\"\n"
+		);
+		assert_eq!(
+			super::export(ExportFormat::Json, &header, &["index"], &rows),
+			"[\n  {\"index\": 0, \"path\": \"m/44'/60'/0'/0/0\"},\n  {\"index\": 1, \"path\": \"a,\\\"b\\\"\\\\\"}\n]\n"
+		);
+		assert_eq!(
+			super::export(ExportFormat::Csv, &header, &["index"], &[]),
+			"index,path\n"
+		);
+		assert_eq!(
+			super::export(ExportFormat::Json, &header, &["index"], &[]),
+			"[]\n"
+		);
+		assert_eq!(
+			super::export(ExportFormat::Json, &header, &[], &rows[..1]),
+			"[\n  {\"index\": \"0\", \"path\": \"m/44'/60'/0'/0/0\"}\n]\n"
+		);
+	}
+}
@@ -0,0 +1,151 @@
+use crate::bip32::ChildNumber;
+use crate::bip32::{format_path, Bip32Error, Key};
+use crate::bip39::Mnemonic;
+use crate::portfolio;
+use crate::util::json_string;
+use std::fmt::Write;
+
+/// Version of the `vectors` JSON schema, bumped on any change to its fields.
+pub const VERSION: u32 = 1;
+
+/// Derivation vectors for `paths` as JSON, for comparison with other tools.
+/// The mnemonic, seed and private keys are only included with
+/// `include_secrets`.
+pub fn vectors(
+	mnemonic: &Mnemonic,
+	passphrase: &str,
+	paths: &[Vec<ChildNumber>],
+	include_secrets: bool,
+) -> Result<String, Bip32Error> {
+	let seed = mnemonic.seed(passphrase);
+	let root = seed.root_key()?;
+	let mut out = String::new();
+	writeln!(out, "{{").unwrap();
+	writeln!(out, "  \"version\": {VERSION},").unwrap();
+	if include_secrets {
+		writeln!(
+			out,
+			"  \"mnemonic\": {},",
+			json_string(&mnemonic.to_string())
+		)
+		.unwrap();
+		writeln!(out, "  \"seed\": \"{}\",", seed.to_hex()).unwrap();
+	}
+	writeln!(out, "  \"fingerprint\": \"{}\",", seed.fingerprint()).unwrap();
+	writeln!(out, "  \"root\": {{").unwrap();
+	if include_secrets {
+		writeln!(out, "    \"xprv\": \"{}\",", root.xprv().reveal()).unwrap();
+	}
+	writeln!(out, "    \"xpub\": \"{}\"", root.neuter()).unwrap();
+	writeln!(out, "  }},").unwrap();
+
+	let mut derivations = vec![];
+	for path in paths {
+		let key = root.derive_path(path).ok_or(Bip32Error::InvalidKey)?;
+		let pubkey = key.neuter().key().serialize();
+		let mut fields = vec![("path", json_string(&format_path(path)))];
+		if include_secrets {
+			fields.push(("xprv", json_string(key.xprv().reveal())));
+			fields.push((
+				"private_key",
+				json_string(&hex::encode(key.key().to_bytes())),
+			));
+		}
+		fields.push(("xpub", json_string(&key.neuter().to_string())));
+		fields.push(("public_key", json_string(&hex::encode(pubkey))));
+		fields.push((
+			"address",
+			portfolio::address(path, &pubkey).map_or("null".to_string(), |a| json_string(&a)),
+		));
+		let fields: Vec<_> = fields
+			.iter()
+			.map(|(name, value)| format!("      \"{name}\": {value}"))
+			.collect();
+		derivations.push(format!("    {{\n{}\n    }}", fields.join(",\n")));
+	}
+	if derivations.is_empty() {
+		writeln!(out, "  \"derivations\": []").unwrap();
+	} else {
+		writeln!(
+			out,
+			"  \"derivations\": [\n{}\n  ]",
+			derivations.join(",\n")
+		)
+		.unwrap();
+	}
+	writeln!(out, "}}").unwrap();
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip32::parse_path;
+
+	#[test]
+	fn vectors() {
+		let mnemonic = Mnemonic::from_entropy([0; 16]);
+		let paths: Vec<_> = ["m/44'/60'/0'/0/0", "m/84'/0'/0'/0/0", "m/0/1"]
+			.iter()
+			.map(|p| parse_path(p).unwrap())
+			.collect();
+		let exp = r#"{
+  "version": 1,
+  "fingerprint": "73c5da0a",
+  "root": {
+    "xpub": "xpub661MyMwAqRbcFkPHucMnrGNzDwb6teAX1RbKQmqtEF8kK3Z7LZ59qafCjB9eCRLiTVG3uxBxgKvRgbubRhqSKXnGGb1aoaqLrpMBDrVxga8"
+  },
+  "derivations": [
+    {
+      "path": "m/44'/60'/0'/0/0",
+      "xpub": "xpub6H6LG2We64bdwqNF7gNkUJ5EvDibiT2gbs77oonbawV86XE3eMxZf9czGQ9CPdSzsdsHLnLEjiJJEDnFMAyLrWATesaVbTYeggBXMHaFKLg",
+      "public_key": "0237b0bb7a8288d38ed49a524b5dc98cff3eb5ca824c9f9dc0dfdb3d9cd600f299",
+      "address": "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+    },
+    {
+      "path": "m/84'/0'/0'/0/0",
+      "xpub": "xpub6FrCS2gWHvogbAX8ipHuBmbPvckXLYs5SfEKq1Lp3tneESUXuNNUw67q6Q6r1xHhmoQtByXS7SXes78nuGckLXWEuRPWNfwBo8Cp5QQLPKy",
+      "public_key": "0330d54fd0dd420a6e5f8d3624f5f3482cae350f79d5f0753bf5beef9c2d91af3c",
+      "address": "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+    },
+    {
+      "path": "m/0/1",
+      "xpub": "xpub6APw4JtXQKMKLhDrWJMpH68anPn1bDQAieDp15CLZgxsT4mnPh9W8gv7Rmowp4bdYfMpM2ai4nANqtSDM9Wa1MuLJX1gFkX4LA259xWhqH2",
+      "public_key": "03446801102d378f09aa200debc1acdff0f6fcf1c6d9bc1e2c7e14076d5fbc740e",
+      "address": null
+    }
+  ]
+}
+"#;
+		assert_eq!(super::vectors(&mnemonic, "", &paths, false).unwrap(), exp);
+
+		let exp = r#"{
+  "version": 1,
+  "mnemonic": "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  "seed": "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4",
+  "fingerprint": "73c5da0a",
+  "root": {
+    "xprv": "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu",
+    "xpub": "xpub661MyMwAqRbcFkPHucMnrGNzDwb6teAX1RbKQmqtEF8kK3Z7LZ59qafCjB9eCRLiTVG3uxBxgKvRgbubRhqSKXnGGb1aoaqLrpMBDrVxga8"
+  },
+  "derivations": [
+    {
+      "path": "m/44'/60'/0'/0/0",
+      "xprv": "xprvA46yrWykFh3LjMHn1eqk7A8WNBt7JzJqEeBX1RNz2bx9Ditu6peK7MJWR8tfXUqPjWNuL7LwLvphdgkWShNpYXiJBuvi9agxJUWiHGHtoNk",
+      "private_key": "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727",
+      "xpub": "xpub6H6LG2We64bdwqNF7gNkUJ5EvDibiT2gbs77oonbawV86XE3eMxZf9czGQ9CPdSzsdsHLnLEjiJJEDnFMAyLrWATesaVbTYeggBXMHaFKLg",
+      "public_key": "0237b0bb7a8288d38ed49a524b5dc98cff3eb5ca824c9f9dc0dfdb3d9cd600f299",
+      "address": "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+    }
+  ]
+}
+"#;
+		assert_eq!(
+			super::vectors(&mnemonic, "", &paths[..1], true).unwrap(),
+			exp
+		);
+
+		let json = super::vectors(&mnemonic, "", &[], false).unwrap();
+		assert!(json.ends_with("  \"derivations\": []\n}\n"));
+	}
+}
@@ -0,0 +1,326 @@
+use crate::bip32::{format_path, parse_path, ChildNumber, ExtKey, Version};
+use crate::electrum::{YPUB, ZPUB};
+use crate::eth::{Address, ToAddress};
+use k256::{PublicKey, SecretKey};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(PartialEq)]
+pub enum ExpectationError {
+	InvalidFormat,
+	InvalidPath,
+	InvalidArtifact,
+}
+
+impl fmt::Debug for ExpectationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidFormat => f.write_str("expected '<path> <xpub|ypub|zpub|address>'"),
+			Self::InvalidPath => f.write_str("invalid path"),
+			Self::InvalidArtifact => f.write_str("invalid xpub, ypub, zpub or address"),
+		}
+	}
+}
+
+/// A SLIP-132 version and the single-sig script type it stands for.
+pub struct Slip132 {
+	pub prefix: &'static str,
+	pub version: Version,
+	pub script: &'static str,
+	pub purpose: u32,
+}
+
+pub const SLIP132: [Slip132; 2] = [
+	Slip132 {
+		prefix: "ypub",
+		version: YPUB,
+		script: "P2SH-P2WPKH",
+		purpose: 49,
+	},
+	Slip132 {
+		prefix: "zpub",
+		version: ZPUB,
+		script: "P2WPKH",
+		purpose: 84,
+	},
+];
+
+/// Extended keys carry their SLIP-132 version, `None` for xpub, which any
+/// script type uses.
+pub enum Artifact {
+	ExtKey(ExtKey<PublicKey>, Option<&'static Slip132>),
+	Address(Address),
+}
+
+pub struct Expectation {
+	pub path: Vec<ChildNumber>,
+	pub artifact: Artifact,
+}
+
+impl FromStr for Expectation {
+	type Err = ExpectationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split_whitespace();
+		let (Some(path), Some(artifact), None) = (parts.next(), parts.next(), parts.next()) else {
+			return Err(ExpectationError::InvalidFormat);
+		};
+		let path = parse_path(path).map_err(|_| ExpectationError::InvalidPath)?;
+		let artifact = if artifact.starts_with("0x") {
+			Artifact::Address(
+				artifact
+					.parse()
+					.map_err(|_| ExpectationError::InvalidArtifact)?,
+			)
+		} else if let Some(slip) = SLIP132.iter().find(|v| artifact.starts_with(v.prefix)) {
+			let key = ExtKey::parse_with_version(artifact, slip.version)
+				.map_err(|_| ExpectationError::InvalidArtifact)?;
+			Artifact::ExtKey(key, Some(slip))
+		} else {
+			let key = artifact
+				.parse()
+				.map_err(|_| ExpectationError::InvalidArtifact)?;
+			Artifact::ExtKey(key, None)
+		};
+		Ok(Self { path, artifact })
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Component {
+	Depth,
+	ChildNumber,
+	ParentFingerprint,
+	ChainCode,
+	Key,
+	/// A ypub or zpub under another BIP44 purpose than its script type's.
+	ScriptType,
+	Address,
+}
+
+impl fmt::Display for Component {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Depth => "depth",
+			Self::ChildNumber => "child number",
+			Self::ParentFingerprint => "parent fingerprint",
+			Self::ChainCode => "chain code",
+			Self::Key => "key",
+			Self::ScriptType => "script type",
+			Self::Address => "address",
+		})
+	}
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+	Match,
+	Mismatch(Vec<Component>),
+	Underivable,
+}
+
+pub struct Check {
+	pub path: Vec<ChildNumber>,
+	pub kind: &'static str,
+	pub outcome: Outcome,
+}
+
+pub struct Report(pub Vec<Check>);
+
+impl Report {
+	pub fn is_match(&self) -> bool {
+		self.0.iter().all(|c| c.outcome == Outcome::Match)
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for check in &self.0 {
+			write!(f, "{} {}: ", format_path(&check.path), check.kind)?;
+			match &check.outcome {
+				Outcome::Match => writeln!(f, "match")?,
+				Outcome::Underivable => writeln!(f, "cannot derive")?,
+				Outcome::Mismatch(components) => {
+					f.write_str("MISMATCH (")?;
+					for (i, c) in components.iter().enumerate() {
+						if i > 0 {
+							f.write_str(", ")?;
+						}
+						write!(f, "{c}")?;
+					}
+					writeln!(f, ")")?;
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+fn compare(derived: &ExtKey<PublicKey>, expected: &ExtKey<PublicKey>) -> Vec<Component> {
+	let mut out = Vec::new();
+	if derived.depth() != expected.depth() {
+		out.push(Component::Depth);
+	}
+	if derived.number() != expected.number() {
+		out.push(Component::ChildNumber);
+	}
+	if derived.parent_fingerprint() != expected.parent_fingerprint() {
+		out.push(Component::ParentFingerprint);
+	}
+	if derived.chain_code() != expected.chain_code() {
+		out.push(Component::ChainCode);
+	}
+	if derived.key() != expected.key() {
+		out.push(Component::Key);
+	}
+	out
+}
+
+pub fn verify(root: &ExtKey<SecretKey>, expected: &[Expectation]) -> Report {
+	let checks = expected.iter().map(|exp| {
+		let derived = root.derive_path(&exp.path);
+		let (kind, outcome) = match &exp.artifact {
+			Artifact::ExtKey(key, slip) => (
+				slip.map_or("xpub", |s| s.prefix),
+				match derived {
+					None => Outcome::Underivable,
+					Some(derived) => {
+						let mut components = compare(&derived.neuter(), key);
+						if let (Some(slip), Some(ChildNumber::Hardened(purpose))) =
+							(slip, exp.path.first())
+						{
+							if [44, 49, 84, 86].contains(purpose) && *purpose != slip.purpose {
+								components.push(Component::ScriptType);
+							}
+						}
+						match components {
+							components if components.is_empty() => Outcome::Match,
+							components => Outcome::Mismatch(components),
+						}
+					}
+				},
+			),
+			Artifact::Address(address) => (
+				"address",
+				match derived {
+					None => Outcome::Underivable,
+					Some(derived) if derived.address() == *address => Outcome::Match,
+					Some(_) => Outcome::Mismatch(vec![Component::Address]),
+				},
+			),
+		};
+		Check {
+			path: exp.path.clone(),
+			kind,
+			outcome,
+		}
+	});
+	Report(checks.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bip39::Mnemonic;
+	use Component::*;
+
+	fn root() -> ExtKey<SecretKey> {
+		Mnemonic::from_entropy([0; 16]).root_key("").unwrap()
+	}
+
+	fn xpub(path: &str) -> String {
+		let path = parse_path(path).unwrap();
+		root().derive_path(&path).unwrap().neuter().to_string()
+	}
+
+	#[test]
+	fn verify() {
+		let lines = [
+			"m/44'/60'/0'/0/0 0x9858EfFD232B4033E47d90003D41EC34EcaEda94".to_string(),
+			"m/44'/60'/0'/0/1 0x9858EfFD232B4033E47d90003D41EC34EcaEda94".to_string(),
+			format!("m/44'/60'/0' {}", xpub("m/44'/60'/0'")),
+			format!("m/44'/60'/0' {}", xpub("m/44'/60'/1'")),
+			format!("m/44'/60'/0' {}", xpub("m/44'/60'")),
+		];
+		let expected: Vec<Expectation> = lines.iter().map(|l| l.parse().unwrap()).collect();
+		let report = super::verify(&root(), &expected);
+		let outcomes: Vec<_> = report.0.iter().map(|c| &c.outcome).collect();
+		assert_eq!(
+			outcomes,
+			[
+				&Outcome::Match,
+				&Outcome::Mismatch(vec![Address]),
+				&Outcome::Match,
+				&Outcome::Mismatch(vec![ChildNumber, ChainCode, Key]),
+				&Outcome::Mismatch(vec![Depth, ChildNumber, ParentFingerprint, ChainCode, Key]),
+			]
+		);
+		assert!(!report.is_match());
+		assert_eq!(
+			report.to_string().lines().take(4).collect::<Vec<_>>(),
+			[
+				"m/44'/60'/0'/0/0 address: match",
+				"m/44'/60'/0'/0/1 address: MISMATCH (address)",
+				"m/44'/60'/0' xpub: match",
+				"m/44'/60'/0' xpub: MISMATCH (child number, chain code, key)",
+			]
+		);
+
+		let report = super::verify(&root(), &expected[..1]);
+		assert!(report.is_match());
+	}
+
+	#[test]
+	fn slip132() {
+		let ypub = |path: &str| {
+			let path = parse_path(path).unwrap();
+			let key = root().derive_path(&path).unwrap().neuter();
+			key.with_version(YPUB).to_string()
+		};
+		let lines = [
+			// BIP84 test vector
+			"m/84'/0'/0' zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs".to_string(),
+			format!("m/49'/0'/0' {}", ypub("m/49'/0'/0'")),
+			format!("m/44'/0'/0' {}", ypub("m/44'/0'/0'")),
+			format!("m/0' {}", ypub("m/0'")),
+		];
+		let expected: Vec<Expectation> = lines.iter().map(|l| l.parse().unwrap()).collect();
+		let report = super::verify(&root(), &expected);
+		let outcomes: Vec<_> = report.0.iter().map(|c| (c.kind, &c.outcome)).collect();
+		assert_eq!(
+			outcomes,
+			[
+				("zpub", &Outcome::Match),
+				("ypub", &Outcome::Match),
+				("ypub", &Outcome::Mismatch(vec![ScriptType])),
+				("ypub", &Outcome::Match),
+			]
+		);
+		assert_eq!(
+			"m/0 zpub123".parse::<Expectation>().err(),
+			Some(ExpectationError::InvalidArtifact)
+		);
+	}
+
+	#[test]
+	fn parse() {
+		use ExpectationError::*;
+		let data = [
+			("", InvalidFormat),
+			("m/0", InvalidFormat),
+			(
+				"m/0 0x9858EfFD232B4033E47d90003D41EC34EcaEda94 x",
+				InvalidFormat,
+			),
+			("0 0x9858EfFD232B4033E47d90003D41EC34EcaEda94", InvalidPath),
+			(
+				"m/0 0x9858EfFD232B4033E47d90003D41EC34EcaEda95",
+				InvalidArtifact,
+			),
+			("m/0 xpub123", InvalidArtifact),
+		];
+		for (s, err) in data {
+			assert_eq!(s.parse::<Expectation>().err(), Some(err));
+		}
+	}
+}
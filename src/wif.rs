@@ -0,0 +1,197 @@
+use crate::base58;
+use crate::btc;
+use crate::eth::ToAddress;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::SecretKey;
+use std::fmt;
+use std::fmt::Write;
+
+/// Version byte of mainnet WIF keys.
+const MAINNET: u8 = 0x80;
+
+#[derive(PartialEq)]
+pub enum KeyError {
+	InvalidHex,
+	InvalidLength,
+	/// Not base58, or the base58check checksum does not match.
+	InvalidWif,
+	/// WIF version byte other than mainnet.
+	Version(u8),
+	Zero,
+	/// Not below the secp256k1 group order.
+	Overflow,
+}
+
+impl fmt::Debug for KeyError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidHex => f.write_str("invalid hex"),
+			Self::InvalidLength => f.write_str("expected 32 bytes"),
+			Self::InvalidWif => f.write_str("invalid WIF checksum or character"),
+			Self::Version(0xef) => f.write_str("testnet WIF, expected mainnet"),
+			Self::Version(v) => write!(f, "unknown WIF version {v:#04x}"),
+			Self::Zero => f.write_str("key is zero"),
+			Self::Overflow => f.write_str("key is not below the secp256k1 order"),
+		}
+	}
+}
+
+/// A single private key with the public key encoding its addresses use.
+pub struct ImportedKey {
+	pub key: SecretKey,
+	/// Uncompressed keys only come from WIF without the compression flag.
+	pub compressed: bool,
+}
+
+fn secret_key(bytes: &[u8]) -> Result<SecretKey, KeyError> {
+	if bytes.len() != 32 {
+		return Err(KeyError::InvalidLength);
+	}
+	if bytes.iter().all(|&b| b == 0) {
+		return Err(KeyError::Zero);
+	}
+	SecretKey::from_slice(bytes).map_err(|_| KeyError::Overflow)
+}
+
+impl ImportedKey {
+	/// Parses 64 hex digits with or without `0x`, or a mainnet WIF key.
+	pub fn parse(s: &str) -> Result<Self, KeyError> {
+		let s = s.trim();
+		let hex = s.strip_prefix("0x").unwrap_or(s);
+		if hex.len() == 64 || hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+			let bytes = hex::decode(hex).map_err(|_| KeyError::InvalidHex)?;
+			return Ok(Self {
+				key: secret_key(&bytes)?,
+				compressed: true,
+			});
+		}
+
+		let data = base58::decode_check(s).ok_or(KeyError::InvalidWif)?;
+		let (&version, payload) = data.split_first().ok_or(KeyError::InvalidLength)?;
+		if version != MAINNET {
+			return Err(KeyError::Version(version));
+		}
+		let (bytes, compressed) = match payload {
+			[key @ .., 0x01] if key.len() == 32 => (key, true),
+			key => (key, false),
+		};
+		Ok(Self {
+			key: secret_key(bytes)?,
+			compressed,
+		})
+	}
+
+	/// Public key encodings and the addresses of the key, no private data.
+	pub fn describe(&self) -> String {
+		let public = self.key.public_key();
+		let compressed: [u8; 33] = public.to_encoded_point(true).as_bytes().try_into().unwrap();
+		let uncompressed = public.to_encoded_point(false);
+		let mut out = String::new();
+		let mut line = |name: &str, value: &str| writeln!(out, "{name:<18} {value}").unwrap();
+		line("Compressed key:", &hex::encode(compressed));
+		line("Uncompressed key:", &hex::encode(uncompressed.as_bytes()));
+		line("ETH:", &self.key.address().to_string());
+		if !self.compressed {
+			line("BTC P2PKH:", &btc::p2pkh_address(uncompressed.as_bytes()));
+			line("", "(uncompressed WIF, segwit addresses do not apply)");
+			return out;
+		}
+		line("BTC P2PKH:", &btc::p2pkh_address(&compressed));
+		line("BTC P2SH-P2WPKH:", &btc::p2sh_p2wpkh_address(&compressed));
+		line("BTC P2WPKH:", &btc::p2wpkh_address(&compressed));
+		if let Some(address) = btc::p2tr_address(&compressed) {
+			line("BTC P2TR:", &address);
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse() {
+		let hex = "f8f8a2f43c8376ccb0871305060d7b27b0554d2cc72bccf41b2705608452f315";
+		let data = [
+			(hex.to_string(), true),
+			(format!("0x{hex}"), true),
+			(
+				"L5ZgJVf5X4nACX9NeUNchjbUpwAWbefPZ2xHkC15kV6HuEh9GDry".to_string(),
+				true,
+			),
+			(
+				"5KhwDaEABSKqkqyGnCBnhVgXmqyXiqQejbt3SC6ujUfMbbgAzid".to_string(),
+				false,
+			),
+		];
+		for (s, compressed) in data {
+			let key = ImportedKey::parse(&s).unwrap();
+			assert_eq!(hex::encode(key.key.to_bytes()), hex, "{s}");
+			assert_eq!(key.compressed, compressed);
+			assert_eq!(
+				key.key.address().to_string(),
+				"0x001d3F1ef827552Ae1114027BD3ECF1f086bA0F9"
+			);
+		}
+	}
+
+	#[test]
+	fn errors() {
+		let data = [
+			(
+				"f8f8a2f43c8376ccb0871305060d7b27b0554d2cc72bccf41b2705608452f31x",
+				KeyError::InvalidHex,
+			),
+			(
+				"0000000000000000000000000000000000000000000000000000000000000000",
+				KeyError::Zero,
+			),
+			(
+				"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+				KeyError::Overflow,
+			),
+			(
+				"L5ZgJVf5X4nACX9NeUNchjbUpwAWbefPZ2xHkC15kV6HuEh9GDrz",
+				KeyError::InvalidWif,
+			),
+			(
+				"cVvfmQevx8URMxce2tBk546YTATvG6m5d56krcTbFbkJ9yrNidBK",
+				KeyError::Version(0xef),
+			),
+			(
+				"L5oLkpV3aqBjhki6LmvChTCV6odsp4SXM6FfU2Gppt5kFqRzExJJ",
+				KeyError::Overflow,
+			),
+			(
+				"KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73Nd2Mcv1",
+				KeyError::Zero,
+			),
+			("f8f8a2f4", KeyError::InvalidLength),
+			("0xf8f8a2f", KeyError::InvalidHex),
+		];
+		for (s, err) in data {
+			assert_eq!(ImportedKey::parse(s).err(), Some(err), "{s}");
+		}
+		let short = base58::encode_check([MAINNET, 1, 2, 3]);
+		assert_eq!(
+			ImportedKey::parse(&short).err(),
+			Some(KeyError::InvalidLength)
+		);
+	}
+
+	#[test]
+	fn describe() {
+		let key =
+			ImportedKey::parse("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617").unwrap();
+		let out = key.describe();
+		assert!(out.contains("BTC P2PKH:         1LoVGDgRs9hTfTNJNuXKSpywcbdvwRXpmK\n"));
+		assert_eq!(out.lines().count(), 7);
+
+		let key =
+			ImportedKey::parse("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ").unwrap();
+		let out = key.describe();
+		assert!(out.contains("BTC P2PKH:         1GAehh7TsJAHuUAeKZcXf5CnwuGuGgyX2S\n"));
+		assert_eq!(out.lines().count(), 5);
+	}
+}